@@ -0,0 +1,57 @@
+//! Times puzzle generation across board sizes and difficulties, run with `cargo bench`.
+//! Alongside the timings are the mean islands, loops and retries, to see what a change does to the puzzles.
+//!
+//! Generation runs on the page's main thread, so anything creeping towards a frame budget
+//! here will be felt as a stall when starting a game.
+
+use hashi::hashi::{Difficulty, GenAlgo, GenStats, GenerationOptions, HashiGrid};
+use std::time::{Duration, Instant};
+
+const SIZES: [(u8, u8); 5] = [(5, 10), (8, 16), (15, 15), (30, 30), (3, 30)];
+const SEEDS: u64 = 20;
+
+fn main() {
+    println!(
+        "{:<14} {:<8} {:<10} {:>10} {:>10} {:>8} {:>6} {:>8}",
+        "algorithm", "size", "difficulty", "mean", "worst", "islands", "loops", "retries"
+    );
+    for algorithm in GenAlgo::ALL {
+        for (width, height) in SIZES {
+            for difficulty in Difficulty::ALL {
+                let options = GenerationOptions {
+                    algorithm,
+                    ..difficulty.options()
+                };
+                let mut total = Duration::ZERO;
+                let mut worst = Duration::ZERO;
+                let mut sums = GenStats::default();
+                for seed in 0..SEEDS {
+                    let start = Instant::now();
+                    let generated = HashiGrid::generate_with_stats(width, height, seed, &options);
+                    let elapsed = start.elapsed();
+                    let Ok((_, stats)) = generated else {
+                        panic!("{}x{} seed {} failed", width, height, seed);
+                    };
+
+                    total += elapsed;
+                    worst = worst.max(elapsed);
+                    sums.islands_placed += stats.islands_placed;
+                    sums.loops_added += stats.loops_added;
+                    sums.retries += stats.retries;
+                }
+                let mean = |sum: usize| sum as f64 / SEEDS as f64;
+                println!(
+                    "{:<14} {:<8} {:<10} {:>10.2?} {:>10.2?} {:>8.1} {:>6.1} {:>8.1}",
+                    algorithm.to_string(),
+                    format!("{}x{}", width, height),
+                    difficulty.to_string(),
+                    total / SEEDS as u32,
+                    worst,
+                    mean(sums.islands_placed),
+                    mean(sums.loops_added),
+                    mean(sums.retries as usize)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Generates a puzzle natively and prints it, for checking what the generator makes without a browser.
+//!
+//! `cargo run --bin hashi-cli -- --width 8 --height 16 --seed 42 --difficulty hard --solve`
+
+use hashi::hashi::{Difficulty, HashiGrid};
+use std::io::{self, ErrorKind, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: hashi-cli [--width N] [--height N] [--seed N] [--difficulty beginner|easy|medium|hard] [--solve] [--help]";
+
+/// What to generate and print, from the command line
+struct Args {
+    width: u8,
+    height: u8,
+    seed: Option<u64>,
+    difficulty: Difficulty,
+    solve: bool,
+    /// Print the usage instead of a puzzle
+    help: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        // the size a new player's first puzzle has
+        Args {
+            width: 6,
+            height: 12,
+            seed: None,
+            difficulty: Difficulty::default(),
+            solve: false,
+            help: false,
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--solve" => {
+                parsed.solve = true;
+                continue;
+            }
+            "--help" | "-h" => {
+                parsed.help = true;
+                continue;
+            }
+            _ => {}
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", flag))?;
+        let invalid = || format!("{} isn't a valid value for {}", value, flag);
+        match flag.as_str() {
+            "--width" => parsed.width = value.parse().map_err(|_| invalid())?,
+            "--height" => parsed.height = value.parse().map_err(|_| invalid())?,
+            "--seed" => parsed.seed = Some(value.parse().map_err(|_| invalid())?),
+            "--difficulty" => {
+                parsed.difficulty = Difficulty::ALL
+                    .into_iter()
+                    .find(|difficulty| difficulty.to_string().eq_ignore_ascii_case(&value))
+                    .ok_or_else(invalid)?
+            }
+            _ => return Err(format!("unknown flag {}", flag)),
+        }
+    }
+    Ok(parsed)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&args, &mut io::stdout().lock()) {
+        Ok(code) => code,
+        // a reader that stops early, like `head`, closes the pipe, which is no failure here
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("couldn't write the puzzle: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Generate the puzzle the arguments ask for and write it out, or the usage if they ask for that
+fn run(args: &Args, out: &mut impl Write) -> io::Result<ExitCode> {
+    if args.help {
+        writeln!(out, "{}", USAGE)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let seed = args.seed.unwrap_or_else(rand::random::<u64>);
+    let options = args.difficulty.options();
+    let (solved, stats) =
+        match HashiGrid::generate_with_stats(args.width, args.height, seed, &options) {
+            Ok(generated) => generated,
+            Err(e) => {
+                eprintln!("couldn't generate a puzzle: {}", e);
+                return Ok(ExitCode::FAILURE);
+            }
+        };
+    let puzzle = solved.wipe_bridges();
+
+    writeln!(
+        out,
+        "{}x{} seed {} {}: {} islands, {} retries, {} bridges given, rated {}",
+        args.width,
+        args.height,
+        seed,
+        args.difficulty,
+        stats.islands_placed,
+        stats.retries,
+        stats.givens,
+        puzzle.difficulty_rating()
+    )?;
+    writeln!(out, "{}", puzzle)?;
+
+    if args.solve {
+        let Some(bridges) = puzzle.solve() else {
+            eprintln!("the solver found no solution");
+            return Ok(ExitCode::FAILURE);
+        };
+        let mut solution = puzzle.clone();
+        solution.bridges = bridges;
+        writeln!(out, "{}", solution)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        // Test: Flags set the size, seed, difficulty and solving, bad or unknown ones are refused
+        let args = parse(&[
+            "--width",
+            "8",
+            "--solve",
+            "--seed",
+            "42",
+            "--difficulty",
+            "HARD",
+        ])
+        .unwrap();
+        assert_eq!((args.width, args.height), (8, 12));
+        assert_eq!(args.seed, Some(42));
+        assert_eq!(args.difficulty, Difficulty::Hard);
+        assert!(args.solve);
+
+        assert!(parse(&[]).unwrap().seed.is_none());
+        assert!(parse(&["--width", "300"]).is_err());
+        assert!(parse(&["--height"]).is_err());
+        assert!(parse(&["--difficulty", "brutal"]).is_err());
+        assert!(parse(&["--colour"]).is_err());
+    }
+
+    #[test]
+    fn test_help_prints_usage() {
+        // Test: --help and -h take no value and print the usage in place of a puzzle
+        for flag in ["--help", "-h"] {
+            let args = parse(&[flag]).unwrap();
+            assert!(args.help, "{}", flag);
+
+            let mut out = Vec::new();
+            assert_eq!(run(&args, &mut out).unwrap(), ExitCode::SUCCESS);
+            assert_eq!(String::from_utf8(out).unwrap(), format!("{}\n", USAGE));
+        }
+    }
+
+    #[test]
+    fn test_run_stops_at_a_closed_pipe() {
+        // Test: A write that fails comes back as an error for main to handle, rather than a panic
+        struct Closed;
+        impl Write for Closed {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let args = Args {
+            width: 5,
+            height: 5,
+            seed: Some(1),
+            ..Args::default()
+        };
+
+        let error = run(&args, &mut Closed).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    }
+}
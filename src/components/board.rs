@@ -0,0 +1,541 @@
+use crate::components::game::Palette;
+use crate::hashi::{BridgeDirection, BridgeLine, BridgeType, HashiGrid, Variant};
+use crate::settings::{BridgeStyle, Settings};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct BoardViewProps {
+    pub grid: HashiGrid,
+}
+
+/// Radius of an island's circle, in SVG units, when it isn't selected
+pub const ISLAND_RADIUS: i32 = 28;
+
+/// Read-only drawing of a grid's islands and bridges, for anywhere a board is shown but not played
+#[function_component(BoardView)]
+pub fn board_view(props: &BoardViewProps) -> Html {
+    let settings = Settings::load();
+    let palette = Palette::for_settings(&settings);
+    let look = BridgeLook::new(settings.bridge_style, 0.0);
+    let grid = &props.grid;
+
+    let bridges: Html = grid
+        .bridges
+        .iter()
+        .flat_map(|(line, bridge_type)| {
+            bridge_strands(grid, line, bridge_type, look)
+                .into_iter()
+                .map(|(x1, y1, x2, y2)| {
+                    html! {
+                        <line
+                            x1={x1.to_string()}
+                            y1={y1.to_string()}
+                            x2={x2.to_string()}
+                            y2={y2.to_string()}
+                            stroke={palette.bridge}
+                            stroke-width={look.stroke_width(bridge_type).to_string()}
+                            stroke-linecap="round"
+                        />
+                    }
+                })
+        })
+        .collect();
+
+    let islands: Html = grid
+        .islands
+        .iter()
+        .map(|(position, island)| {
+            let cx = position.x as i32 * 100;
+            let cy = position.y as i32 * 100;
+            html! {
+                <>
+                    <circle
+                        cx={cx.to_string()}
+                        cy={cy.to_string()}
+                        r={ISLAND_RADIUS.to_string()}
+                        fill={palette.island_fill}
+                        stroke={palette.island_stroke}
+                        stroke-width="2"
+                    />
+                    <text
+                        x={cx.to_string()}
+                        y={cy.to_string()}
+                        text-anchor="middle"
+                        dominant-baseline="central"
+                        font-size={clue_font_size(island.required_bridges).to_string()}
+                        font-family="sans-serif"
+                        fill={palette.text}
+                    >
+                        { island.required_bridges.to_string() }
+                    </text>
+                </>
+            }
+        })
+        .collect();
+
+    html! {
+        <svg
+            viewBox={view_box(grid)}
+            preserveAspectRatio="xMidYMid meet"
+            class="game-svg"
+        >
+            { bridges }
+            { islands }
+        </svg>
+    }
+}
+
+/// The whole board with a margin of one cell for the islands on its edges
+pub fn view_box(grid: &HashiGrid) -> String {
+    format!(
+        "-100 -100 {} {}",
+        grid.width as i32 * 100 + 100,
+        grid.height as i32 * 100 + 100
+    )
+}
+
+/// The `(x, y, width, height)` of the whole board with a margin of one cell, as `view_box` shows it
+pub fn whole_extent(grid: &HashiGrid) -> (f64, f64, f64, f64) {
+    (
+        -100.0,
+        -100.0,
+        grid.width as f64 * 100.0 + 100.0,
+        grid.height as f64 * 100.0 + 100.0,
+    )
+}
+
+/// The `(x, y, width, height)` of the board a game shows: its islands with a cell's margin round
+/// them, leaving off empty rows and columns at the edges. A toroidal board's bridges run out to
+/// its edges, so it is shown whole, as is a board with no islands
+pub fn board_extent(grid: &HashiGrid) -> (f64, f64, f64, f64) {
+    if grid.variant != Variant::Classic {
+        return whole_extent(grid);
+    }
+    let Some((min, max)) = grid.bounding_box_of_islands() else {
+        return whole_extent(grid);
+    };
+    (
+        min.x as f64 * 100.0 - 100.0,
+        min.y as f64 * 100.0 - 100.0,
+        (max.x - min.x) as f64 * 100.0 + 200.0,
+        (max.y - min.y) as f64 * 100.0 + 200.0,
+    )
+}
+
+/// Font size for an island's clue, shrinking as digits are added so the text stays inside the circle
+pub fn clue_font_size(clue: u8) -> u32 {
+    match clue {
+        0..=9 => 20,
+        10..=99 => 18,
+        _ => 14,
+    }
+}
+
+/// A standalone SVG file of the board as it stands, with the same geometry as the live board
+/// but nothing to interact with. Islands whose clue is met are filled like they are in play.
+pub fn board_svg(grid: &HashiGrid, palette: &Palette, look: BridgeLook) -> String {
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{}">"#,
+        view_box(grid)
+    );
+    push_board_shapes(&mut svg, grid, palette, look);
+    svg.push_str("</svg>");
+    svg
+}
+
+/// `board_svg` on a solid background with a line of text underneath, an image for sharing a result
+pub fn result_svg(grid: &HashiGrid, palette: &Palette, look: BridgeLook, caption: &str) -> String {
+    let (width, height) = (
+        grid.width as i32 * 100 + 100,
+        grid.height as i32 * 100 + 200,
+    );
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-100 -100 {} {}">"#,
+        width, height
+    );
+    svg.push_str(&format!(
+        r#"<rect x="-100" y="-100" width="{}" height="{}" fill="{}"/>"#,
+        width, height, palette.island_fill
+    ));
+    push_board_shapes(&mut svg, grid, palette, look);
+    svg.push_str(&format!(
+        r#"<text x="{}" y="{}" text-anchor="middle" font-size="40" font-family="sans-serif" fill="{}">{}</text>"#,
+        width / 2 - 100,
+        grid.height as i32 * 100 + 40,
+        palette.text,
+        escape_text(caption)
+    ));
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Text with the characters that would end it early in SVG markup escaped
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The bridges with their double marks and then the islands of a board, as SVG elements
+fn push_board_shapes(svg: &mut String, grid: &HashiGrid, palette: &Palette, look: BridgeLook) {
+    let push_line = |svg: &mut String, (x1, y1, x2, y2): (f64, f64, f64, f64), width: f64| {
+        svg.push_str(&format!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+            x1, y1, x2, y2, palette.bridge, width
+        ));
+    };
+    for (line, bridge_type) in &grid.bridges {
+        for strand in bridge_strands(grid, line, bridge_type, look) {
+            push_line(svg, strand, look.stroke_width(bridge_type));
+        }
+    }
+    for (line, _) in grid
+        .bridges
+        .iter()
+        .filter(|(_, bridge_type)| **bridge_type == BridgeType::Double)
+    {
+        let DoubleMarks { bars, label } = double_marks(grid, line, look);
+        for bar in bars {
+            push_line(svg, bar, look.double_width);
+        }
+        if let Some((x, y)) = label {
+            svg.push_str(&format!(
+                r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="central" font-size="{}" font-family="sans-serif" fill="{}">×2</text>"#,
+                x,
+                y,
+                look.double_gap * 0.6,
+                palette.bridge
+            ));
+        }
+    }
+    for (position, island) in &grid.islands {
+        let cx = position.x as i32 * 100;
+        let cy = position.y as i32 * 100;
+        let fill = if grid.degree(*position) == island.required_bridges {
+            palette.satisfied
+        } else {
+            palette.island_fill
+        };
+        svg.push_str(&format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}" stroke="{}" stroke-width="2"/>"#,
+            cx, cy, ISLAND_RADIUS, fill, palette.island_stroke
+        ));
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="central" font-size="{}" font-family="sans-serif" fill="{}">{}</text>"#,
+            cx,
+            cy,
+            clue_font_size(island.required_bridges),
+            palette.text,
+            island.required_bridges
+        ));
+    }
+}
+
+/// Stroke widths and spacing for drawing bridges, in SVG units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BridgeLook {
+    pub single_width: f64,
+    /// Width of each of a double bridge's two strands
+    pub double_width: f64,
+    /// Distance between the centers of a double bridge's strands
+    pub double_gap: f64,
+    /// Mark doubles with bars across their ends and a count between their strands
+    pub marked_doubles: bool,
+}
+
+impl BridgeLook {
+    /// Sizes for a style on a board shown at `units_per_px` SVG units per CSS pixel, or 0 if
+    /// unknown. Shrunken boards get wider strands and gaps so doubles don't blur into one line.
+    pub fn new(style: BridgeStyle, units_per_px: f64) -> Self {
+        match style {
+            BridgeStyle::Standard => {
+                let width = (1.5 * units_per_px).max(4.0);
+                BridgeLook {
+                    single_width: width,
+                    double_width: width,
+                    double_gap: (width + 3.0 * units_per_px).clamp(10.0, 36.0),
+                    marked_doubles: false,
+                }
+            }
+            BridgeStyle::Distinct => {
+                let double_width = (1.5 * units_per_px).max(3.0);
+                BridgeLook {
+                    single_width: (3.0 * units_per_px).max(6.0),
+                    double_width,
+                    // kept under the island's diameter so both strands still meet the circle
+                    double_gap: (double_width + 5.0 * units_per_px).clamp(18.0, 36.0),
+                    marked_doubles: false,
+                }
+            }
+            BridgeStyle::Split => {
+                let width = (1.5 * units_per_px).max(4.0);
+                BridgeLook {
+                    single_width: width,
+                    double_width: width,
+                    // room between the strands for the count
+                    double_gap: (20.0 + 4.0 * units_per_px).min(36.0),
+                    marked_doubles: true,
+                }
+            }
+        }
+    }
+
+    pub fn stroke_width(&self, bridge_type: &BridgeType) -> f64 {
+        match bridge_type {
+            BridgeType::Single => self.single_width,
+            BridgeType::Double => self.double_width,
+        }
+    }
+}
+
+/// What marks a double bridge in a look with `marked_doubles`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DoubleMarks {
+    /// Bars across the strands' ends, just outside the islands
+    pub bars: Vec<(f64, f64, f64, f64)>,
+    /// Where the "×2" goes, midway along the first piece of the line
+    pub label: Option<(f64, f64)>,
+}
+
+/// The marks for a double along a line, none for a look that doesn't mark doubles. Nothing can
+/// cross a bridge, so the count between its strands never lands on another
+pub fn double_marks(grid: &HashiGrid, bridge_line: &BridgeLine, look: BridgeLook) -> DoubleMarks {
+    if !look.marked_doubles {
+        return DoubleMarks::default();
+    }
+    let reach = ISLAND_RADIUS as f64 + 6.0;
+    let half = look.double_gap / 2.0 + look.double_width / 2.0;
+    let segments = line_segments(grid, bridge_line);
+    let bar = |x: f64, y: f64| match bridge_line.direction {
+        BridgeDirection::Right => (x, y - half, x, y + half),
+        BridgeDirection::Down => (x - half, y, x + half, y),
+    };
+    let bars = segments
+        .iter()
+        .flat_map(|&(x1, y1, x2, y2)| {
+            let length = (x2 - x1).hypot(y2 - y1);
+            let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+            // a wrapped line's pieces end past the board's edge rather than at an island
+            let far_end = (!bridge_line.wraps).then(|| bar(x2 - dx * reach, y2 - dy * reach));
+            std::iter::once(bar(x1 + dx * reach, y1 + dy * reach)).chain(far_end)
+        })
+        .collect();
+    let label = segments
+        .first()
+        .map(|&(x1, y1, x2, y2)| ((x1 + x2) / 2.0, (y1 + y2) / 2.0));
+    DoubleMarks { bars, label }
+}
+
+/// Endpoints of the pieces a line is drawn in: one straight between its islands, or for a line
+/// wrapping round the board one from each island out past the edge it leaves by
+pub fn line_segments(grid: &HashiGrid, bridge_line: &BridgeLine) -> Vec<(f64, f64, f64, f64)> {
+    let (sx, sy) = (
+        bridge_line.start.x as f64 * 100.0,
+        bridge_line.start.y as f64 * 100.0,
+    );
+    let (ex, ey) = (
+        bridge_line.end.x as f64 * 100.0,
+        bridge_line.end.y as f64 * 100.0,
+    );
+    if !bridge_line.wraps {
+        return vec![(sx, sy, ex, ey)];
+    }
+    // half a cell past the edge, inside the margin the view box leaves
+    match bridge_line.direction {
+        BridgeDirection::Right => vec![
+            (sx, sy, -50.0, sy),
+            (ex, ey, grid.width as f64 * 100.0 - 50.0, ey),
+        ],
+        BridgeDirection::Down => vec![
+            (sx, sy, sx, -50.0),
+            (ex, ey, ex, grid.height as f64 * 100.0 - 50.0),
+        ],
+    }
+}
+
+/// Endpoints of each line drawn for a bridge, one for a single and two side by side for a double,
+/// for every piece of the line
+pub fn bridge_strands(
+    grid: &HashiGrid,
+    bridge_line: &BridgeLine,
+    bridge_type: &BridgeType,
+    look: BridgeLook,
+) -> Vec<(f64, f64, f64, f64)> {
+    let offsets = match bridge_type {
+        BridgeType::Single => vec![0.0],
+        BridgeType::Double => vec![-look.double_gap / 2.0, look.double_gap / 2.0],
+    };
+
+    line_segments(grid, bridge_line)
+        .into_iter()
+        .flat_map(|(x1, y1, x2, y2)| {
+            offsets
+                .iter()
+                .map(move |&offset| match bridge_line.direction {
+                    BridgeDirection::Right => (x1, y1 + offset, x2, y2 + offset),
+                    BridgeDirection::Down => (x1 + offset, y1, x2 + offset, y2),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashi::Position;
+
+    #[test]
+    fn test_board_extent_crops_empty_edges() {
+        // Test: Empty rows and columns round the islands are cropped, a toroidal board is kept whole
+        let at = |x, y| Position { x, y };
+        let mut grid = HashiGrid::from_layout(8, 10, &[(at(2, 1), 1), (at(5, 1), 1)]).unwrap();
+        assert_eq!(board_extent(&grid), (100.0, 0.0, 500.0, 200.0));
+
+        grid.variant = Variant::Toroidal;
+        assert_eq!(board_extent(&grid), (-100.0, -100.0, 900.0, 1100.0));
+        assert_eq!(
+            board_extent(&HashiGrid::new(8, 10).unwrap()),
+            (-100.0, -100.0, 900.0, 1100.0)
+        );
+    }
+
+    #[test]
+    fn test_bridge_strands_double_side_by_side() {
+        // Test: A double bridge is drawn as two parallel strands either side of the single strand
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        let look = BridgeLook::new(BridgeStyle::Standard, 0.0);
+        let grid = HashiGrid::new(6, 6).unwrap();
+
+        assert_eq!(
+            bridge_strands(&grid, &line, &BridgeType::Single, look),
+            vec![(100.0, 200.0, 400.0, 200.0)]
+        );
+        assert_eq!(
+            bridge_strands(&grid, &line, &BridgeType::Double, look),
+            vec![(100.0, 195.0, 400.0, 195.0), (100.0, 205.0, 400.0, 205.0)]
+        );
+    }
+
+    #[test]
+    fn test_wrapped_bridge_drawn_out_past_both_edges() {
+        // Test: A wrapped bridge is two pieces, one from each island out into the margin beyond its edge
+        let line = BridgeLine::wrapping(Position { x: 2, y: 1 }, Position { x: 2, y: 4 }).unwrap();
+        let look = BridgeLook::new(BridgeStyle::Standard, 0.0);
+        let grid = HashiGrid::new(6, 6).unwrap();
+
+        assert_eq!(
+            bridge_strands(&grid, &line, &BridgeType::Single, look),
+            vec![(200.0, 100.0, 200.0, -50.0), (200.0, 400.0, 200.0, 550.0)]
+        );
+        assert_eq!(
+            bridge_strands(&grid, &line, &BridgeType::Double, look).len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_board_svg_draws_every_island_and_strand() {
+        // Test: The exported image has a circle and clue per island and a line per bridge strand, and no handlers
+        let mut grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[(Position { x: 1, y: 2 }, 2), (Position { x: 4, y: 2 }, 3)],
+        )
+        .unwrap();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        grid.add_bridge(line).unwrap();
+        grid.add_bridge(line).unwrap();
+        let palette = Palette::for_settings(&Settings::default());
+        let svg = board_svg(&grid, &palette, BridgeLook::new(BridgeStyle::Standard, 0.0));
+
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-100 -100 600 600">"#
+        ));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert!(svg.contains(">3</text>"));
+        assert_eq!(svg.matches(palette.satisfied).count(), 1);
+        assert!(!svg.contains("×2"));
+
+        // split doubles carry their end bars and count, as on the live board
+        let split = board_svg(&grid, &palette, BridgeLook::new(BridgeStyle::Split, 0.0));
+        assert_eq!(split.matches("<line").count(), 4);
+        assert_eq!(split.matches(">×2</text>").count(), 1);
+    }
+
+    #[test]
+    fn test_result_svg_adds_background_and_caption() {
+        // Test: The shared image is the board on a filled background, taller to fit the caption under it
+        let grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[(Position { x: 1, y: 2 }, 1), (Position { x: 4, y: 2 }, 1)],
+        )
+        .unwrap();
+        let palette = Palette::for_settings(&Settings::default());
+        let svg = result_svg(
+            &grid,
+            &palette,
+            BridgeLook::new(BridgeStyle::Standard, 0.0),
+            "Solved in 01:05",
+        );
+
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-100 -100 600 700">"#
+        ));
+        assert!(svg.contains(r#"<rect x="-100" y="-100" width="600" height="700""#));
+        assert!(svg.contains(">Solved in 01:05</text>"));
+        assert_eq!(svg.matches("<circle").count(), 2);
+
+        let look = BridgeLook::new(BridgeStyle::Standard, 0.0);
+        let svg = result_svg(&grid, &palette, look, "Me & <you>");
+        assert!(svg.contains(">Me &amp; &lt;you&gt;</text>"));
+    }
+
+    #[test]
+    fn test_clue_font_size_shrinks_with_digits() {
+        // Test: Multi-digit clues use a smaller font so they fit in the island circle
+        assert_eq!(clue_font_size(8), 20);
+        assert!(clue_font_size(12) < clue_font_size(8));
+        assert!(clue_font_size(200) < clue_font_size(12));
+    }
+
+    #[test]
+    fn test_bridge_look_scales_with_board() {
+        // Test: Shrinking the board widens the double gap, never past the island, and the distinct style stands apart
+        for style in BridgeStyle::ALL {
+            let full_size = BridgeLook::new(style, 1.0);
+            let phone = BridgeLook::new(style, 3.0);
+            assert!(phone.double_gap > full_size.double_gap, "{}", style);
+            assert!(BridgeLook::new(style, 50.0).double_gap <= 36.0, "{}", style);
+        }
+
+        let distinct = BridgeLook::new(BridgeStyle::Distinct, 1.0);
+        assert!(distinct.single_width > distinct.double_width);
+        assert!(distinct.double_gap > BridgeLook::new(BridgeStyle::Standard, 1.0).double_gap);
+    }
+
+    #[test]
+    fn test_double_marks_bar_each_end() {
+        // Test: Split doubles get a bar just outside each island and a count midway, other styles nothing
+        let grid = HashiGrid::new(5, 5).unwrap();
+        let line = BridgeLine::new(Position { x: 0, y: 1 }, Position { x: 3, y: 1 }).unwrap();
+        let look = BridgeLook::new(BridgeStyle::Split, 0.0);
+        let DoubleMarks { bars, label } = double_marks(&grid, &line, look);
+
+        let half = look.double_gap / 2.0 + look.double_width / 2.0;
+        assert_eq!(
+            bars,
+            vec![
+                (34.0, 100.0 - half, 34.0, 100.0 + half),
+                (266.0, 100.0 - half, 266.0, 100.0 + half)
+            ]
+        );
+        assert_eq!(label, Some((150.0, 100.0)));
+        assert_eq!(
+            double_marks(&grid, &line, BridgeLook::new(BridgeStyle::Standard, 0.0)),
+            DoubleMarks::default()
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use crate::Route;
+use crate::components::board::{ISLAND_RADIUS, clue_font_size, view_box};
+use crate::components::game::{Game, Palette, copy_to_clipboard};
+use crate::components::home::{MAX_BOARD_SIZE, MIN_BOARD_SIZE, parse_board_size};
+use crate::components::not_found::NotFound;
+use crate::hashi::{HashiGrid, Position};
+use crate::settings::Settings;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+/// Highest clue an island can have, with a double bridge in each of four directions
+const MAX_CLUE: u8 = 8;
+
+/// What tapping a cell does to it: an empty cell gets a 1, an island's clue goes up, and past 8 it's taken away
+fn next_clue(clue: Option<u8>) -> Option<u8> {
+    match clue {
+        None => Some(1),
+        Some(clue) if clue < MAX_CLUE => Some(clue + 1),
+        Some(_) => None,
+    }
+}
+
+/// Why a hand-made puzzle can't be shared, or a warning about one that can
+fn check_puzzle(grid: &HashiGrid) -> Result<Option<&'static str>, &'static str> {
+    if grid.islands.len() < 2 {
+        return Err("Place at least two islands");
+    }
+    let total: u32 = grid
+        .islands
+        .values()
+        .map(|island| island.required_bridges as u32)
+        .sum();
+    if total % 2 == 1 {
+        return Err("The clues add up to an odd number, but every bridge counts at both its ends");
+    }
+    if grid.solve().is_none() {
+        return Err("No set of bridges meets every clue while joining all the islands up");
+    }
+    if grid.count_solutions(2) > 1 {
+        return Ok(Some(
+            "It has more than one solution, so players may finish it differently from you",
+        ));
+    }
+    Ok(None)
+}
+
+/// The same puzzle on a different size of board, dropping any islands that no longer fit
+fn resized(grid: &HashiGrid, width: u8, height: u8) -> HashiGrid {
+    let islands: Vec<(Position, u8)> = grid
+        .islands
+        .iter()
+        .filter(|(position, _)| position.x < width && position.y < height)
+        .map(|(position, island)| (*position, island.required_bridges))
+        .collect();
+    // safe unwrap, the size was parsed within bounds and the islands come from a grid
+    HashiGrid::from_layout(width, height, &islands).unwrap()
+}
+
+/// Page for making a puzzle by hand: tap cells to place islands and step their clues,
+/// then check it has a solution and share its code. Puzzles made here are always classic ones,
+/// as a puzzle code doesn't say which variant it's for
+#[function_component(CreatePuzzle)]
+pub fn create_puzzle() -> Html {
+    let navigator = use_navigator().unwrap();
+    let settings = use_state(Settings::load);
+    let grid = use_state(|| {
+        // stored settings can be edited by hand, so the size is brought back within bounds
+        let size = |size: u8| size.clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE);
+        // safe unwrap, the size was just clamped within bounds
+        HashiGrid::new(size(settings.board_width), size(settings.board_height)).unwrap()
+    });
+    let width_input = use_state(|| grid.width.to_string());
+    let height_input = use_state(|| grid.height.to_string());
+    let size_error = use_state(|| None::<String>);
+    // the result of the last check, cleared by any edit
+    let checked = use_state(|| None::<Result<Option<&'static str>, &'static str>>);
+
+    let on_back = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| {
+            navigator.push(&Route::Home);
+        })
+    };
+
+    let edit = |change: fn(&mut HashiGrid, Position, i32)| {
+        let grid = grid.clone();
+        let checked = checked.clone();
+        move |position: Position, amount: i32| {
+            let mut g = (*grid).clone();
+            change(&mut g, position, amount);
+            grid.set(g);
+            checked.set(None);
+        }
+    };
+    let on_tap = edit(|grid, position, _| {
+        let clue = grid
+            .islands
+            .get(&position)
+            .map(|island| island.required_bridges);
+        match next_clue(clue) {
+            // safe unwrap, every tapped cell is on the grid and the editor never builds bridges
+            Some(clue) => grid.set_clue(position, clue).unwrap(),
+            None => {
+                grid.remove_island(position);
+            }
+        }
+    });
+    let on_scroll = edit(|grid, position, amount| {
+        if let Some(island) = grid.islands.get_mut(&position) {
+            island.required_bridges =
+                (island.required_bridges as i32 + amount).clamp(1, MAX_CLUE as i32) as u8;
+        }
+    });
+
+    let on_size_input = |value: &UseStateHandle<String>| {
+        let value = value.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            value.set(input.value());
+        })
+    };
+    let on_width_input = on_size_input(&width_input);
+    let on_height_input = on_size_input(&height_input);
+
+    let on_resize = {
+        let grid = grid.clone();
+        let checked = checked.clone();
+        let size_error = size_error.clone();
+        let (width, height) = ((*width_input).clone(), (*height_input).clone());
+        Callback::from(move |_| match parse_board_size(&width, &height) {
+            Ok((width, height)) => {
+                grid.set(resized(&grid, width, height));
+                checked.set(None);
+                size_error.set(None);
+            }
+            Err(message) => size_error.set(Some(message)),
+        })
+    };
+
+    let on_clear = {
+        let grid = grid.clone();
+        let checked = checked.clone();
+        Callback::from(move |_| {
+            // safe unwrap, the current size is already a valid one
+            grid.set(HashiGrid::new(grid.width, grid.height).unwrap());
+            checked.set(None);
+        })
+    };
+
+    let on_check = {
+        let grid = grid.clone();
+        let checked = checked.clone();
+        Callback::from(move |_| checked.set(Some(check_puzzle(&grid))))
+    };
+
+    let route = Route::Custom {
+        code: grid.share_code(),
+    };
+    let on_play = {
+        let navigator = navigator.clone();
+        let route = route.clone();
+        Callback::from(move |_| navigator.push(&route))
+    };
+    let on_copy_link = {
+        let basename = navigator.basename().unwrap_or_default().to_string();
+        Callback::from(move |_| {
+            if let Some(origin) = web_sys::window().and_then(|w| w.location().origin().ok()) {
+                copy_to_clipboard(format!("{}{}{}", origin, basename, route.to_path()));
+            }
+        })
+    };
+
+    let palette = Palette::for_settings(&settings);
+    let cells: Html = (0..grid.height)
+        .flat_map(|y| (0..grid.width).map(move |x| Position { x, y }))
+        .map(|position| {
+            let cx = position.x as i32 * 100;
+            let cy = position.y as i32 * 100;
+            let onclick = {
+                let on_tap = on_tap.clone();
+                Callback::from(move |_: MouseEvent| on_tap(position, 0))
+            };
+            let onwheel = {
+                let on_scroll = on_scroll.clone();
+                Callback::from(move |e: WheelEvent| {
+                    e.prevent_default();
+                    on_scroll(position, if e.delta_y() < 0.0 { 1 } else { -1 });
+                })
+            };
+            let island = match grid.islands.get(&position) {
+                Some(island) => html! {
+                    <>
+                        <circle
+                            cx={cx.to_string()}
+                            cy={cy.to_string()}
+                            r={ISLAND_RADIUS.to_string()}
+                            fill={palette.island_fill}
+                            stroke={palette.island_stroke}
+                            stroke-width="2"
+                        />
+                        <text
+                            x={cx.to_string()}
+                            y={cy.to_string()}
+                            text-anchor="middle"
+                            dominant-baseline="central"
+                            font-size={clue_font_size(island.required_bridges).to_string()}
+                            font-family="sans-serif"
+                            fill={palette.text}
+                        >
+                            { island.required_bridges.to_string() }
+                        </text>
+                    </>
+                },
+                None => html! {
+                    <circle
+                        cx={cx.to_string()}
+                        cy={cy.to_string()}
+                        r="6"
+                        fill={palette.island_stroke}
+                        opacity="0.2"
+                    />
+                },
+            };
+            html! {
+                <g onclick={onclick} onwheel={onwheel} style="cursor:pointer;">
+                    <rect
+                        x={(cx - 50).to_string()}
+                        y={(cy - 50).to_string()}
+                        width="100"
+                        height="100"
+                        fill="transparent"
+                    />
+                    { island }
+                </g>
+            }
+        })
+        .collect();
+
+    html! {
+        <div class={classes!("create-page", settings.dark_mode.then_some("dark"))}>
+            <div class="create-controls">
+                <button onclick={on_back} class="btn btn-back">{"← Back"}</button>
+                <input
+                    type="number"
+                    min={MIN_BOARD_SIZE.to_string()}
+                    max={MAX_BOARD_SIZE.to_string()}
+                    value={(*width_input).clone()}
+                    oninput={on_width_input}
+                    aria-label="Width"
+                    class="size-input"
+                />
+                <span class="size-separator">{"x"}</span>
+                <input
+                    type="number"
+                    min={MIN_BOARD_SIZE.to_string()}
+                    max={MAX_BOARD_SIZE.to_string()}
+                    value={(*height_input).clone()}
+                    oninput={on_height_input}
+                    aria-label="Height"
+                    class="size-input"
+                />
+                <button onclick={on_resize} class="btn btn-primary">{"Resize"}</button>
+                <button onclick={on_clear} class="btn btn-primary">{"Clear"}</button>
+                <button onclick={on_check} class="btn btn-primary">{"✓ Check and share"}</button>
+            </div>
+            <p class="create-help">
+                {"Tap a cell to place an island, tap again to raise its clue, past 8 it's removed. Scroll over an island to change its clue."}
+            </p>
+            { if let Some(message) = &*size_error {
+                html! { <p class="size-error">{ message }</p> }
+            } else {
+                html! {}
+            }}
+            { match *checked {
+                None => html! {},
+                Some(Err(problem)) => html! {
+                    <div class="check-result check-problem">{ format!("✗ {}", problem) }</div>
+                },
+                Some(Ok(warning)) => html! {
+                    <div class="check-result check-ok create-share">
+                        <span>{"✓ This puzzle can be solved"}</span>
+                        { if let Some(warning) = warning {
+                            html! { <span>{ format!(" · {}", warning) }</span> }
+                        } else {
+                            html! {}
+                        }}
+                        <code class="create-code">{ grid.share_code() }</code>
+                        <button onclick={on_play} class="btn btn-game-large success">{"▶ Play"}</button>
+                        <button onclick={on_copy_link} class="btn btn-game-large">{"📋 Copy link"}</button>
+                    </div>
+                },
+            }}
+            <svg
+                viewBox={view_box(&grid)}
+                preserveAspectRatio="xMidYMid meet"
+                class="game-svg create-board"
+                role="group"
+                aria-label="Puzzle being made"
+            >
+                { cells }
+            </svg>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CustomPuzzleProps {
+    pub code: AttrValue,
+}
+
+/// Play a hand-made puzzle from its code in a link, read once when the page opens rather than
+/// on every render, as checking a code with locked bridges means solving it
+#[function_component(CustomPuzzle)]
+pub fn custom_puzzle(props: &CustomPuzzleProps) -> Html {
+    let grid = use_memo(props.code.clone(), |code| HashiGrid::from_code(code));
+    match grid.as_ref() {
+        Ok(grid) => html! {
+            <Game
+                width={grid.width}
+                height={grid.height}
+                puzzle_id={0}
+                puzzle={grid.clone()}
+            />
+        },
+        Err(_) => html! { <NotFound /> },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_clue_cycles_to_removal() {
+        // Test: Tapping places a 1, raises it to 8 and then takes the island away
+        let mut clue = None;
+        let mut seen = Vec::new();
+        for _ in 0..9 {
+            clue = next_clue(clue);
+            seen.push(clue);
+        }
+        assert_eq!(seen.first(), Some(&Some(1)));
+        assert_eq!(seen[7], Some(8));
+        assert_eq!(seen[8], None);
+    }
+
+    #[test]
+    fn test_check_puzzle() {
+        // Test: Too few islands, an odd clue total and unsolvable clues are refused, a puzzle with solutions passes
+        let at = |x, y| Position { x, y };
+        let layout = |islands: &[(Position, u8)]| HashiGrid::from_layout(5, 5, islands).unwrap();
+
+        assert!(check_puzzle(&layout(&[(at(0, 0), 1)])).is_err());
+        assert!(check_puzzle(&layout(&[(at(0, 0), 1), (at(2, 0), 2)])).is_err());
+        // the 3 can only reach one island, which can take at most 2
+        assert!(check_puzzle(&layout(&[(at(0, 0), 3), (at(2, 0), 3)])).is_err());
+        assert_eq!(
+            check_puzzle(&layout(&[(at(0, 0), 2), (at(2, 0), 2)])),
+            Ok(None)
+        );
+        // a square of 3s can have its doubles across the top and bottom or down the sides
+        assert!(matches!(
+            check_puzzle(&layout(&[
+                (at(0, 0), 3),
+                (at(2, 0), 3),
+                (at(0, 2), 3),
+                (at(2, 2), 3)
+            ])),
+            Ok(Some(_))
+        ));
+    }
+
+    #[test]
+    fn test_resized_drops_islands_off_the_board() {
+        // Test: Shrinking the board keeps the islands that still fit with their clues
+        let at = |x, y| Position { x, y };
+        let grid = HashiGrid::from_layout(8, 8, &[(at(1, 1), 2), (at(6, 1), 3)]).unwrap();
+        let smaller = resized(&grid, 5, 5);
+
+        assert_eq!((smaller.width, smaller.height), (5, 5));
+        assert_eq!(smaller.islands.len(), 1);
+        assert_eq!(smaller.islands[&at(1, 1)].required_bridges, 2);
+    }
+}
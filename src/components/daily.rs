@@ -0,0 +1,229 @@
+use crate::components::game::Game;
+use crate::hashi::{Difficulty, splitmix64};
+use crate::storage::save_quietly;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use web_sys::js_sys::Date;
+use yew::prelude::*;
+
+pub const DAILY_WIDTH: u8 = 6;
+pub const DAILY_HEIGHT: u8 = 12;
+
+const STREAK_KEY: &str = "hashi.daily_streak";
+
+/// A calendar day in UTC, so every player shares the same daily puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct DailyDate {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl DailyDate {
+    pub fn today() -> Self {
+        let now = Date::new_0();
+        Self {
+            year: now.get_utc_full_year(),
+            month: now.get_utc_month() + 1,
+            day: now.get_utc_date(),
+        }
+    }
+
+    /// The day before, rolling back over month and year ends
+    pub fn previous(&self) -> Self {
+        if self.day > 1 {
+            return Self {
+                day: self.day - 1,
+                ..*self
+            };
+        }
+        let (year, month) = if self.month > 1 {
+            (self.year, self.month - 1)
+        } else {
+            (self.year - 1, 12)
+        };
+        Self {
+            year,
+            month,
+            day: days_in_month(year, month),
+        }
+    }
+
+    /// Deterministic puzzle seed for the day, YYYYMMDD run through a splitmix64 step so neighbouring days differ widely
+    pub fn seed(&self) -> u64 {
+        splitmix64(self.year as u64 * 10_000 + self.month as u64 * 100 + self.day as u64)
+    }
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Run of consecutive days the daily puzzle has been solved, persisted in localStorage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyStreak {
+    pub count: u32,
+    pub last_completed: Option<DailyDate>,
+}
+
+impl DailyStreak {
+    pub fn load() -> Self {
+        LocalStorage::get(STREAK_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_quietly(STREAK_KEY, self);
+    }
+
+    /// Count a solve of the given day's puzzle, extending the streak if yesterday's was solved too
+    pub fn record(&mut self, today: DailyDate) {
+        if self.last_completed == Some(today) {
+            return;
+        }
+        self.count = if self.last_completed == Some(today.previous()) {
+            self.count + 1
+        } else {
+            1
+        };
+        self.last_completed = Some(today);
+    }
+
+    /// The streak as of a day, which is broken once a whole day passes without a solve
+    pub fn current(&self, today: DailyDate) -> u32 {
+        match self.last_completed {
+            Some(last) if last == today || last == today.previous() => self.count,
+            _ => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for DailyDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[function_component(Daily)]
+pub fn daily() -> Html {
+    let today = DailyDate::today();
+    let streak = use_state(DailyStreak::load);
+
+    let on_solved = {
+        let streak = streak.clone();
+        Callback::from(move |_| {
+            let mut s = *streak;
+            s.record(today);
+            s.save();
+            streak.set(s);
+        })
+    };
+
+    let days = streak.current(today);
+    let note = format!(
+        "🔥 Daily streak: {} day{}",
+        days,
+        if days == 1 { "" } else { "s" }
+    );
+
+    html! {
+        <Game
+            width={DAILY_WIDTH}
+            height={DAILY_HEIGHT}
+            puzzle_id={today.seed()}
+            title={AttrValue::from(format!("Daily {}", today))}
+            difficulty={Difficulty::Medium}
+            on_solved={on_solved}
+            victory_note={AttrValue::from(note)}
+        />
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_seed_is_stable() {
+        // Test: The same date always gives the same seed
+        let date = DailyDate {
+            year: 2025,
+            month: 3,
+            day: 14,
+        };
+        assert_eq!(date.seed(), date.seed());
+    }
+
+    #[test]
+    fn test_daily_seed_differs_between_days() {
+        // Test: Consecutive days get different puzzles
+        let monday = DailyDate {
+            year: 2025,
+            month: 3,
+            day: 14,
+        };
+        let tuesday = DailyDate {
+            year: 2025,
+            month: 3,
+            day: 15,
+        };
+        assert_ne!(monday.seed(), tuesday.seed());
+    }
+
+    fn date(year: u32, month: u32, day: u32) -> DailyDate {
+        DailyDate { year, month, day }
+    }
+
+    #[test]
+    fn test_previous_day_rolls_over() {
+        // Test: The day before the 1st is the end of the previous month, including leap years and new year
+        assert_eq!(date(2025, 3, 15).previous(), date(2025, 3, 14));
+        assert_eq!(date(2025, 3, 1).previous(), date(2025, 2, 28));
+        assert_eq!(date(2024, 3, 1).previous(), date(2024, 2, 29));
+        assert_eq!(date(2025, 5, 1).previous(), date(2025, 4, 30));
+        assert_eq!(date(2025, 1, 1).previous(), date(2024, 12, 31));
+    }
+
+    #[test]
+    fn test_streak_counts_consecutive_days() {
+        // Test: Solving on consecutive days grows the streak, solving twice in a day does not
+        let mut streak = DailyStreak::default();
+        streak.record(date(2025, 2, 28));
+        streak.record(date(2025, 3, 1));
+        streak.record(date(2025, 3, 1));
+
+        assert_eq!(streak.count, 2);
+        assert_eq!(streak.current(date(2025, 3, 1)), 2);
+        // still alive the next day, before that day's puzzle is solved
+        assert_eq!(streak.current(date(2025, 3, 2)), 2);
+    }
+
+    #[test]
+    fn test_streak_resets_after_missed_day() {
+        // Test: Missing a day shows no streak, and the next solve starts again from one
+        let mut streak = DailyStreak::default();
+        streak.record(date(2025, 3, 1));
+        streak.record(date(2025, 3, 2));
+
+        assert_eq!(streak.current(date(2025, 3, 4)), 0);
+        streak.record(date(2025, 3, 4));
+        assert_eq!(streak.count, 1);
+    }
+
+    #[test]
+    fn test_daily_date_display() {
+        // Test: Dates are shown zero padded in ISO order
+        let date = DailyDate {
+            year: 2025,
+            month: 3,
+            day: 4,
+        };
+        assert_eq!(date.to_string(), "2025-03-04");
+    }
+}
@@ -0,0 +1,140 @@
+use crate::Route;
+use crate::components::game::{GameBoard, QueryParams};
+use crate::hashi::{GenerationOptions, HashiError, HashiGrid};
+use crate::settings::Settings;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+/// Puzzles shown side by side on the gallery page
+pub const GALLERY_PUZZLES: u8 = 4;
+
+/// Generate the gallery's puzzles with their bridges wiped, the nth from `seed + n` like a printed sheet
+fn gallery_puzzles(
+    width: u8,
+    height: u8,
+    seed: u64,
+    options: &GenerationOptions,
+) -> Result<Vec<HashiGrid>, HashiError> {
+    (0..GALLERY_PUZZLES as u64)
+        .map(|n| {
+            HashiGrid::generate_with_options(width, height, seed.wrapping_add(n), options)
+                .map(HashiGrid::wipe_bridges)
+        })
+        .collect()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GalleryProps {
+    pub width: u8,
+    pub height: u8,
+    pub seed: u64,
+}
+
+/// Several small puzzles played at once on one page, for quick practice
+#[function_component(Gallery)]
+pub fn gallery(props: &GalleryProps) -> Html {
+    let navigator = use_navigator().unwrap();
+    let settings = use_state(Settings::load);
+    let solved = use_state(|| 0u8);
+    let difficulty = use_location()
+        .and_then(|loc| loc.query::<QueryParams>().ok())
+        .unwrap_or_default()
+        .difficulty();
+    let puzzles = use_memo(
+        (props.width, props.height, props.seed, difficulty),
+        |&(width, height, seed, difficulty)| {
+            gallery_puzzles(width, height, seed, &difficulty.options())
+        },
+    );
+
+    // a new set starts the solved count over
+    {
+        let solved = solved.clone();
+        use_effect_with(props.seed, move |_| {
+            solved.set(0);
+            || ()
+        });
+    }
+
+    let on_back = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| {
+            navigator.push(&Route::Home);
+        })
+    };
+
+    let on_new_set = {
+        let (width, height) = (props.width, props.height);
+        let query = QueryParams::page(difficulty);
+        Callback::from(move |_| {
+            let route = Route::Gallery {
+                width,
+                height,
+                seed: rand::random::<u64>(),
+            };
+            let _ = navigator.push_with_query(&route, &query);
+        })
+    };
+
+    let puzzles = match puzzles.as_ref() {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            return html! {
+                <div class="rules-container">
+                    <p>{ format!("This gallery couldn't be generated: {}", e) }</p>
+                    <button onclick={on_back} class="btn btn-back">{"Back to Home"}</button>
+                </div>
+            };
+        }
+    };
+
+    let on_solved = {
+        let solved = solved.clone();
+        Callback::from(move |_| solved.set(*solved + 1))
+    };
+
+    html! {
+        <div class={classes!("gallery", settings.dark_mode.then_some("dark"))}>
+            <div class="gallery-controls">
+                <button onclick={on_back} class="btn btn-back">{"← Back"}</button>
+                <button onclick={on_new_set} class="btn btn-primary">{"🎲 New set"}</button>
+                <div class="game-progress">
+                    { format!("{} / {} solved", *solved, puzzles.len()) }
+                </div>
+            </div>
+            <div class="gallery-grid">
+                { for puzzles.iter().enumerate().map(|(n, grid)| {
+                    let puzzle_id = props.seed.wrapping_add(n as u64);
+                    html! {
+                        <div class="gallery-item">
+                            <GameBoard
+                                key={puzzle_id}
+                                grid={grid.clone()}
+                                puzzle_id={puzzle_id}
+                                difficulty={difficulty}
+                                on_solved={on_solved.clone()}
+                            />
+                        </div>
+                    }
+                })}
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gallery_puzzles_are_repeatable_and_blank() {
+        // Test: The same seed gives the same set of different puzzles, all ready to play
+        let options = GenerationOptions::default();
+        let puzzles = gallery_puzzles(5, 5, 11, &options).unwrap();
+
+        assert_eq!(puzzles, gallery_puzzles(5, 5, 11, &options).unwrap());
+        assert_eq!(puzzles.len(), GALLERY_PUZZLES as usize);
+        assert_ne!(puzzles[0], puzzles[1]);
+        assert!(puzzles.iter().all(|grid| grid.bridges.is_empty()));
+    }
+}
@@ -1,8 +1,26 @@
-use crate::hashi::{BridgeLine, HashiGrid, Position};
+use crate::components::board::{
+    BridgeLook, DoubleMarks, ISLAND_RADIUS, board_extent, board_svg, bridge_strands,
+    clue_font_size, double_marks, line_segments, result_svg, whole_extent,
+};
+use crate::components::minimap::{Minimap, Viewport};
+use crate::components::modal::use_modal_keys;
+use crate::components::replay::{Move, MoveKind, replay_query};
+use crate::components::rules::RulesContent;
+use crate::components::tutorial::{TutorialStep, tutorial_grid};
+use crate::hashi::{
+    BridgeLine, Difficulty, GenAlgo, GenerationOptions, HashiError, HashiGrid, Position, Variant,
+};
+use crate::score::{BestScores, PuzzleTimes, efficiency_score};
+use crate::settings::{SeedSettings, Settings};
+use crate::sound::{self, Sound};
 use crate::{Route, hashi};
-use serde::Deserialize;
+use gloo_timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
 use yew::prelude::*;
-use yew_hooks::use_interval;
+use yew_hooks::{use_interval, use_size};
 use yew_router::prelude::*;
 // use web_sys::console;
 // use web_sys::wasm_bindgen::JsValue;
@@ -15,6 +33,705 @@ struct GameState {
     shuddered_island: Option<Position>,
     time_elapsed: u32,
     challenge_time: Option<u32>,
+    /// The challenge's time was counted from its first move rather than from the puzzle appearing
+    challenge_from_first_move: bool,
+    /// Bridges placed or removed so far
+    moves: u32,
+    /// Every successful move in order, for sharing a replay of the solve
+    history: Vec<Move>,
+    challenge_moves: Option<u32>,
+    /// Seed and difficulty the puzzle was generated from, so it can be shared
+    puzzle_id: u64,
+    difficulty: Difficulty,
+    check_report: Option<CompletionReport>,
+    /// Island whose long-press bridge menu is open
+    bridge_menu: Option<Position>,
+    /// Island currently under the pointer
+    hovered: Option<Position>,
+    /// Where the last rejected bridge ran into another bridge or island, shown while shuddering
+    conflict: Option<Position>,
+    /// The player gave up and the solution is on show, so the board is read-only and never counts as a win
+    surrendered: bool,
+    /// Set when a surrender found no solution, which means the generator produced a broken puzzle
+    solution_missing: bool,
+    /// Lines the player has marked as "no bridge here", purely a note to themselves
+    annotations: BTreeSet<BridgeLine>,
+    /// When set, picking two islands toggles a "no bridge" mark instead of building a bridge
+    marking: bool,
+    /// When set, picking two islands builds a double in one go instead of a single
+    place_double: bool,
+    /// The most recently removed bridge, drawn fading out until the next bridge is added or removed
+    last_removed: Option<(BridgeLine, hashi::BridgeType)>,
+    /// Current prompt when playing the tutorial puzzle
+    tutorial: Option<TutorialStep>,
+    /// Why the puzzle couldn't be generated, e.g. a hand-typed URL with an impossible size
+    generation_error: Option<String>,
+    /// How far the board is zoomed in, and where
+    viewport: Viewport,
+    /// Help given by the hint button, cleared by the next move
+    hint: Option<Hint>,
+    /// Islands whose bridges are treated as final, so taps can't build more bridges onto them
+    locked: BTreeSet<Position>,
+    /// Lock each island as soon as its clue is met
+    auto_lock: bool,
+    /// Warn when a bridge is built that no solution has
+    warn_wrong_moves: bool,
+    /// Hold the timer at zero until the first bridge is built
+    timer_on_first_move: bool,
+    /// A bridge has been built, which starts a timer waiting for the first move
+    started: bool,
+    /// The player closed the progress message, so it stays hidden for this puzzle
+    encouragement_dismissed: bool,
+    /// The puzzle started with its opening bridges built for the player, so the solve isn't a best score
+    opening_filled: bool,
+    /// Played from a puzzle code rather than a seed, so links to it carry the code
+    hand_made: bool,
+    /// Generator settings the seed was generated with, which links to the puzzle carry
+    seed_settings: SeedSettings,
+    /// The board has a running timer. Boards without one, like the gallery's, keep no best times or scores
+    has_timer: bool,
+    /// A bridge clicked once when removing takes a double click, and when in milliseconds,
+    /// waiting for the second click
+    armed_removal: Option<(BridgeLine, f64)>,
+    /// Why the last bridge couldn't be built, or a warning about one that was, shown in a toast
+    /// that fades by itself. Numbered so the same message twice in a row shows afresh
+    rejection: Option<(u32, &'static str)>,
+    /// Bridge a long press asked about, its ends shown in a toast. Numbered like `rejection`
+    inspected: Option<(u32, BridgeLine)>,
+}
+
+/// What the hint button last showed. Pressing it again with an island circled builds one of its bridges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Hint {
+    /// An island whose bridges are all forced, circled so the player can work them out
+    Island(Position),
+    /// Nothing can be worked out from the current bridges without guessing
+    NoneFound,
+}
+
+/// How long an island must be held before its bridge menu opens
+const LONG_PRESS_MS: u32 = 500;
+
+/// Longest gap between the two clicks that remove a bridge, when removing takes a double click
+const DOUBLE_CLICK_MS: u32 = 400;
+
+/// Biggest puzzle, in islands, whose moves are checked against the solver when warning of wrong ones
+const WRONG_MOVE_MAX_ISLANDS: usize = 100;
+
+/// How far, in CSS pixels, a press can wander before it counts as a drag rather than a hold
+const DRAG_THRESHOLD_PX: i32 = 10;
+
+/// Tracks an in-progress press on an island, held for the bridge menu or dragged to another island
+#[derive(Default)]
+struct IslandPress {
+    /// Dropping the timeout cancels the press
+    timer: Option<Timeout>,
+    /// Set once the press has opened the bridge menu, so the click that ends the press is ignored
+    fired: bool,
+    /// Where a drag started, until the pointer that started it is lifted
+    drag: Option<DragStart>,
+    /// A second finger touched the board during the drag, so it builds a double. Stays set until
+    /// the next press, so the second finger's own tap is ignored too.
+    double: bool,
+    /// Clears the shudder of the last rejected bridge, dropping it cancels
+    shudder: Option<Timeout>,
+    /// The state as of the latest render, which the shudder is cleared from so nothing that
+    /// happened while it showed is put back
+    live: Option<UseStateHandle<GameState>>,
+}
+
+/// How long a rejected bridge's island shudders and its conflict stays marked
+const SHUDDER_MS: u32 = 300;
+
+/// Show a rejected bridge's shudder, clearing it a moment later from the board as it is by then,
+/// so a hint, undo or timer tick in the meantime stays. Each shudder replaces the last one's pending clear
+fn shudder(state: &UseStateHandle<GameState>, press: &Rc<RefCell<IslandPress>>, s: GameState) {
+    state.set(s);
+    // weak, as the press holds the timeout holding this
+    let live_press = Rc::downgrade(press);
+    press.borrow_mut().shudder = Some(Timeout::new(SHUDDER_MS, move || {
+        let Some(state) = live_press
+            .upgrade()
+            .and_then(|press| press.borrow().live.clone())
+        else {
+            return;
+        };
+        let mut s = (*state).clone();
+        s.shuddered_island = None;
+        s.conflict = None;
+        state.set(s);
+    }));
+}
+
+/// Clear a shudder still showing before a new move, cancelling its pending clear as there's
+/// nothing left for it to do
+fn settle_shudder(press: &Rc<RefCell<IslandPress>>, s: &mut GameState) {
+    if press.borrow_mut().shudder.take().is_some() {
+        s.shuddered_island = None;
+        s.conflict = None;
+    }
+}
+
+/// The island a drag started on, the pointer dragging and where it went down
+#[derive(Clone, Copy)]
+struct DragStart {
+    island: Position,
+    pointer_id: i32,
+    client: (i32, i32),
+}
+
+/// Past this scale a cell is under about 30 CSS pixels, and the board offers zooming in
+const MAX_UNZOOMED_UNITS_PER_PX: f64 = 3.5;
+
+/// Smallest comfortable touch target, in CSS pixels
+const MIN_TOUCH_TARGET_PX: f64 = 44.0;
+
+/// Sizes of the invisible shapes that catch taps on islands and bridges, in SVG units
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HitSizes {
+    island_radius: f64,
+    bridge_width: f64,
+}
+
+/// The part of the board a game shows, cropped to its islands unless grid lines are on, whose
+/// numbers sit in the margin of the whole board
+fn shown_extent(grid: &HashiGrid, settings: &Settings) -> (f64, f64, f64, f64) {
+    if settings.show_grid {
+        whole_extent(grid)
+    } else {
+        board_extent(grid)
+    }
+}
+
+/// SVG units per CSS pixel once a board's `shown_extent` is scaled to its rendered size, or 0
+/// before it is laid out
+fn units_per_px(extent: (f64, f64, f64, f64), rendered: (u32, u32)) -> f64 {
+    let (rendered_width, rendered_height) = rendered;
+    if rendered_width == 0 || rendered_height == 0 {
+        return 0.0;
+    }
+
+    // matches the viewBox, which `meet` scales by whichever side is tighter
+    let (_, _, view_width, view_height) = extent;
+    (view_width / rendered_width as f64).max(view_height / rendered_height as f64)
+}
+
+impl HitSizes {
+    /// Grow the hit shapes so they stay at least a comfortable touch target at the board's
+    /// rendered scale, without reaching into neighboring cells
+    fn for_board(units_per_px: f64) -> Self {
+        let target = MIN_TOUCH_TARGET_PX * units_per_px;
+
+        HitSizes {
+            island_radius: (target / 2.0).clamp(35.0, 50.0),
+            bridge_width: target.clamp(35.0, 90.0),
+        }
+    }
+
+    /// A piece of a bridge's hit line pulled back clear of the islands' hit circles, so a tap
+    /// near an island picks the island rather than the bridge. A piece starts at an island and
+    /// only ends at one when `ends_at_island`, otherwise it runs off the edge of a toroidal board.
+    /// Nothing is left when the circles cover it all.
+    fn bridge_hit(
+        &self,
+        (x1, y1, x2, y2): (f64, f64, f64, f64),
+        ends_at_island: bool,
+    ) -> Option<(f64, f64, f64, f64)> {
+        let length = (x2 - x1).hypot(y2 - y1);
+        let end_margin = if ends_at_island {
+            self.island_radius
+        } else {
+            0.0
+        };
+        if length <= self.island_radius + end_margin {
+            return None;
+        }
+        let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+        Some((
+            x1 + dx * self.island_radius,
+            y1 + dy * self.island_radius,
+            x2 - dx * end_margin,
+            y2 - dy * end_margin,
+        ))
+    }
+}
+
+/// Result of an on-demand solution check
+#[derive(Clone, Debug, PartialEq)]
+struct CompletionReport {
+    /// Islands whose bridge count does not match their clue
+    unsatisfied: Vec<Position>,
+    /// Whether the placed bridges join every island into one network
+    connected: bool,
+}
+
+impl CompletionReport {
+    fn is_complete(&self) -> bool {
+        self.unsatisfied.is_empty() && self.connected
+    }
+}
+
+impl GameState {
+    /// A fresh game of `grid`, which should have its bridges wiped
+    fn new(grid: HashiGrid, puzzle_id: u64, difficulty: Difficulty) -> Self {
+        GameState {
+            grid,
+            puzzle_id,
+            difficulty,
+            ..GameState::default()
+        }
+    }
+
+    /// Whether the player has solved the puzzle themselves, rather than surrendering.
+    /// The empty placeholder grid shown before generation never counts.
+    fn is_won(&self) -> bool {
+        !self.grid.islands.is_empty() && self.grid.is_complete() && !self.surrendered
+    }
+
+    /// Whether leaving now would throw away bridges the player has built on an unfinished puzzle
+    fn has_progress(&self) -> bool {
+        // bridges built for the player at the start aren't theirs to lose
+        self.moves > 0
+            && !self.grid.bridges.is_empty()
+            && !self.grid.is_complete()
+            && !self.surrendered
+    }
+
+    /// Number of islands whose bridge count matches their clue, alongside the total island count
+    fn satisfied_count(&self) -> (usize, usize) {
+        let satisfied = self
+            .grid
+            .islands
+            .iter()
+            .filter(|(position, island)| self.grid.degree(**position) == island.required_bridges)
+            .count();
+
+        (satisfied, self.grid.islands.len())
+    }
+
+    /// Size of the puzzle being played, the requested size only until a grid has been generated
+    fn board_size(&self, requested: (u8, u8)) -> (u8, u8) {
+        if self.grid.width == 0 {
+            requested
+        } else {
+            (self.grid.width, self.grid.height)
+        }
+    }
+
+    /// A word of encouragement once enough islands are satisfied, until the puzzle is over or the player closes it
+    fn encouragement(&self) -> Option<&'static str> {
+        if self.encouragement_dismissed || self.surrendered || self.tutorial.is_some() {
+            return None;
+        }
+        let (satisfied, total) = self.satisfied_count();
+        if satisfied == total {
+            return None;
+        }
+        // compared in whole numbers so a board of 5 isn't "halfway" at 2
+        if satisfied * 5 >= total * 4 {
+            Some("Almost done!")
+        } else if satisfied * 2 >= total {
+            Some("Halfway there!")
+        } else {
+            None
+        }
+    }
+
+    /// Islands that the selected island could build a bridge to right now
+    fn buildable_islands(&self) -> Vec<Position> {
+        let Some(selected) = self.selected else {
+            return Vec::new();
+        };
+
+        self.grid
+            .islands
+            .keys()
+            .filter(|&&position| {
+                self.grid
+                    .line_between(selected, position)
+                    .and_then(|line| self.grid.can_bridge(line))
+                    .is_ok()
+            })
+            .copied()
+            .collect()
+    }
+
+    /// The bridge from the selected to the hovered island, if it is in line but could not be built
+    fn blocked_preview(&self) -> Option<BridgeLine> {
+        let line = self.grid.line_between(self.selected?, self.hovered?).ok()?;
+        self.grid.can_bridge(line).is_err().then_some(line)
+    }
+
+    /// Build a bridge along a line, a single or a second one making a double, and record the move
+    fn place_bridge(&mut self, line: BridgeLine) -> Result<hashi::BridgeType, HashiError> {
+        let bridge_type = self.grid.add_bridge(line)?;
+        self.moves += 1;
+        self.started = true;
+        self.history.push(Move {
+            line,
+            kind: match bridge_type {
+                hashi::BridgeType::Single => MoveKind::Add,
+                hashi::BridgeType::Double => MoveKind::Upgrade,
+            },
+            at_seconds: self.time_elapsed,
+        });
+        self.last_removed = None;
+        self.hint = None;
+        if self.auto_lock {
+            for end in [line.start, line.end] {
+                if self.is_satisfied(end) {
+                    self.locked.insert(end);
+                }
+            }
+        }
+        // each check is a solver search, which gets too slow to run on every move of a big board
+        if self.warn_wrong_moves
+            && self.grid.islands.len() <= WRONG_MOVE_MAX_ISLANDS
+            && !self.grid.is_bridge_in_some_solution(line, bridge_type)
+        {
+            self.toast("No solution has this bridge, it will have to come out again");
+        }
+        Ok(bridge_type)
+    }
+
+    /// Build the bridge a drag asked for: one more bridge, or for a two finger drag a double
+    fn drag_bridge(
+        &mut self,
+        line: BridgeLine,
+        double: bool,
+    ) -> Result<hashi::BridgeType, HashiError> {
+        self.check_report = None;
+        self.selected = None;
+        self.place_strands(line, double)
+    }
+
+    /// Build the bridge two picked islands asked for, a double in one go when doubles are chosen
+    fn place_chosen(&mut self, line: BridgeLine) -> Result<hashi::BridgeType, HashiError> {
+        self.place_strands(line, self.place_double)
+    }
+
+    /// Build one more bridge along a line, and with `double` a second one straight after
+    fn place_strands(
+        &mut self,
+        line: BridgeLine,
+        double: bool,
+    ) -> Result<hashi::BridgeType, HashiError> {
+        let built = self.place_bridge(line)?;
+        if double && built == hashi::BridgeType::Single {
+            // an island without room for a second strand keeps the single
+            return Ok(self.place_bridge(line).unwrap_or(built));
+        }
+        Ok(built)
+    }
+
+    /// Step a line's bridge from none to single to double and back to none. A single that can't
+    /// become a double, because an island is already full, goes straight back to none.
+    fn cycle_bridge(&mut self, line: BridgeLine) -> Result<(), HashiError> {
+        self.check_report = None;
+        if self.grid.locked_bridges.contains(&line) {
+            return Err(HashiError::LockedBridge { line });
+        }
+
+        let previous = self.grid.bridges.get(&line).copied();
+        if previous != Some(hashi::BridgeType::Double) {
+            match self.place_bridge(line) {
+                Ok(_) => return Ok(()),
+                Err(e) if previous.is_none() => return Err(e),
+                Err(_) => {}
+            }
+        }
+
+        if let Some(removed) = self.grid.remove_bridge(line) {
+            self.last_removed = Some((line, removed));
+            self.hint = None;
+            self.unlock_unsatisfied(line);
+            self.moves += 1;
+            self.history.push(Move {
+                line,
+                kind: MoveKind::Clear,
+                at_seconds: self.time_elapsed,
+            });
+        }
+        Ok(())
+    }
+
+    /// Every island joined to this one through placed bridges, itself included
+    fn component_of(&self, position: Position) -> BTreeSet<Position> {
+        self.grid
+            .components()
+            .into_iter()
+            .find(|component| component.contains(&position))
+            .unwrap_or_default()
+    }
+
+    fn is_satisfied(&self, position: Position) -> bool {
+        self.grid
+            .island_at(position)
+            .is_some_and(|island| self.grid.degree(position) == island.required_bridges)
+    }
+
+    /// What the last move did, for screen readers: the bridge now on its line, and any island
+    /// it just completed
+    fn announcement(&self) -> String {
+        let Some(last) = self.history.last() else {
+            return String::new();
+        };
+        let line = last.line;
+        let mut message = format!(
+            "{} between {} and {}.",
+            bridge_name(self.grid.bridges.get(&line).copied()),
+            place_name(line.start),
+            place_name(line.end)
+        );
+        if matches!(last.kind, MoveKind::Add | MoveKind::Upgrade) {
+            for end in [line.start, line.end] {
+                if self.is_satisfied(end) {
+                    message.push_str(&format!(" Island at {} is complete.", place_name(end)));
+                }
+            }
+        }
+        message
+    }
+
+    /// Whether either end of a line is locked, so no bridge should be built or changed along it by a tap
+    fn touches_locked(&self, line: BridgeLine) -> bool {
+        self.locked.contains(&line.start) || self.locked.contains(&line.end)
+    }
+
+    /// Lock or unlock an island by hand, only satisfied islands can be locked
+    fn toggle_lock(&mut self, position: Position) {
+        if !self.locked.remove(&position) && self.is_satisfied(position) {
+            self.locked.insert(position);
+        }
+    }
+
+    /// A deliberately removed bridge means its islands weren't final after all
+    fn unlock_unsatisfied(&mut self, line: BridgeLine) {
+        for end in [line.start, line.end] {
+            if !self.is_satisfied(end) {
+                self.locked.remove(&end);
+            }
+        }
+    }
+
+    /// An island the player could finish by reasoning alone, whose bridges don't match that reasoning yet
+    fn next_forced_island(&self) -> Option<Position> {
+        self.grid.forced_islands().into_iter().next()
+    }
+
+    /// One bridge the clues force onto the island that it doesn't have yet
+    fn hint_bridge(&self, island: Position) -> Option<BridgeLine> {
+        self.grid
+            .forced_bridges()
+            .into_iter()
+            .find(|(line, count)| {
+                let placed = match self.grid.bridges.get(line) {
+                    Some(hashi::BridgeType::Single) => 1,
+                    Some(hashi::BridgeType::Double) => 2,
+                    None => 0,
+                };
+                (line.start == island || line.end == island) && placed < *count
+            })
+            .map(|(line, _)| line)
+    }
+
+    /// The hint button's two steps: first circle a forced island, then build one of its bridges
+    fn take_hint(&mut self) {
+        self.check_report = None;
+        match self.hint {
+            Some(Hint::Island(island)) => match self.hint_bridge(island) {
+                Some(line) => {
+                    let _ = self.place_bridge(line);
+                }
+                // the island only needs bridges taken away, which is left to the player
+                None => self.hint = None,
+            },
+            _ => {
+                self.hint = Some(
+                    self.next_forced_island()
+                        .map_or(Hint::NoneFound, Hint::Island),
+                );
+            }
+        }
+    }
+
+    /// Take one bridge away from a line: doubles become singles and singles are removed
+    fn remove_one_bridge(&mut self, line: BridgeLine) {
+        self.check_report = None;
+
+        if let Some(previous) = self.grid.downgrade_bridge(line) {
+            self.last_removed = Some((line, previous));
+            self.hint = None;
+            self.unlock_unsatisfied(line);
+            self.moves += 1;
+            self.history.push(Move {
+                line,
+                kind: MoveKind::Remove,
+                at_seconds: self.time_elapsed,
+            });
+        }
+    }
+
+    /// A click on a placed bridge at `at_ms`. With `confirm` the first click only arms the bridge,
+    /// and it's a second click on it soon after that takes one strand away.
+    fn click_bridge(&mut self, line: BridgeLine, confirm: bool, at_ms: f64) {
+        if self.grid.locked_bridges.contains(&line) {
+            self.reject(&HashiError::LockedBridge { line });
+            return;
+        }
+        let double_click = self.armed_removal.is_some_and(|(armed, armed_at)| {
+            armed == line && at_ms - armed_at <= DOUBLE_CLICK_MS as f64
+        });
+        if confirm && !double_click {
+            self.armed_removal = Some((line, at_ms));
+            return;
+        }
+        self.armed_removal = None;
+        self.remove_one_bridge(line);
+    }
+
+    /// Mark a line as "no bridge here", or clear the mark if it is already there
+    fn toggle_annotation(&mut self, line: BridgeLine) {
+        if !self.annotations.remove(&line) {
+            self.annotations.insert(line);
+        }
+    }
+
+    /// Explain to the player why a bridge they tried couldn't be built
+    fn reject(&mut self, error: &HashiError) {
+        self.toast(rejection_message(error));
+    }
+
+    /// Show a message in the toast, starting its fade afresh
+    fn toast(&mut self, message: &'static str) {
+        let count = self.rejection.map_or(0, |(count, _)| count);
+        self.rejection = Some((count + 1, message));
+    }
+
+    /// The same puzzle from the start: no bridges, moves or time, but any challenge still set.
+    /// The page is already on this puzzle's route, so this stands in for navigating to it again.
+    fn restart(&self) -> GameState {
+        let mut restarted = GameState {
+            grid: self.grid.clone().wipe_bridges(),
+            challenge_time: self.challenge_time,
+            challenge_from_first_move: self.challenge_from_first_move,
+            challenge_moves: self.challenge_moves,
+            puzzle_id: self.puzzle_id,
+            difficulty: self.difficulty,
+            tutorial: self.tutorial.map(|_| TutorialStep::SelectIsland),
+            viewport: self.viewport,
+            place_double: self.place_double,
+            auto_lock: self.auto_lock,
+            warn_wrong_moves: self.warn_wrong_moves,
+            timer_on_first_move: self.timer_on_first_move,
+            encouragement_dismissed: self.encouragement_dismissed,
+            hand_made: self.hand_made,
+            seed_settings: self.seed_settings,
+            has_timer: self.has_timer,
+            ..GameState::default()
+        };
+        if self.opening_filled {
+            restarted.fill_opening();
+        }
+        restarted
+    }
+
+    /// Build the bridges each clue forces on its own, as replayable moves the player isn't charged for
+    fn fill_opening(&mut self) {
+        for (line, count) in self.grid.opening_bridges() {
+            for _ in 0..count {
+                // a broken puzzle could force crossing bridges, the first one built wins
+                let _ = self.place_bridge(line);
+            }
+        }
+        self.moves = 0;
+        // bridges built for the player don't count as their first move
+        self.started = false;
+        self.opening_filled = true;
+    }
+
+    /// The line the last move built a bridge on, if the last move built one rather than took one away
+    fn last_placed(&self) -> Option<BridgeLine> {
+        self.history
+            .last()
+            .filter(|m| matches!(m.kind, MoveKind::Add | MoveKind::Upgrade))
+            .map(|m| m.line)
+    }
+
+    /// Key this puzzle's best time is kept under: a hand-made puzzle's code, otherwise everything
+    /// generating it depends on, and whether the timer waited for the first move
+    fn puzzle_key(&self) -> String {
+        let (puzzle, seed_settings) = if self.hand_made {
+            (self.grid.to_code(), SeedSettings::default())
+        } else {
+            (
+                format!(
+                    "{}x{}/{}/{}/{:?}",
+                    self.grid.width,
+                    self.grid.height,
+                    self.puzzle_id,
+                    self.difficulty,
+                    self.grid.variant
+                ),
+                self.seed_settings,
+            )
+        };
+        // a time from the first move is shorter than one from the puzzle appearing, so they're kept apart
+        let query = QueryParams {
+            timer_on_first_move: self.timer_on_first_move.then_some(1),
+            ..QueryParams::seeded(seed_settings)
+        }
+        .to_query();
+        if query.is_empty() {
+            puzzle
+        } else {
+            format!("{}&{}", puzzle, query)
+        }
+    }
+
+    /// Whether the timer is running: the puzzle is in play, and when the timer waits for the
+    /// first move, a bridge has been built
+    fn is_timing(&self) -> bool {
+        !self.grid.is_complete() && !self.surrendered && (self.started || !self.timer_on_first_move)
+    }
+
+    /// Drop the selection and any shudder left on it, like tapping away from the islands
+    fn deselect(&mut self) {
+        self.selected = None;
+        self.shuddered_island = None;
+    }
+
+    /// Give up on the puzzle and replace the player's bridges with the solver's
+    fn surrender(&mut self) {
+        self.surrendered = true;
+        self.selected = None;
+        self.bridge_menu = None;
+        self.check_report = None;
+        self.marking = false;
+        self.last_removed = None;
+        self.hint = None;
+
+        match self.grid.solve() {
+            Some(bridges) => self.grid.bridges = bridges,
+            None => self.solution_missing = true,
+        }
+    }
+
+    /// Describe exactly what is still wrong with the player's bridges
+    fn completion_report(&self) -> CompletionReport {
+        let unsatisfied = self
+            .grid
+            .islands
+            .iter()
+            .filter(|(position, island)| self.grid.degree(**position) != island.required_bridges)
+            .map(|(position, _)| *position)
+            .collect();
+
+        CompletionReport {
+            unsatisfied,
+            connected: self.grid.is_connected(),
+        }
+    }
 }
 
 impl Default for GameState {
@@ -25,8 +742,97 @@ impl Default for GameState {
             shuddered_island: None,
             time_elapsed: 0,
             challenge_time: None,
+            challenge_from_first_move: false,
+            moves: 0,
+            history: Vec::new(),
+            challenge_moves: None,
+            puzzle_id: 0,
+            difficulty: Difficulty::default(),
+            check_report: None,
+            bridge_menu: None,
+            hovered: None,
+            conflict: None,
+            surrendered: false,
+            solution_missing: false,
+            annotations: BTreeSet::new(),
+            marking: false,
+            place_double: false,
+            last_removed: None,
+            tutorial: None,
+            generation_error: None,
+            viewport: Viewport::default(),
+            hint: None,
+            locked: BTreeSet::new(),
+            auto_lock: false,
+            warn_wrong_moves: false,
+            timer_on_first_move: false,
+            started: false,
+            encouragement_dismissed: false,
+            opening_filled: false,
+            hand_made: false,
+            seed_settings: SeedSettings::default(),
+            has_timer: true,
+            armed_removal: None,
+            rejection: None,
+            inspected: None,
+        }
+    }
+}
+
+/// Colors used to draw the board
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Palette {
+    pub(crate) satisfied: &'static str,
+    pub(crate) selected: &'static str,
+    pub(crate) error: &'static str,
+    pub(crate) reachable: &'static str,
+    pub(crate) island_fill: &'static str,
+    pub(crate) island_stroke: &'static str,
+    pub(crate) text: &'static str,
+    pub(crate) bridge: &'static str,
+}
+
+impl Palette {
+    pub(crate) fn for_settings(settings: &Settings) -> Self {
+        let mut palette = if settings.colorblind {
+            // Okabe-Ito hues, distinguishable with the common forms of colorblindness
+            Palette {
+                satisfied: "#56B4E9",
+                selected: "#E69F00",
+                error: "#D55E00",
+                reachable: "#009E73",
+                ..Palette::LIGHT
+            }
+        } else {
+            Palette::LIGHT
+        };
+
+        if settings.dark_mode {
+            palette.island_fill = "#2b2b2b";
+            palette.island_stroke = "#bbbbbb";
+            palette.text = "#eeeeee";
+            palette.bridge = "#dddddd";
+            // darker fills so the light clue text stays readable
+            palette.satisfied = if settings.colorblind {
+                "#0072B2"
+            } else {
+                "#558B2F"
+            };
         }
+
+        palette
     }
+
+    const LIGHT: Palette = Palette {
+        satisfied: "#8BC34A",
+        selected: "#2196F3",
+        error: "#f44336",
+        reachable: "#8BC34A",
+        island_fill: "#FFFFFF",
+        island_stroke: "#000000",
+        text: "#000000",
+        bridge: "black",
+    };
 }
 
 #[derive(Properties, PartialEq)]
@@ -34,60 +840,352 @@ pub struct GameProps {
     pub puzzle_id: u64,
     pub width: u8,
     pub height: u8,
+    /// Optional label shown in the controls, e.g. the date of a daily puzzle
+    #[prop_or_default]
+    pub title: Option<AttrValue>,
+    /// Fixed difficulty for puzzles everyone should share, instead of the player's setting
+    #[prop_or_default]
+    pub difficulty: Option<Difficulty>,
+    /// Play the fixed tutorial puzzle with step by step prompts instead of a generated one
+    #[prop_or_default]
+    pub tutorial: bool,
+    /// Play this hand-made puzzle, from a puzzle code, instead of generating one from the seed
+    #[prop_or_default]
+    pub puzzle: Option<HashiGrid>,
+    /// Called once the puzzle is solved, not counting a surrender
+    #[prop_or_default]
+    pub on_solved: Callback<()>,
+    /// Extra line for the victory screen, e.g. a daily streak
+    #[prop_or_default]
+    pub victory_note: Option<AttrValue>,
+    /// Show only the board, for embedding in another page. Also set by `embed=1` in the query.
+    #[prop_or_default]
+    pub embed: bool,
+    /// Generate the puzzle with these rules, e.g. bridges wrapping round the edges
+    #[prop_or_default]
+    pub variant: Variant,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
-struct QueryParams {
+/// Read one query parameter, taking a value that doesn't parse as left out, so a mangled
+/// parameter in a shared link doesn't throw away the rest of it
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(T::deserialize(deserializer).ok())
+}
+
+/// Unknown parameters are ignored, and each known one is read on its own by `lenient`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct QueryParams {
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
     challenge_time: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    challenge_moves: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    difficulty: Option<Difficulty>,
+    /// `1` shows just the board, without controls or links away from it
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    embed: Option<u8>,
+    /// `1` with `embed` shows the puzzle already solved
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    solved: Option<u8>,
+    /// Generator the seed is generated with, when not the standard one
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    generator: Option<GenAlgo>,
+    /// `1` when the seed is laid out with half-turn symmetry
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    symmetric: Option<u8>,
+    /// `1` when the seed's clues are varied so fewer in a line match
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    vary_clues: Option<u8>,
+    /// `1` when the challenge's time was counted from its first move
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    timer_on_first_move: Option<u8>,
+}
+
+impl QueryParams {
+    /// Query string for a link challenging someone else to beat this result on the same puzzle
+    fn challenge(
+        elapsed_seconds: u32,
+        moves: u32,
+        difficulty: Difficulty,
+        seed_settings: SeedSettings,
+        timer_on_first_move: bool,
+    ) -> String {
+        QueryParams {
+            challenge_time: Some(elapsed_seconds),
+            challenge_moves: Some(moves),
+            timer_on_first_move: timer_on_first_move.then_some(1),
+            ..QueryParams::new_puzzle(difficulty, seed_settings)
+        }
+        .to_query()
+    }
+
+    /// Query naming just the generator settings changed from the standard ones, empty for a standard puzzle
+    fn seeded(seed_settings: SeedSettings) -> Self {
+        QueryParams {
+            generator: (seed_settings.generator != GenAlgo::default())
+                .then_some(seed_settings.generator),
+            symmetric: seed_settings.symmetric.then_some(1),
+            vary_clues: seed_settings.vary_clues.then_some(1),
+            ..QueryParams::default()
+        }
+    }
+
+    /// Query for a new random puzzle at the given difficulty, with the generator settings it's made with
+    pub(crate) fn new_puzzle(difficulty: Difficulty, seed_settings: SeedSettings) -> Self {
+        QueryParams {
+            difficulty: Some(difficulty),
+            ..QueryParams::seeded(seed_settings)
+        }
+    }
+
+    /// Query for a page of puzzles shared by link, like a gallery or a printed sheet, naming just its
+    /// difficulty. Pages are made with the standard generator and take nothing from the viewer's
+    /// settings, so the same link is the same page whoever opens it
+    pub(crate) fn page(difficulty: Difficulty) -> Self {
+        QueryParams {
+            difficulty: Some(difficulty),
+            ..QueryParams::default()
+        }
+    }
+
+    /// The difficulty the link names, the default for a link from before links named one
+    pub(crate) fn difficulty(&self) -> Difficulty {
+        self.difficulty.unwrap_or_default()
+    }
+
+    /// The parameters that are set, as a query string without the leading `?`
+    pub(crate) fn to_query(&self) -> String {
+        serde_urlencoded::to_string(self).expect("query parameters are plain values")
+    }
+
+    /// The generator settings the link asks for, standard ones where it names none
+    pub(crate) fn seed_settings(&self) -> SeedSettings {
+        SeedSettings {
+            generator: self.generator.unwrap_or_default(),
+            symmetric: self.symmetric == Some(1),
+            vary_clues: self.vary_clues == Some(1),
+        }
+    }
+}
+
+/// The page for a generated puzzle, on the route for its variant
+fn puzzle_route(width: u8, height: u8, id: u64, variant: Variant) -> Route {
+    match variant {
+        Variant::Classic => Route::Game { width, height, id },
+        Variant::Toroidal => Route::Torus { width, height, id },
+    }
+}
+
+/// A fresh puzzle with the same size, difficulty and variant as the one being played, with any
+/// challenge dropped, made with the player's own generator settings
+fn next_puzzle(
+    width: u8,
+    height: u8,
+    difficulty: Difficulty,
+    variant: Variant,
+    seed_settings: SeedSettings,
+) -> (Route, QueryParams) {
+    (
+        puzzle_route(width, height, rand::random::<u64>(), variant),
+        QueryParams::new_puzzle(difficulty, seed_settings),
+    )
+}
+
+/// Warning for when a generated grid isn't the size its link asked for, which would leave links
+/// to it pointing at a different board
+fn size_mismatch(requested: (u8, u8), grid: &HashiGrid) -> Option<String> {
+    let actual = (grid.width, grid.height);
+    (requested != actual).then(|| {
+        format!(
+            "Asked for a {}x{} puzzle but generated {}x{}, links will use {}x{}",
+            requested.0, requested.1, actual.0, actual.1, actual.0, actual.1
+        )
+    })
+}
+
+/// Callback for every "next" or "new" puzzle button, so none of them forget a setting
+fn on_next_puzzle(
+    navigator: &Navigator,
+    width: u8,
+    height: u8,
+    difficulty: Difficulty,
+    variant: Variant,
+) -> Callback<MouseEvent> {
+    let navigator = navigator.clone();
+    Callback::from(move |_| {
+        let seed_settings = Settings::load().seed_settings();
+        let (route, query) = next_puzzle(width, height, difficulty, variant, seed_settings);
+        let _ = navigator.push_with_query(&route, &query);
+    })
 }
 
 #[function_component(Game)]
 pub fn game(props: &GameProps) -> Html {
     let state: UseStateHandle<GameState> = use_state(GameState::default);
-    let navigator = use_navigator().unwrap();
+    let settings = use_state(Settings::load);
+    let show_rules = use_state(|| false);
+    // read once per puzzle, a new best is recorded by the victory overlay
+    let puzzle_best = use_memo(state.puzzle_key(), |key| PuzzleTimes::load().best(key));
+    let press = use_mut_ref(IslandPress::default);
+    let board = use_node_ref();
+    let board_size = use_size(board.clone());
+    let scale = units_per_px(shown_extent(&state.grid, &settings), board_size);
+    // an embedded board may be shown outside a router, so only the full page needs a navigator
+    let navigator = use_navigator();
     let puzzle_id = props.puzzle_id;
     let width = props.width;
     let height = props.height;
-    let query_params = match use_location() {
-        Some(loc) => match loc.query::<QueryParams>() {
-            Ok(params) => params,
-            Err(_) => QueryParams {
-                challenge_time: None,
-            },
-        },
-        None => QueryParams {
-            challenge_time: None,
-        },
+    let tutorial = props.tutorial;
+    let query_params = use_location()
+        .and_then(|loc| loc.query::<QueryParams>().ok())
+        .unwrap_or_default();
+    let difficulty = props
+        .difficulty
+        .or(query_params.difficulty)
+        .unwrap_or(settings.difficulty);
+    let embed = props.embed || query_params.embed == Some(1);
+    let show_solved = embed && query_params.solved == Some(1);
+    // puzzles everyone shares, like the daily, stay on the standard generator so they match, any
+    // other seed is generated as its link says so the link is always the same puzzle
+    let seed_settings = if props.difficulty.is_some() {
+        SeedSettings::default()
+    } else {
+        query_params.seed_settings()
+    };
+    let options = GenerationOptions {
+        variant: props.variant,
+        ..seed_settings.options(difficulty)
     };
 
     {
         let state = state.clone();
+        let auto_lock = settings.lock_satisfied;
+        let warn_wrong_moves = settings.warn_wrong_moves;
+        let timer_on_first_move = settings.timer_on_first_move;
+        let fill_opening = settings.fill_opening;
+        let puzzle = props.puzzle.clone();
 
         use_effect_with(puzzle_id, move |_| {
             {
-                let hashi_grid = hashi::HashiGrid::generate_with_seed(width, height, puzzle_id)
-                    .unwrap() // TODO: handle error properly
-                    .wipe_bridges();
-
-                state.set(GameState {
-                    grid: hashi_grid,
-                    selected: None,
-                    shuddered_island: None,
-                    time_elapsed: 0,
-                    challenge_time: query_params.challenge_time,
+                let hand_made = puzzle.is_some();
+                let generated = if tutorial {
+                    Ok(tutorial_grid())
+                } else if let Some(puzzle) = puzzle {
+                    Ok(puzzle.wipe_bridges())
+                } else {
+                    hashi::HashiGrid::generate_with_options(width, height, puzzle_id, &options)
+                        .map(HashiGrid::wipe_bridges)
+                };
+                state.set(match generated {
+                    Ok(hashi_grid) => {
+                        if let Some(warning) = size_mismatch((width, height), &hashi_grid)
+                            && !hand_made
+                        {
+                            gloo::console::warn!(warning);
+                        }
+                        let mut s = GameState {
+                            challenge_time: query_params.challenge_time,
+                            challenge_from_first_move: query_params.timer_on_first_move == Some(1),
+                            challenge_moves: query_params.challenge_moves,
+                            tutorial: tutorial.then_some(TutorialStep::SelectIsland),
+                            auto_lock,
+                            warn_wrong_moves,
+                            timer_on_first_move,
+                            hand_made,
+                            seed_settings,
+                            ..GameState::new(hashi_grid, puzzle_id, difficulty)
+                        };
+                        if show_solved {
+                            // the same read-only solved board as giving up, without the banner
+                            s.surrender();
+                        } else if fill_opening && !tutorial {
+                            s.fill_opening();
+                        }
+                        s
+                    }
+                    Err(e) => GameState {
+                        generation_error: Some(e.to_string()),
+                        ..GameState::default()
+                    },
                 });
             }
             || ()
         });
     }
 
+    {
+        let on_solved = props.on_solved.clone();
+        use_effect_with(state.is_won(), move |won| {
+            if *won {
+                on_solved.emit(());
+            }
+            || ()
+        });
+    }
+
+    // move the tutorial on whenever the board shows the current step has been done
+    {
+        let state = state.clone();
+        use_effect(move || {
+            if let Some(step) = state.tutorial {
+                let next = step.advance(&state.grid, state.selected);
+                if next != step {
+                    let mut s = (*state).clone();
+                    s.tutorial = Some(next);
+                    state.set(s);
+                }
+            }
+            || ()
+        });
+    }
+
     // Timer using yew_hooks
     {
         let state = state.clone();
         use_interval(
             move || {
                 let mut s = (*state).clone();
-                if !s.grid.is_complete() {
+                if s.is_timing() {
                     s.time_elapsed += 1;
                     state.set(s);
                 }
@@ -96,6 +1194,19 @@ pub fn game(props: &GameProps) -> Html {
         );
     }
 
+    if embed {
+        return html! {
+            <div class={classes!("game-wrapper", "embed", settings.dark_mode.then_some("dark"))}>
+                { if let Some(message) = &state.generation_error {
+                    html! { <div class="check-result check-problem">{ message }</div> }
+                } else {
+                    render_game(&state, &settings, &press, &board, scale, None, true)
+                }}
+            </div>
+        };
+    }
+
+    let navigator = navigator.unwrap();
     let on_back = {
         let navigator = navigator.clone();
         Callback::from(move |_| {
@@ -103,29 +1214,288 @@ pub fn game(props: &GameProps) -> Html {
         })
     };
 
-    let on_new_puzzle = {
-        let navigator = navigator.clone();
+    if let Some(message) = &state.generation_error {
+        return html! {
+            <div class="rules-container">
+                <h1>{"Couldn't generate this puzzle"}</h1>
+                <p>{ format!("{}. Try one of the sizes on the home screen.", message) }</p>
+                <button onclick={on_back} class="btn btn-back">
+                    {"Back to Home"}
+                </button>
+            </div>
+        };
+    }
+
+    // the grid's own size, in case it ever differs from the route's
+    let (width, height) = state.board_size((width, height));
+    let on_new_puzzle = on_next_puzzle(&navigator, width, height, difficulty, props.variant);
+
+    let on_copy_text = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            copy_to_clipboard(state.grid.to_string());
+        })
+    };
+
+    let on_save_image = {
+        let state = state.clone();
+        let settings = settings.clone();
         Callback::from(move |_| {
-            navigator.push(&Route::Game {
-                width,
-                height,
-                id: rand::random::<u64>(),
+            // always the light colors, the image is for printing and pasting onto white pages
+            let palette = Palette::for_settings(&Settings {
+                dark_mode: false,
+                ..(*settings).clone()
             });
+            let look = BridgeLook::new(settings.bridge_style, 0.0);
+            let file_name = format!(
+                "hashi-{}x{}-{}.svg",
+                state.grid.width, state.grid.height, state.puzzle_id
+            );
+            download(
+                &file_name,
+                &board_svg(&state.grid, &palette, look),
+                "image/svg+xml",
+            );
         })
     };
 
-    html! {
-        <div class="game-wrapper">
-            <div class="game-controls">
-                <button onclick={on_back} class="btn btn-game-large">
-                    {"← Back"}
-                </button>
-                <button onclick={on_new_puzzle} class="btn btn-game-large success">
-                    {"🎲 Next"}
-                </button>
+    // the seed alone is a different puzzle under other generator settings, so a link is copied
+    // that carries them along with it
+    let on_copy_seed = copy_link(
+        &navigator,
+        puzzle_route(
+            state.grid.width,
+            state.grid.height,
+            state.puzzle_id,
+            props.variant,
+        ),
+        QueryParams::new_puzzle(state.difficulty, state.seed_settings).to_query(),
+    );
+
+    let on_restart = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            state.set(state.restart());
+        })
+    };
+
+    let on_check = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            let mut s = (*state).clone();
+            s.check_report = Some(s.completion_report());
+            state.set(s);
+        })
+    };
+
+    let on_toggle_zen = {
+        let settings = settings.clone();
+        Callback::from(move |_| {
+            let mut s = (*settings).clone();
+            s.show_timer = !s.show_timer;
+            s.save();
+            settings.set(s);
+        })
+    };
+
+    let on_zoom = |factor: f64| {
+        let state = state.clone();
+        let settings = settings.clone();
+        Callback::from(move |_| {
+            let mut s = (*state).clone();
+            s.viewport.zoom_by(factor, shown_extent(&s.grid, &settings));
+            state.set(s);
+        })
+    };
+    let on_zoom_in = on_zoom(2.0);
+    let on_zoom_out = on_zoom(0.5);
+
+    let on_hint = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            let mut s = (*state).clone();
+            s.take_hint();
+            state.set(s);
+        })
+    };
+
+    let on_surrender = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            let mut s = (*state).clone();
+            s.surrender();
+            state.set(s);
+        })
+    };
+
+    let on_toggle_marking = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            let mut s = (*state).clone();
+            s.marking = !s.marking;
+            s.selected = None;
+            state.set(s);
+        })
+    };
+
+    let on_toggle_double = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            let mut s = (*state).clone();
+            s.place_double = !s.place_double;
+            state.set(s);
+        })
+    };
+
+    let on_dismiss_encouragement = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            let mut s = (*state).clone();
+            s.encouragement_dismissed = true;
+            state.set(s);
+        })
+    };
+
+    // the rules open over the board, which carries on underneath
+    let on_toggle_rules = {
+        let show_rules = show_rules.clone();
+        Callback::from(move |_| show_rules.set(!*show_rules))
+    };
+
+    let (satisfied, total) = state.satisfied_count();
+    let can_surrender = !state.surrendered && !state.grid.is_complete();
+
+    html! {
+        <div class={classes!("game-wrapper", settings.dark_mode.then_some("dark"))}>
+            <div class="game-controls">
+                <button onclick={confirm_leave(&state, on_back.clone())} class="btn btn-game-large">
+                    {"← Back"}
+                </button>
+                <button
+                    onclick={confirm_leave(&state, on_new_puzzle.clone())}
+                    class="btn btn-game-large success"
+                >
+                    {"🎲 Next"}
+                </button>
+                <button onclick={on_restart} class="btn btn-game-large">
+                    {"🔁 Restart"}
+                </button>
+                <button onclick={on_copy_text} class="btn btn-game-large">
+                    {"📋 Copy as text"}
+                </button>
+                <button onclick={on_save_image} class="btn btn-game-large">
+                    {"🖼️ Save image"}
+                </button>
+                <button onclick={on_check} class="btn btn-game-large">
+                    {"✓ Check"}
+                </button>
+                <button
+                    onclick={on_toggle_marking}
+                    class={classes!("btn", "btn-game-large", state.marking.then_some("active"))}
+                    disabled={state.surrendered}
+                >
+                    {"✕ Mark no bridge"}
+                </button>
+                <button
+                    onclick={on_toggle_double}
+                    class={classes!("btn", "btn-game-large", state.place_double.then_some("active"))}
+                    disabled={state.surrendered}
+                    title="What picking two islands builds"
+                >
+                    { if state.place_double { "═ Placing doubles" } else { "─ Placing singles" } }
+                </button>
+                <button
+                    onclick={on_toggle_zen}
+                    class={classes!("btn", "btn-game-large", (!settings.show_timer).then_some("active"))}
+                >
+                    {"🧘 Zen"}
+                </button>
+                <button onclick={on_hint} class="btn btn-game-large" disabled={!can_surrender}>
+                    { if matches!(state.hint, Some(Hint::Island(_))) {
+                        "💡 Show a bridge"
+                    } else {
+                        "💡 Hint"
+                    }}
+                </button>
+                <button onclick={on_surrender} class="btn btn-game-large" disabled={!can_surrender}>
+                    {"🏳 Show solution"}
+                </button>
+                <button onclick={on_toggle_rules.clone()} class="btn btn-game-large" title="Rules">
+                    {"❓"}
+                </button>
+                { if scale > MAX_UNZOOMED_UNITS_PER_PX || state.viewport.is_zoomed() {
+                    html! {
+                        <>
+                            <button onclick={on_zoom_in} class="btn btn-game-large">
+                                {"🔍 Zoom in"}
+                            </button>
+                            <button
+                                onclick={on_zoom_out}
+                                class="btn btn-game-large"
+                                disabled={!state.viewport.is_zoomed()}
+                            >
+                                {"🔍 Zoom out"}
+                            </button>
+                        </>
+                    }
+                } else {
+                    html! {}
+                }}
+                { if let Some(title) = &props.title {
+                    html! { <div class="game-title">{ title.clone() }</div> }
+                } else {
+                    html! {}
+                }}
+                <div class="game-progress">
+                    {format!("{} / {} islands", satisfied, total)}
+                </div>
+                // hand-made puzzles have no seed to share
+                { if tutorial || state.hand_made {
+                    html! {}
+                } else {
+                    html! {
+                        <button
+                            onclick={on_copy_seed}
+                            class="game-progress game-seed"
+                            title="Copy a link to this puzzle"
+                        >
+                            {format!("Seed: {} 📋", state.puzzle_id)}
+                        </button>
+                    }
+                }}
+                <div class="game-progress">
+                    { if let Some(target) = state.challenge_moves {
+                        format!("Moves: {} / {}", state.moves, target)
+                    } else {
+                        format!("Moves: {}", state.moves)
+                    }}
+                </div>
+                { if let Some(message) = state.encouragement() {
+                    html! {
+                        <button
+                            onclick={on_dismiss_encouragement}
+                            class="game-progress game-encouragement"
+                            title="Hide"
+                        >
+                            { format!("{} ✕", message) }
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
                 <div class="game-timer-container">
+                    { match *puzzle_best {
+                        Some(best) if settings.show_timer => html! {
+                            <div class="challenge-time">
+                                {format!("Your best on this puzzle: {}", format_time(best))}
+                            </div>
+                        },
+                        _ => html! {},
+                    }}
                     {
-                        if let Some(ct) = state.challenge_time {
+                        if !settings.show_timer {
+                            html! {}
+                        } else if let Some(ct) = state.challenge_time {
                             let is_beating = state.time_elapsed < ct;
                             let color_class = if is_beating { "beating" } else { "not-beating" };
                             html! {
@@ -148,7 +1518,144 @@ pub fn game(props: &GameProps) -> Html {
                     }
                 </div>
             </div>
-            { render_game(&state) }
+            { render_check_report(&state) }
+            { if state.hint == Some(Hint::NoneFound) {
+                html! {
+                    <div class="check-result check-problem">
+                        {"💡 Nothing more can be worked out for certain, try ✓ Check or take a guess"}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+            { if let Some(step) = state.tutorial {
+                html! { <div class="check-result tutorial-prompt">{ step.prompt() }</div> }
+            } else {
+                html! {}
+            }}
+            { render_surrender(&state, on_new_puzzle, on_back) }
+            { render_game(&state, &settings, &press, &board, scale, props.victory_note.clone(), false) }
+            { if *show_rules {
+                let show_rules = show_rules.clone();
+                html! { <RulesOverlay on_close={Callback::from(move |_| show_rules.set(false))} /> }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
+
+fn render_check_report(state: &UseStateHandle<GameState>) -> Html {
+    let Some(report) = &state.check_report else {
+        return html! {};
+    };
+
+    if report.is_complete() {
+        return html! {
+            <div class="check-result check-ok">{"✓ Everything checks out!"}</div>
+        };
+    }
+
+    let mut problems = Vec::new();
+    if !report.unsatisfied.is_empty() {
+        problems.push(format!(
+            "{} island(s) have the wrong number of bridges",
+            report.unsatisfied.len()
+        ));
+    }
+    if !report.connected {
+        problems.push("the islands are not all connected".to_string());
+    }
+
+    html! {
+        <div class="check-result check-problem">
+            {format!("✗ Not yet: {}", problems.join(", and "))}
+        </div>
+    }
+}
+
+/// Banner shown once the player has given up, offering a way out of the read-only board
+fn render_surrender(
+    state: &UseStateHandle<GameState>,
+    on_new_puzzle: Callback<MouseEvent>,
+    on_home: Callback<MouseEvent>,
+) -> Html {
+    if !state.surrendered {
+        return html! {};
+    }
+
+    let message = if state.solution_missing {
+        "✗ No solution could be found, this puzzle was generated incorrectly"
+    } else {
+        "🏳 Here's the solution. This one doesn't count as a win."
+    };
+
+    html! {
+        <div class="check-result surrender-result">
+            <span>{ message }</span>
+            <button onclick={on_new_puzzle} class="btn btn-game-large success">
+                {"🎲 New"}
+            </button>
+            <button onclick={on_home} class="btn btn-game-large">
+                {"🏠 Home"}
+            </button>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GameBoardProps {
+    /// The puzzle to play, with its bridges wiped
+    pub grid: HashiGrid,
+    /// Seed and difficulty the puzzle came from, for the victory screen
+    pub puzzle_id: u64,
+    pub difficulty: Difficulty,
+    /// Called once the puzzle is solved
+    #[prop_or_default]
+    pub on_solved: Callback<()>,
+}
+
+/// Just the playable board for a puzzle it's given, keeping its own state and needing no router,
+/// so a page can show several at once. Give each a `key` so a different puzzle starts afresh.
+#[function_component(GameBoard)]
+pub fn game_board(props: &GameBoardProps) -> Html {
+    let settings = use_state(Settings::load);
+    let state = {
+        let auto_lock = settings.lock_satisfied;
+        let warn_wrong_moves = settings.warn_wrong_moves;
+        let fill_opening = settings.fill_opening;
+        let (grid, puzzle_id, difficulty) = (props.grid.clone(), props.puzzle_id, props.difficulty);
+        use_state(move || {
+            let mut s = GameState {
+                auto_lock,
+                warn_wrong_moves,
+                has_timer: false,
+                ..GameState::new(grid, puzzle_id, difficulty)
+            };
+            if fill_opening {
+                s.fill_opening();
+            }
+            s
+        })
+    };
+    let press = use_mut_ref(IslandPress::default);
+    let board = use_node_ref();
+    let board_size = use_size(board.clone());
+    let scale = units_per_px(shown_extent(&state.grid, &settings), board_size);
+
+    {
+        let on_solved = props.on_solved.clone();
+        use_effect_with(state.is_won(), move |won| {
+            if *won {
+                on_solved.emit(());
+            }
+            || ()
+        });
+    }
+
+    html! {
+        <div class={classes!("game-wrapper", "embed", settings.dark_mode.then_some("dark"))}>
+            { render_game(&state, &settings, &press, &board, scale, None, true) }
         </div>
     }
 }
@@ -160,13 +1667,47 @@ fn random_game_redirect() -> Html {
     }
 }
 
-fn render_game(state: &UseStateHandle<GameState>) -> Html {
-    let is_complete = state.grid.is_complete();
+fn render_game(
+    state: &UseStateHandle<GameState>,
+    settings: &Settings,
+    press: &Rc<RefCell<IslandPress>>,
+    board: &NodeRef,
+    scale: f64,
+    victory_note: Option<AttrValue>,
+    embed: bool,
+) -> Html {
+    press.borrow_mut().live = Some(state.clone());
+    let palette = Palette::for_settings(settings);
+    // zooming in shows fewer units in the same pixels
+    let scale = scale / state.viewport.zoom;
+    let hit = HitSizes::for_board(scale);
+    let look = BridgeLook::new(settings.bridge_style, scale);
+
+    let sound_effects = settings.sound_effects;
+    let play = move |effect: Sound| {
+        if sound_effects {
+            sound::play(effect);
+        }
+    };
 
     let on_island_click = {
         let state = state.clone();
+        let press = press.clone();
         Callback::from(move |currently_selected: hashi::Position| {
+            if state.surrendered {
+                return;
+            }
             let mut s = (*state).clone();
+            s.check_report = None;
+            settle_shudder(&press, &mut s);
+
+            // a locked island can't start or end a new bridge
+            if s.locked.contains(&currently_selected) && !s.marking {
+                play(Sound::Invalid);
+                s.selected = None;
+                state.set(s);
+                return;
+            }
 
             match s.selected {
                 None => s.selected = Some(currently_selected),
@@ -175,49 +1716,40 @@ fn render_game(state: &UseStateHandle<GameState>) -> Html {
                         // Is there a valid bridgeline between the two?
 
                         let proposed_bridge =
-                            match hashi::BridgeLine::new(previously_selected, currently_selected) {
-                                Err(_) => {
+                            match s.grid.line_between(previously_selected, currently_selected) {
+                                Err(e) => {
                                     // Invalid bridge (diagonal)
+                                    play(Sound::Invalid);
                                     s.shuddered_island = Some(currently_selected);
                                     s.selected = None;
-                                    state.set(s.clone());
-
-                                    // Clear shudder after 300ms
-                                    let state_for_timeout = state.clone();
-                                    gloo_timers::callback::Timeout::new(300, move || {
-                                        let mut s = (*state_for_timeout).clone();
-                                        s.shuddered_island = None;
-                                        s.selected = None;
-                                        state_for_timeout.set(s);
-                                    })
-                                    .forget();
-
+                                    s.reject(&e);
+                                    shudder(&state, &press, s);
                                     return;
                                 }
                                 Ok(b) => b,
                             };
 
-                        match s.grid.add_bridge(proposed_bridge) {
+                        if s.marking {
+                            s.toggle_annotation(proposed_bridge);
+                            s.selected = None;
+                            state.set(s);
+                            return;
+                        }
+
+                        match s.place_chosen(proposed_bridge) {
                             Ok(_) => {
+                                play(Sound::Place);
                                 s.selected = None;
                                 s.shuddered_island = None;
                             }
-                            Err(_) => {
+                            Err(e) => {
                                 // Invalid bridge placement - shudder the island
+                                play(Sound::Invalid);
                                 s.shuddered_island = Some(currently_selected);
+                                s.conflict = e.conflict_position();
                                 s.selected = None;
-
-                                state.set(s.clone());
-
-                                // Clear shudder after 300ms
-                                let state_for_timeout = state.clone();
-                                gloo_timers::callback::Timeout::new(300, move || {
-                                    let mut s = (*state_for_timeout).clone();
-                                    s.shuddered_island = None;
-                                    s.selected = None;
-                                    state_for_timeout.set(s);
-                                })
-                                .forget();
+                                s.reject(&e);
+                                shudder(&state, &press, s.clone());
                             }
                         }
                     } else {
@@ -231,15 +1763,182 @@ fn render_game(state: &UseStateHandle<GameState>) -> Html {
         })
     };
 
-    let width = state.grid.width as i32 * 100;
-    let height = state.grid.height as i32 * 100;
+    let on_cycle = {
+        let state = state.clone();
+        let press = press.clone();
+        Callback::from(move |line: BridgeLine| {
+            let mut s = (*state).clone();
+            s.selected = None;
+            settle_shudder(&press, &mut s);
+            if s.touches_locked(line) {
+                play(Sound::Invalid);
+                state.set(s);
+                return;
+            }
+            match s.cycle_bridge(line) {
+                Ok(()) => {
+                    play(Sound::Place);
+                    state.set(s);
+                }
+                Err(e) => {
+                    play(Sound::Invalid);
+                    s.conflict = e.conflict_position();
+                    s.reject(&e);
+                    shudder(&state, &press, s);
+                }
+            }
+        })
+    };
+
+    let (view_x, view_y, view_width, view_height) =
+        state.viewport.view_box(shown_extent(&state.grid, settings));
+
+    // every bridge counts towards both its islands' clues
+    let solution_moves = state
+        .grid
+        .islands
+        .values()
+        .map(|island| island.required_bridges as u32)
+        .sum::<u32>()
+        / 2;
+
+    let on_background_click = {
+        let state = state.clone();
+        Callback::from(move |e: MouseEvent| {
+            // only taps on the board itself, islands and bridges are drawn over it and take their own
+            if e.target() != e.current_target() {
+                return;
+            }
+            if state.selected.is_some() || state.shuddered_island.is_some() {
+                let mut s = (*state).clone();
+                s.deselect();
+                state.set(s);
+            }
+        })
+    };
+
+    // a second finger landing anywhere on the board while dragging from an island asks for a double
+    let on_board_pointer_down = {
+        let press = press.clone();
+        Callback::from(move |e: PointerEvent| {
+            let mut press = press.borrow_mut();
+            if press
+                .drag
+                .is_some_and(|drag| drag.pointer_id != e.pointer_id())
+            {
+                press.double = true;
+            }
+        })
+    };
+
+    // once a press has moved it's a drag, and won't open the bridge menu
+    let on_board_pointer_move = {
+        let press = press.clone();
+        Callback::from(move |e: PointerEvent| {
+            let mut press = press.borrow_mut();
+            if let Some(drag) = press.drag
+                && drag.pointer_id == e.pointer_id()
+                && ((e.client_x() - drag.client.0).abs() > DRAG_THRESHOLD_PX
+                    || (e.client_y() - drag.client.1).abs() > DRAG_THRESHOLD_PX)
+            {
+                press.timer = None;
+            }
+        })
+    };
+
+    let on_board_pointer_up = {
+        let state = state.clone();
+        let press = press.clone();
+        Callback::from(move |e: PointerEvent| {
+            let (drag, double) = {
+                let mut press = press.borrow_mut();
+                match press.drag {
+                    Some(drag) if drag.pointer_id == e.pointer_id() => {
+                        press.drag = None;
+                        (drag, press.double)
+                    }
+                    _ => return,
+                }
+            };
+            // touch pointers stay with the island they started on, so look for the one under the finger
+            let Some(end) = island_at(e.client_x(), e.client_y()) else {
+                return;
+            };
+            // lifting on the same island is an ordinary tap
+            if end == drag.island || state.surrendered || state.marking {
+                return;
+            }
+
+            let mut s = (*state).clone();
+            settle_shudder(&press, &mut s);
+            let line = match s.grid.line_between(drag.island, end) {
+                Ok(line) => line,
+                Err(e) => {
+                    play(Sound::Invalid);
+                    s.reject(&e);
+                    state.set(s);
+                    return;
+                }
+            };
+            if s.touches_locked(line) {
+                play(Sound::Invalid);
+                state.set(s);
+                return;
+            }
+            match s.drag_bridge(line, double) {
+                Ok(_) => {
+                    play(Sound::Place);
+                    state.set(s);
+                }
+                Err(e) => {
+                    play(Sound::Invalid);
+                    s.conflict = e.conflict_position();
+                    s.reject(&e);
+                    shudder(&state, &press, s);
+                }
+            }
+        })
+    };
+
+    // the browser took the touch over, or the mouse left the board, so the drag is off
+    let on_board_pointer_cancel = {
+        let press = press.clone();
+        Callback::from(move |e: PointerEvent| {
+            let mut press = press.borrow_mut();
+            if press
+                .drag
+                .is_some_and(|drag| drag.pointer_id == e.pointer_id())
+            {
+                press.drag = None;
+                press.timer = None;
+            }
+        })
+    };
+
+    let on_recenter = {
+        let state = state.clone();
+        Callback::from(move |center: (f64, f64)| {
+            let mut s = (*state).clone();
+            s.viewport.center = center;
+            state.set(s);
+        })
+    };
 
     html! {
         <div class="game-container">
             <svg
-                viewBox={format!("-100 -100 {} {}", width + 100, height + 100)}
+                ref={board.clone()}
+                viewBox={format!("{} {} {} {}", view_x, view_y, view_width, view_height)}
                 preserveAspectRatio="xMidYMid meet"
                 class="game-svg"
+                role="group"
+                aria-label="Puzzle board"
+                onclick={on_background_click}
+                onpointerdown={on_board_pointer_down}
+                onpointermove={on_board_pointer_move}
+                onpointerup={on_board_pointer_up}
+                onpointercancel={on_board_pointer_cancel.clone()}
+                onpointerleave={on_board_pointer_cancel}
             >
                 <defs>
                     <filter id="selectedGlow">
@@ -247,17 +1946,89 @@ fn render_game(state: &UseStateHandle<GameState>) -> Html {
                             dx="0"
                             dy="0"
                             stdDeviation="5"
-                            flood-color="#2196F3"
+                            flood-color={palette.selected}
                             flood-opacity="0.7"
                         />
                     </filter>
                 </defs>
-                { render_bridges(state) }
-                { render_islands(state, on_island_click) }
+                { if settings.show_grid {
+                    render_grid_lines(&state.grid, &palette, scale)
+                } else {
+                    html! {}
+                }}
+                { render_annotations(state, &palette) }
+                { render_bridges(state, &palette, press, hit, look, settings.double_click_remove) }
+                { if settings.tap_to_cycle && !state.marking && !state.surrendered {
+                    render_cycle_targets(state, hit, on_cycle)
+                } else {
+                    html! {}
+                }}
+                { render_blocked_preview(state, &palette) }
+                { render_conflict(state, &palette) }
+                { render_islands(state, &palette, settings, press, hit, on_island_click) }
+                { render_bridge_menu(state, &palette) }
             </svg>
+            <div class="sr-only" aria-live="polite">{ state.announcement() }</div>
+            { render_rejection(state) }
+            { render_inspected(state) }
+
+            { if state.viewport.is_zoomed() {
+                html! {
+                    <Minimap
+                        grid={state.grid.clone()}
+                        viewport={state.viewport}
+                        extent={shown_extent(&state.grid, settings)}
+                        on_recenter={on_recenter}
+                    />
+                }
+            } else {
+                html! {}
+            }}
 
-            { if is_complete {
-                html! { <VictoryOverlay next_width={state.grid.width} next_height={state.grid.height} elapsed_seconds={state.time_elapsed} challenge_time={state.challenge_time} /> }
+            { if state.is_won() {
+                html! {
+                    <VictoryOverlay
+                        next_width={state.grid.width}
+                        next_height={state.grid.height}
+                        puzzle_id={state.puzzle_id}
+                        difficulty={state.difficulty}
+                        elapsed_seconds={state.time_elapsed}
+                        // zen mode drops the time comparison along with the timer
+                        challenge_time={state.challenge_time.filter(|_| settings.show_timer)}
+                        // a board without a timer has no time to show
+                        challenge_from_first_move={state.challenge_from_first_move}
+                        timer_on_first_move={state.timer_on_first_move}
+                        show_timer={settings.show_timer && state.has_timer}
+                        record={state.has_timer}
+                        moves={state.moves}
+                        challenge_moves={state.challenge_moves}
+                        solution_moves={solution_moves}
+                        replay={AttrValue::from(replay_query(
+                            state.difficulty,
+                            state.seed_settings,
+                            &state.history,
+                        ))}
+                        note={victory_note}
+                        embed={embed}
+                        assisted={state.opening_filled}
+                        code={state.hand_made.then(|| AttrValue::from(state.grid.share_code()))}
+                        puzzle_key={state.puzzle_key()}
+                        seed_settings={state.seed_settings}
+                        variant={state.grid.variant}
+                        grid={state.grid.clone()}
+                        result_image={result_svg(
+                            &state.grid,
+                            &palette,
+                            BridgeLook::new(settings.bridge_style, 0.0),
+                            &result_summary(
+                                state.grid.width,
+                                state.grid.height,
+                                settings.show_timer.then_some(state.time_elapsed),
+                                state.moves,
+                            ),
+                        )}
+                    />
+                }
             } else {
                 html! {}
             }}
@@ -265,41 +2036,297 @@ fn render_game(state: &UseStateHandle<GameState>) -> Html {
     }
 }
 
+#[derive(Properties, PartialEq)]
+struct RulesOverlayProps {
+    on_close: Callback<()>,
+}
+
+/// The rules in a dialog over the board, closed by its button, Escape or a click beside it
+#[function_component(RulesOverlay)]
+fn rules_overlay(props: &RulesOverlayProps) -> Html {
+    let (modal, onkeydown) = use_modal_keys(props.on_close.clone());
+    let on_close = props.on_close.reform(|_: MouseEvent| ());
+
+    html! {
+        <div class="rules-overlay" onclick={on_close.clone()} ref={modal} onkeydown={onkeydown}>
+            <div
+                class="rules-modal"
+                role="dialog"
+                aria-modal="true"
+                aria-label="Rules"
+                onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}
+            >
+                <RulesContent />
+                <button onclick={on_close} class="btn btn-back">{"Close"}</button>
+            </div>
+        </div>
+    }
+}
+
 #[derive(Properties, PartialEq)]
 struct VictoryOverlayProps {
     next_width: u8,
     next_height: u8,
+    puzzle_id: u64,
+    difficulty: Difficulty,
     elapsed_seconds: u32,
     challenge_time: Option<u32>,
+    /// The challenge's time was counted from its first move
+    #[prop_or_default]
+    challenge_from_first_move: bool,
+    /// This solve's time was counted from its first move
+    #[prop_or_default]
+    timer_on_first_move: bool,
+    show_timer: bool,
+    /// Keep the solve's score and time as bests, off on a board whose time never ran
+    record: bool,
+    moves: u32,
+    challenge_moves: Option<u32>,
+    /// Bridges in the finished board, the fewest moves a solve can take
+    solution_moves: u32,
+    /// Query string for replaying this solve
+    replay: AttrValue,
+    #[prop_or_default]
+    note: Option<AttrValue>,
+    /// Leave out the buttons, which all lead away from an embedded board
+    #[prop_or_default]
+    embed: bool,
+    /// The puzzle started with bridges built for the player, so the score isn't kept as a best
+    #[prop_or_default]
+    assisted: bool,
+    /// Code of a hand-made puzzle, which challenge links use in place of the seed
+    #[prop_or_default]
+    code: Option<AttrValue>,
+    #[prop_or_default]
+    variant: Variant,
+    /// Generator settings of a seeded puzzle, which challenge links carry
+    #[prop_or_default]
+    seed_settings: SeedSettings,
+    /// The solved board, rated for how hard its puzzle is
+    grid: HashiGrid,
+    /// SVG of the solved board with its time and moves, attached when sharing the result
+    #[prop_or_default]
+    result_image: AttrValue,
+    /// What the best time on this particular puzzle is kept under, see `PuzzleTimes`
+    #[prop_or_default]
+    puzzle_key: AttrValue,
 }
 
-#[function_component(VictoryOverlay)]
-fn victory_overlay(props: &VictoryOverlayProps) -> Html {
-    let navigator = use_navigator().unwrap();
-    let nw = props.next_width;
-    let nh = props.next_height;
+/// One line account of a solve, under the shared image and in the shared message
+fn result_summary(width: u8, height: u8, elapsed_seconds: Option<u32>, moves: u32) -> String {
+    match elapsed_seconds {
+        Some(seconds) => format!(
+            "Solved a {}x{} Hashi in {} with {} moves",
+            width,
+            height,
+            format_time(seconds),
+            moves
+        ),
+        None => format!("Solved a {}x{} Hashi with {} moves", width, height, moves),
+    }
+}
 
-    let on_new_puzzle = {
-        let navigator = navigator.clone();
-        Callback::from(move |_| {
-            navigator.push(&Route::Game {
-                width: nw,
-                height: nh,
-                id: rand::random::<u64>(),
-            });
+/// Said beside a challenge's time when it was timed differently from this solve, as a timer
+/// waiting for the first move leaves out the time spent studying the board
+fn timer_mode_note(props: &VictoryOverlayProps) -> &'static str {
+    match (props.challenge_from_first_move, props.timer_on_first_move) {
+        (true, false) => " (theirs timed from the first move)",
+        (false, true) => " (yours timed from the first move)",
+        _ => "",
+    }
+}
+
+/// One line summary when a challenge set both a time and a move target, e.g. "Beat time ✅, missed moves ❌"
+fn challenge_summary(props: &VictoryOverlayProps) -> Option<String> {
+    let (time, moves) = props.challenge_time.zip(props.challenge_moves)?;
+    let mark = |beaten: bool| if beaten { "✅" } else { "❌" };
+    let beat_time = props.elapsed_seconds < time;
+    // matching the move target counts, an optimal solve cannot be bettered
+    let beat_moves = props.moves <= moves;
+
+    Some(format!(
+        "{} time {}{}, {} moves {}",
+        if beat_time { "Beat" } else { "Missed" },
+        mark(beat_time),
+        timer_mode_note(props),
+        if beat_moves { "beat" } else { "missed" },
+        mark(beat_moves)
+    ))
+}
+
+#[function_component(VictoryOverlay)]
+fn victory_overlay(props: &VictoryOverlayProps) -> Html {
+    let navigator = use_navigator();
+    let nw = props.next_width;
+
+    // each copied link shows a fading confirmation, numbered so copying again shows it afresh
+    let copied = use_state(|| 0u32);
+    let confirm_copy = |copy: Callback<MouseEvent>| {
+        let copied = copied.clone();
+        Callback::from(move |e: MouseEvent| {
+            copy.emit(e);
+            copied.set(*copied + 1);
         })
     };
 
-    let on_home = {
-        let navigator = navigator.clone();
-        Callback::from(move |_| {
-            navigator.push(&Route::Home);
-        })
+    // Escape does what the Home button would, an embedded board has no buttons to go anywhere
+    let (modal, onkeydown) = use_modal_keys(match &navigator {
+        Some(navigator) if !props.embed => {
+            let navigator = navigator.clone();
+            Callback::from(move |_| navigator.push(&Route::Home))
+        }
+        _ => Callback::noop(),
+    });
+
+    use_effect_with((), |_| {
+        if Settings::load().sound_effects {
+            sound::play(Sound::Victory);
+        }
+        || ()
+    });
+
+    let nh = props.next_height;
+
+    let score = efficiency_score(
+        props.solution_moves,
+        props.moves,
+        props.show_timer.then_some(props.elapsed_seconds),
+    );
+    // rated once, solving the puzzle again to see how it plays
+    let rating = *use_memo((), {
+        let grid = props.grid.clone();
+        // from the clues alone, as the puzzle was first seen
+        move |_| grid.wipe_bridges().difficulty_rating()
+    });
+
+    // read before this solve is recorded, to tell whether it set a new best
+    let previous_best = *use_memo((), move |_| BestScores::load().best(nw, nh));
+    // a solve started with bridges built for the player isn't a best either
+    let record = props.record && !props.assisted;
+    // nor is an untimed one, whose score has no time to lose, or zen mode would beat every timed best
+    let keep_score = record && props.show_timer;
+    use_effect_with((), move |_| {
+        let mut scores = BestScores::load();
+        if keep_score && scores.record(nw, nh, score) {
+            scores.save();
+        }
+        || ()
+    });
+
+    // the same goes for the time on this very puzzle, which only counts with the timer showing
+    let timed = props.show_timer && record;
+    let puzzle_key = props.puzzle_key.clone();
+    let previous_time = *use_memo(puzzle_key.clone(), |key| PuzzleTimes::load().best(key));
+    let elapsed_seconds = props.elapsed_seconds;
+    use_effect_with((), move |_| {
+        if timed {
+            let mut times = PuzzleTimes::load();
+            times.record(&puzzle_key, elapsed_seconds);
+            times.save();
+        }
+        || ()
+    });
+
+    // every button leads away from the board, which an embedded board has nowhere to go
+    let buttons = match navigator {
+        Some(navigator) if !props.embed => {
+            let on_new_puzzle = on_next_puzzle(&navigator, nw, nh, props.difficulty, props.variant);
+
+            let on_home = {
+                let navigator = navigator.clone();
+                Callback::from(move |_| {
+                    navigator.push(&Route::Home);
+                })
+            };
+
+            let challenge_route = match &props.code {
+                Some(code) => Route::Custom {
+                    code: code.to_string(),
+                },
+                None => puzzle_route(nw, nh, props.puzzle_id, props.variant),
+            };
+            let challenge_query = QueryParams::challenge(
+                props.elapsed_seconds,
+                props.moves,
+                props.difficulty,
+                props.seed_settings,
+                props.timer_on_first_move,
+            );
+            let on_challenge = confirm_copy(copy_link(
+                &navigator,
+                challenge_route.clone(),
+                challenge_query.clone(),
+            ));
+
+            let on_share = {
+                let basename = navigator.basename().unwrap_or_default().to_string();
+                let text = result_summary(
+                    nw,
+                    nh,
+                    props.show_timer.then_some(props.elapsed_seconds),
+                    props.moves,
+                );
+                let image = props.result_image.clone();
+                let copied = copied.clone();
+                Callback::from(move |_| {
+                    if let Some(url) = absolute_link(&basename, &challenge_route, &challenge_query)
+                        && share_result(&text, url, &image)
+                    {
+                        copied.set(*copied + 1);
+                    }
+                })
+            };
+
+            let on_replay = {
+                let route = Route::Replay {
+                    width: nw,
+                    height: nh,
+                    id: props.puzzle_id,
+                };
+                confirm_copy(copy_link(&navigator, route, props.replay.to_string()))
+            };
+
+            html! {
+                <div class="victory-buttons">
+                    <button onclick={on_new_puzzle} class="btn btn-victory">
+                        {"🎲 Next Puzzle"}
+                    </button>
+                    <button onclick={on_challenge} class="btn btn-victory-secondary">
+                        {"⚔️ Challenge a friend"}
+                    </button>
+                    <button onclick={on_share} class="btn btn-victory-secondary">
+                        {"📤 Share result"}
+                    </button>
+                    // replays are looked up by seed, which a hand-made puzzle doesn't have,
+                    // and regenerated as classic puzzles
+                    { if props.code.is_none() && props.variant == Variant::Classic {
+                        html! {
+                            <button onclick={on_replay} class="btn btn-victory-secondary">
+                                {"🎬 Copy replay link"}
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    <button onclick={on_home} class="btn btn-victory-secondary">
+                        {"🏠 Home"}
+                    </button>
+                </div>
+            }
+        }
+        _ => html! {},
     };
 
     html! {
         <div class="victory-overlay-background victory-overlay">
-            <div class="victory-modal">
+            <div
+                class="victory-modal"
+                role="dialog"
+                aria-modal="true"
+                aria-label="Puzzle complete"
+                ref={modal}
+                onkeydown={onkeydown}
+            >
                 <div class="victory-emoji">
                     {"🎉"}
                 </div>
@@ -310,8 +2337,53 @@ fn victory_overlay(props: &VictoryOverlayProps) -> Html {
                     {"Congratulations! All islands are connected."}
                 </p>
                 <div class="victory-time">
-                    {"Time: "}{ format_time(props.elapsed_seconds) }
+                    { if props.show_timer {
+                        format!("Time: {} · Moves: {}", format_time(props.elapsed_seconds), props.moves)
+                    } else {
+                        format!("Moves: {}", props.moves)
+                    }}
+                </div>
+                <div class="victory-score">
+                    { match previous_best {
+                        _ if !props.record => format!("Score: {}", score),
+                        _ if props.assisted => {
+                            format!("Score: {} · Started with bridges built, not counted as a best", score)
+                        }
+                        _ if !props.show_timer => {
+                            format!("Score: {} · Untimed, not counted as a best", score)
+                        }
+                        Some(best) if best >= score => {
+                            format!("Score: {} · Best for {}x{}: {}", score, nw, nh, best)
+                        }
+                        Some(_) => format!("Score: {} · 🏅 New best for {}x{}!", score, nw, nh),
+                        None => format!("Score: {}", score),
+                    }}
+                </div>
+                <div class="victory-note">
+                    { format!("Difficulty rating: {} / 100", rating) }
                 </div>
+                { match previous_time {
+                    _ if !timed => html! {},
+                    Some(best) if best <= props.elapsed_seconds => html! {
+                        <div class="victory-note">
+                            { format!("Your best on this puzzle: {}", format_time(best)) }
+                        </div>
+                    },
+                    Some(_) => html! {
+                        <div class="victory-note">{"⏱️ Your fastest solve of this puzzle!"}</div>
+                    },
+                    None => html! {},
+                }}
+                { if let Some(note) = &props.note {
+                    html! { <div class="victory-note">{ note.clone() }</div> }
+                } else {
+                    html! {}
+                }}
+                { if let Some(summary) = challenge_summary(props) {
+                    html! { <div class="victory-challenge-summary">{ summary }</div> }
+                } else {
+                    html! {}
+                }}
                 { if let Some(ct) = props.challenge_time {
                     let is_beating = props.elapsed_seconds < ct;
                     let message = if is_beating {
@@ -321,54 +2393,254 @@ fn victory_overlay(props: &VictoryOverlayProps) -> Html {
                     };
                     html! {
                         <div class={if is_beating { "victory-challenge-beating" } else { "victory-challenge-missed" }}>
-                            { message }{ " " }{ format_time(ct) }
+                            { message }{ " " }{ format_time(ct) }{ timer_mode_note(props) }
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+                { if let Some(target) = props.challenge_moves {
+                    let is_beating = props.moves <= target;
+                    let message = if is_beating {
+                        "🏆 You beat the move target of"
+                    } else {
+                        "Move target was"
+                    };
+                    html! {
+                        <div class={if is_beating { "victory-challenge-beating" } else { "victory-challenge-missed" }}>
+                            { message }{ " " }{ target }
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+                { buttons }
+                { if *copied > 0 {
+                    html! {
+                        <div class="game-toast-area">
+                            <div key={*copied} class="game-toast" role="status">{"📋 Link copied!"}</div>
                         </div>
                     }
                 } else {
                     html! {}
                 }}
-                <div class="victory-buttons">
-                    <button onclick={on_new_puzzle} class="btn btn-victory">
-                        {"🎲 Next Puzzle"}
-                    </button>
-                    <button onclick={on_home} class="btn btn-victory-secondary">
-                        {"🏠 Home"}
-                    </button>
-                </div>
             </div>
         </div>
     }
 }
 
-fn render_islands(state: &UseStateHandle<GameState>, cb: Callback<Position>) -> Html {
+/// Value of the `data-island` attribute marking an island's drawing, so a drag can find the island it ends on
+fn island_key(position: Position) -> String {
+    format!("{},{}", position.x, position.y)
+}
+
+fn parse_island_key(key: &str) -> Option<Position> {
+    let (x, y) = key.split_once(',')?;
+    Some(Position {
+        x: x.parse().ok()?,
+        y: y.parse().ok()?,
+    })
+}
+
+/// The island drawn under a point on the screen, if any
+fn island_at(client_x: i32, client_y: i32) -> Option<Position> {
+    let element = gloo::utils::document().element_from_point(client_x as f32, client_y as f32)?;
+    let island = element.closest("[data-island]").ok()??;
+    parse_island_key(&island.get_attribute("data-island")?)
+}
+
+/// A grid position in the numbering the optional grid lines show
+fn place_name(position: Position) -> String {
+    format!("column {} row {}", position.x, position.y)
+}
+
+fn bridge_name(bridge: Option<hashi::BridgeType>) -> &'static str {
+    match bridge {
+        Some(hashi::BridgeType::Single) => "Single bridge",
+        Some(hashi::BridgeType::Double) => "Double bridge",
+        None => "No bridge",
+    }
+}
+
+/// Accessible name for an island, read out in place of its drawn clue
+fn island_label(position: Position, required: u8, placed: u8) -> String {
+    let plural = if required == 1 { "" } else { "s" };
+    format!(
+        "Island at {}, needs {} bridge{}, has {}",
+        place_name(position),
+        required,
+        plural,
+        placed
+    )
+}
+
+fn render_islands(
+    state: &UseStateHandle<GameState>,
+    palette: &Palette,
+    settings: &Settings,
+    press: &Rc<RefCell<IslandPress>>,
+    hit: HitSizes,
+    cb: Callback<Position>,
+) -> Html {
+    let buildable = state.buildable_islands();
+    // the hovered or focused island's network, to show which islands are already joined up
+    let network = state
+        .hovered
+        .map(|position| state.component_of(position))
+        .filter(|component| component.len() > 1)
+        .unwrap_or_default();
+
     state
         .grid
         .islands
         .iter()
         .map(|(position, island)| {
-            let terminating_bridges = state
-                .grid
-                .bridges
-                .iter()
-                .filter(|(BridgeLine { start, end, .. }, _)| start == position || end == position)
-                .map(|(_, bridge_type)| match bridge_type {
-                    hashi::BridgeType::Single => 1,
-                    hashi::BridgeType::Double => 2,
-                })
-                .sum::<u8>();
-
-            let complete = terminating_bridges == island.required_bridges;
+            let placed = state.grid.degree(*position);
+            let complete = placed == island.required_bridges;
+            let remaining = island.required_bridges.saturating_sub(placed);
             let selected = state.selected == Some(position.to_owned());
+            let reachable = buildable.contains(position);
+            // circled for the current tutorial step or a hint
+            let target = state
+                .tutorial
+                .and_then(|step| step.highlight(state.selected))
+                == Some(*position)
+                || state.hint == Some(Hint::Island(*position));
 
-            let fill = if complete { "#8BC34A" } else { "#FFFFFF" };
-            let stroke = if selected { "#2196F3" } else { "#000000" };
+            let flagged = state
+                .check_report
+                .as_ref()
+                .is_some_and(|report| report.unsatisfied.contains(position));
+
+            let fill = if complete {
+                palette.satisfied
+            } else {
+                palette.island_fill
+            };
+            let stroke = if selected {
+                palette.selected
+            } else if flagged {
+                palette.error
+            } else {
+                palette.island_stroke
+            };
             let stroke_width = if selected { 4 } else { 2 };
-            let radius = if selected { 32 } else { 28 };
+            let radius = if selected { 32 } else { ISLAND_RADIUS };
 
             let onclick = {
+                let cb = cb.clone();
+                let press = press.clone();
+                let pos = position.to_owned();
+                Callback::from(move |_| {
+                    // the click that ends a long press should not also select the island, nor should
+                    // the tap of a second finger that made a drag build a double
+                    if std::mem::take(&mut press.borrow_mut().fired) || press.borrow().double {
+                        return;
+                    }
+                    cb.emit(pos)
+                })
+            };
+
+            let onpointerdown = {
+                let state = state.clone();
+                let press = press.clone();
+                let pos = position.to_owned();
+                Callback::from(move |e: PointerEvent| {
+                    if state.surrendered {
+                        return;
+                    }
+                    // a second finger during a drag only turns it into a double, the board sees to that
+                    if press
+                        .borrow()
+                        .drag
+                        .is_some_and(|drag| drag.pointer_id != e.pointer_id())
+                    {
+                        return;
+                    }
+                    let state = state.clone();
+                    let fired = press.clone();
+                    let timer = Timeout::new(LONG_PRESS_MS, move || {
+                        let mut s = (*state).clone();
+                        if s.grid.bridges_ending_at(pos).is_empty() {
+                            return;
+                        }
+                        fired.borrow_mut().fired = true;
+                        s.bridge_menu = Some(pos);
+                        s.selected = None;
+                        state.set(s);
+                    });
+                    let mut press = press.borrow_mut();
+                    press.fired = false;
+                    press.timer = Some(timer);
+                    press.double = false;
+                    press.drag = Some(DragStart {
+                        island: pos,
+                        pointer_id: e.pointer_id(),
+                        client: (e.client_x(), e.client_y()),
+                    });
+                })
+            };
+
+            let cancel_long_press = {
+                let press = press.clone();
+                Callback::from(move |_: PointerEvent| {
+                    press.borrow_mut().timer = None;
+                })
+            };
+
+            let onpointerenter = {
+                let state = state.clone();
+                let pos = position.to_owned();
+                Callback::from(move |_: PointerEvent| {
+                    if state.hovered != Some(pos) {
+                        let mut s = (*state).clone();
+                        s.hovered = Some(pos);
+                        state.set(s);
+                    }
+                })
+            };
+
+            let onpointerleave = {
+                let state = state.clone();
+                let cancel_long_press = cancel_long_press.clone();
+                Callback::from(move |e: PointerEvent| {
+                    cancel_long_press.emit(e);
+                    let mut s = (*state).clone();
+                    s.hovered = None;
+                    state.set(s);
+                })
+            };
+
+            // keyboard focus counts as hovering, so the same highlights follow it
+            let onfocus = {
+                let state = state.clone();
+                let pos = position.to_owned();
+                Callback::from(move |_: FocusEvent| {
+                    let mut s = (*state).clone();
+                    s.hovered = Some(pos);
+                    state.set(s);
+                })
+            };
+
+            let onblur = {
+                let state = state.clone();
+                Callback::from(move |_: FocusEvent| {
+                    let mut s = (*state).clone();
+                    s.hovered = None;
+                    state.set(s);
+                })
+            };
+
+            // Enter and Space press a focused island, as they would a button
+            let onkeydown = {
                 let cb = cb.clone();
                 let pos = position.to_owned();
-                Callback::from(move |_| cb.emit(pos))
+                Callback::from(move |e: KeyboardEvent| {
+                    if e.key() == "Enter" || e.key() == " " {
+                        e.prevent_default();
+                        cb.emit(pos)
+                    }
+                })
             };
 
             let filter = if selected { "url(#selectedGlow)" } else { "" };
@@ -377,16 +2649,68 @@ fn render_islands(state: &UseStateHandle<GameState>, cb: Callback<Position>) ->
             } else {
                 ""
             };
+            let network_class = network.contains(position).then_some("network-highlight");
 
             html! {
-                <g onclick={onclick} style="cursor:pointer;" class={shudder_class}>
+                <g
+                    role="button"
+                    tabindex="0"
+                    aria-label={island_label(*position, island.required_bridges, placed)}
+                    onclick={onclick}
+                    onkeydown={onkeydown}
+                    onfocus={onfocus}
+                    onblur={onblur}
+                    onpointerdown={onpointerdown}
+                    onpointerup={cancel_long_press}
+                    onpointerenter={onpointerenter}
+                    onpointerleave={onpointerleave}
+                    oncontextmenu={Callback::from(|e: MouseEvent| e.prevent_default())}
+                    // a drag from an island draws a bridge rather than scrolling the page
+                    style="cursor:pointer; touch-action:none;"
+                    class={classes!(shudder_class, network_class)}
+                    data-island={island_key(*position)}
+                >
                     <circle
                         cx={(position.x as i32 * 100).to_string()}
                         cy={(position.y as i32 * 100).to_string()}
-                        r={35}
+                        r={hit.island_radius.to_string()}
                         fill="transparent"
                     />
+                    { if target {
+                        html! {
+                            <circle
+                                class="tutorial-target"
+                                cx={(position.x as i32 * 100).to_string()}
+                                cy={(position.y as i32 * 100).to_string()}
+                                r={42}
+                                fill="none"
+                                stroke={palette.selected}
+                                stroke-width="4"
+                                stroke-dasharray="8 6"
+                                pointer-events="none"
+                            />
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    { if reachable {
+                        html! {
+                            <circle
+                                cx={(position.x as i32 * 100).to_string()}
+                                cy={(position.y as i32 * 100).to_string()}
+                                r={34}
+                                fill="none"
+                                stroke={palette.reachable}
+                                stroke-width="4"
+                                stroke-opacity="0.6"
+                                pointer-events="none"
+                            />
+                        }
+                    } else {
+                        html! {}
+                    }}
                     <circle
+                        class="island-body"
                         cx={(position.x as i32 * 100).to_string()}
                         cy={(position.y as i32 * 100).to_string()}
                         r={radius.to_string()}
@@ -395,102 +2719,1964 @@ fn render_islands(state: &UseStateHandle<GameState>, cb: Callback<Position>) ->
                         stroke-width={stroke_width.to_string()}
                         filter={filter}
                     />
-                    <text
-                        x={(position.x as i32 * 100).to_string()}
-                        y={(position.y as i32 * 100 + 7).to_string()}
-                        text-anchor="middle"
-                        font-size="20"
-                        font-family="sans-serif"
-                        pointer-events="none"
-                    >
-                        { island.required_bridges.to_string() }
-                    </text>
+                    // hidden clues leave blank islands, still colored as their bridges meet the clue
+                    { if settings.hide_clues {
+                        html! {}
+                    } else {
+                        html! {
+                            <text
+                                x={(position.x as i32 * 100).to_string()}
+                                y={(position.y as i32 * 100).to_string()}
+                                text-anchor="middle"
+                                dominant-baseline="central"
+                                font-size={clue_font_size(island.required_bridges).to_string()}
+                                font-family="sans-serif"
+                                fill={palette.text}
+                                pointer-events="none"
+                            >
+                                { island.required_bridges.to_string() }
+                            </text>
+                        }
+                    }}
+                    // the count still to go would give the clue away
+                    { if settings.show_remaining && remaining > 0 && !settings.hide_clues {
+                        // superscript beside the clue, in the corner the colorblind check mark uses once done
+                        html! {
+                            <text
+                                x={(position.x as i32 * 100 + 22).to_string()}
+                                y={(position.y as i32 * 100 - 20).to_string()}
+                                text-anchor="middle"
+                                dominant-baseline="central"
+                                font-size="16"
+                                font-family="sans-serif"
+                                font-weight="bold"
+                                fill={palette.text}
+                                pointer-events="none"
+                            >
+                                { remaining.to_string() }
+                            </text>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    { if state.locked.contains(position) {
+                        html! {
+                            <text
+                                x={(position.x as i32 * 100 + 22).to_string()}
+                                y={(position.y as i32 * 100 + 22).to_string()}
+                                text-anchor="middle"
+                                dominant-baseline="central"
+                                font-size="14"
+                                pointer-events="none"
+                                opacity="0.8"
+                            >
+                                {"🔒"}
+                            </text>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    { if complete && settings.colorblind {
+                        // a glyph so satisfied islands don't rely on color alone
+                        html! {
+                            <text
+                                x={(position.x as i32 * 100 + 20).to_string()}
+                                y={(position.y as i32 * 100 - 20).to_string()}
+                                text-anchor="middle"
+                                dominant-baseline="central"
+                                font-size="18"
+                                font-family="sans-serif"
+                                font-weight="bold"
+                                fill={palette.text}
+                                pointer-events="none"
+                            >
+                                {"✓"}
+                            </text>
+                        }
+                    } else {
+                        html! {}
+                    }}
                 </g>
             }
         })
         .collect()
 }
 
-fn render_bridges(state: &UseStateHandle<GameState>) -> Html {
-    state
-        .grid
-        .bridges
-        .iter()
-        .flat_map(|(bridge_line, bridge_type)| {
-            // offsets for single vs double
-            let offsets: Vec<i32> = match bridge_type {
-                hashi::BridgeType::Single => vec![0], // single line, no offset
-                hashi::BridgeType::Double => vec![-5, 5], // double line, 5px apart
-            };
+/// Faint lines through every row and column, numbered from 0 in the margin like the text view
+fn render_grid_lines(grid: &HashiGrid, palette: &Palette, scale: f64) -> Html {
+    let right = (grid.width as i32 - 1) * 100;
+    let bottom = (grid.height as i32 - 1) * 100;
+    // labels stay readable as the board shrinks, but never outgrow the margin
+    let font_size = (12.0 * scale).clamp(16.0, 40.0).to_string();
 
-            offsets.into_iter().map(move |offset: i32| {
-                let (x1, y1, x2, y2) = match bridge_line.direction {
-                    hashi::BridgeDirection::Right => (
-                        (bridge_line.start.x as i32 * 100),
-                        (bridge_line.start.y as i32 * 100) + offset,
-                        (bridge_line.end.x as i32 * 100),
-                        (bridge_line.end.y as i32 * 100) + offset,
-                    ),
-                    hashi::BridgeDirection::Down => (
-                        (bridge_line.start.x as i32 * 100) + offset,
-                        (bridge_line.start.y as i32 * 100),
-                        (bridge_line.end.x as i32 * 100) + offset,
-                        (bridge_line.end.y as i32 * 100),
-                    ),
-                };
+    let columns = (0..grid.width as i32).map(|x| {
+        html! {
+            <>
+                <line
+                    x1={(x * 100).to_string()}
+                    y1="0"
+                    x2={(x * 100).to_string()}
+                    y2={bottom.to_string()}
+                />
+                <text x={(x * 100).to_string()} y="-60" stroke="none" fill={palette.text}>
+                    { x }
+                </text>
+            </>
+        }
+    });
+    let rows = (0..grid.height as i32).map(|y| {
+        html! {
+            <>
+                <line
+                    x1="0"
+                    y1={(y * 100).to_string()}
+                    x2={right.to_string()}
+                    y2={(y * 100).to_string()}
+                />
+                <text x="-60" y={(y * 100).to_string()} stroke="none" fill={palette.text}>
+                    { y }
+                </text>
+            </>
+        }
+    });
 
-                // clone state for click
-                let state = state.clone();
-                let key = bridge_line.to_owned();
-                let onclick = Callback::from(move |_| {
-                    let mut s = (*state).clone();
+    html! {
+        <g
+            pointer-events="none"
+            stroke={palette.island_stroke}
+            stroke-width="1"
+            stroke-opacity="0.25"
+            fill-opacity="0.5"
+            font-size={font_size}
+            font-family="sans-serif"
+            text-anchor="middle"
+            dominant-baseline="central"
+        >
+            { for columns }
+            { for rows }
+        </g>
+    }
+}
 
-                    if let Some(existing_bridge_type) = s.grid.bridges.get(&key) {
-                        match existing_bridge_type {
-                            hashi::BridgeType::Double => {
-                                // Remove one bridge (double -> single)
-                                s.grid.bridges.insert(key, hashi::BridgeType::Single);
-                            }
-                            hashi::BridgeType::Single => {
-                                // Remove the bridge entirely
-                                s.grid.bridges.remove(&key);
-                            }
-                        }
-                    }
-                    state.set(s);
-                });
+/// What the player is told when a bridge they tried is refused, so the rules are learnt by playing
+fn rejection_message(error: &HashiError) -> &'static str {
+    match error {
+        HashiError::DiagonalBridge => "Bridges only run straight across or down, never diagonally",
+        HashiError::CrossesBridge { .. } => "Bridges can't cross each other",
+        HashiError::CrossesIsland { .. } => "Bridges can't pass over an island",
+        HashiError::IslandFull { .. } => "That island already has as many bridges as its number",
+        HashiError::Overwrite { .. } => "Two islands can be joined by two bridges at most",
+        HashiError::WrappedBridge => "Bridges only wrap round the edges in wrap-around puzzles",
+        HashiError::LockedBridge { .. } => "That bridge came with the puzzle and can't be changed",
+        _ => "That bridge can't be built",
+    }
+}
 
-                html! {
-                    <>
-                        <line
-                            x1={x1.to_string()}
-                            y1={y1.to_string()}
-                            x2={x2.to_string()}
-                            y2={y2.to_string()}
-                            stroke="black"
-                            stroke-width="4"
-                            stroke-linecap="round"
-                            style="cursor:pointer;"
-                        />
-                        <line
-                            x1={x1.to_string()}
-                            y1={y1.to_string()}
-                            x2={x2.to_string()}
-                            y2={y2.to_string()}
-                            stroke="transparent"
-                            stroke-width="35"
-                            style="cursor:pointer;"
-                            {onclick}
-                        />
-                    </>
-                }
-            })
+/// Toast with the reason the last bridge was refused, fading out by itself after about two seconds
+fn render_rejection(state: &UseStateHandle<GameState>) -> Html {
+    let Some((count, message)) = state.rejection else {
+        return html! {};
+    };
+
+    // keyed on the count so a new refusal starts the fade again
+    html! {
+        <div class="game-toast-area">
+            <div key={count} class="game-toast" role="status">{ message }</div>
+        </div>
+    }
+}
+
+/// A bridge's ends and type in grid coordinates, e.g. "(1,2)–(4,2) double"
+fn bridge_tooltip(line: BridgeLine, bridge_type: hashi::BridgeType) -> String {
+    format!(
+        "({},{})–({},{}) {}{}",
+        line.start.x,
+        line.start.y,
+        line.end.x,
+        line.end.y,
+        match bridge_type {
+            hashi::BridgeType::Single => "single",
+            hashi::BridgeType::Double => "double",
+        },
+        if line.wraps { ", round the edge" } else { "" }
+    )
+}
+
+/// Toast with the ends of a long-pressed bridge, the touch stand-in for its hover tooltip
+fn render_inspected(state: &UseStateHandle<GameState>) -> Html {
+    let Some((count, line)) = state.inspected else {
+        return html! {};
+    };
+    let Some(&bridge_type) = state.grid.bridges.get(&line) else {
+        return html! {};
+    };
+
+    html! {
+        <div class="game-toast-area">
+            <div key={count} class="game-toast" role="status">{ bridge_tooltip(line, bridge_type) }</div>
+        </div>
+    }
+}
+
+/// Faint dotted cross halfway along each line the player has marked as "no bridge"
+fn render_annotations(state: &UseStateHandle<GameState>, palette: &Palette) -> Html {
+    state
+        .annotations
+        .iter()
+        .map(|line| {
+            // halfway along the first piece, a wrapped line's other piece is off at the far edge
+            let (x1, y1, x2, y2) = line_segments(&state.grid, line)[0];
+            let (x, y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+            html! {
+                <g
+                    stroke={palette.island_stroke}
+                    stroke-width="3"
+                    stroke-dasharray="3 4"
+                    stroke-opacity="0.4"
+                    pointer-events="none"
+                >
+                    <line x1={(x - 12.0).to_string()} y1={(y - 12.0).to_string()} x2={(x + 12.0).to_string()} y2={(y + 12.0).to_string()} />
+                    <line x1={(x - 12.0).to_string()} y1={(y + 12.0).to_string()} x2={(x + 12.0).to_string()} y2={(y - 12.0).to_string()} />
+                </g>
+            }
         })
         .collect()
 }
 
-fn format_time(seconds: u32) -> String {
-    let mins = seconds / 60;
-    let secs = seconds % 60;
-    format!("{:02}:{:02}", mins, secs)
+/// Dashed line showing that the hovered island cannot be bridged to from the selection
+fn render_blocked_preview(state: &UseStateHandle<GameState>, palette: &Palette) -> Html {
+    let Some(line) = state.blocked_preview() else {
+        return html! {};
+    };
+
+    line_segments(&state.grid, &line)
+        .into_iter()
+        .map(|(x1, y1, x2, y2)| {
+            html! {
+                <line
+                    x1={x1.to_string()}
+                    y1={y1.to_string()}
+                    x2={x2.to_string()}
+                    y2={y2.to_string()}
+                    stroke={palette.error}
+                    stroke-width="4"
+                    stroke-dasharray="12 8"
+                    stroke-linecap="round"
+                    pointer-events="none"
+                />
+            }
+        })
+        .collect()
+}
+
+/// Cross marking where a rejected bridge would have hit another bridge or island
+fn render_conflict(state: &UseStateHandle<GameState>, palette: &Palette) -> Html {
+    let Some(position) = state.conflict else {
+        return html! {};
+    };
+
+    let x = position.x as i32 * 100;
+    let y = position.y as i32 * 100;
+    html! {
+        <g stroke={palette.error} stroke-width="6" stroke-linecap="round" pointer-events="none">
+            <line x1={(x - 15).to_string()} y1={(y - 15).to_string()} x2={(x + 15).to_string()} y2={(y + 15).to_string()} />
+            <line x1={(x - 15).to_string()} y1={(y + 15).to_string()} x2={(x + 15).to_string()} y2={(y - 15).to_string()} />
+        </g>
+    }
+}
+
+/// The way a bridge leaves one of its islands, as a step of -1, 0 or 1 along each axis. A wrapped
+/// bridge heads for the edge of the board rather than straight at its other island
+fn leaving_direction(grid: &HashiGrid, line: &BridgeLine, island: Position) -> (i32, i32) {
+    let (x, y) = (island.x as f64 * 100.0, island.y as f64 * 100.0);
+    line_segments(grid, line)
+        .into_iter()
+        .find_map(|(x1, y1, x2, y2)| {
+            if (x1, y1) == (x, y) {
+                Some((x2 - x1, y2 - y1))
+            } else if (x2, y2) == (x, y) {
+                Some((x1 - x2, y1 - y2))
+            } else {
+                None
+            }
+        })
+        .map_or((0, 0), |(dx, dy)| {
+            ((dx as i32).signum(), (dy as i32).signum())
+        })
+}
+
+/// Menu of an island's bridges shown after a long press, each entry takes one bridge away
+fn render_bridge_menu(state: &UseStateHandle<GameState>, palette: &Palette) -> Html {
+    let Some(island) = state.bridge_menu else {
+        return html! {};
+    };
+
+    let on_close = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            let mut s = (*state).clone();
+            s.bridge_menu = None;
+            state.set(s);
+        })
+    };
+
+    let entries: Html = state
+        .grid
+        .bridges_ending_at(island)
+        .into_iter()
+        .filter(|(line, _)| !state.grid.locked_bridges.contains(line))
+        .map(|(line, bridge_type)| {
+            // place the entry just off the island, on the bridge
+            let (dx, dy) = leaving_direction(&state.grid, line, island);
+            let cx = island.x as i32 * 100 + dx * 55;
+            let cy = island.y as i32 * 100 + dy * 55;
+
+            let label = match bridge_type {
+                hashi::BridgeType::Single => "✕",
+                hashi::BridgeType::Double => "−",
+            };
+
+            let onclick = {
+                let state = state.clone();
+                let line = *line;
+                Callback::from(move |_| {
+                    let mut s = (*state).clone();
+                    s.remove_one_bridge(line);
+                    s.bridge_menu = None;
+                    state.set(s);
+                })
+            };
+
+            html! {
+                <g onclick={onclick} style="cursor:pointer;">
+                    <circle
+                        cx={cx.to_string()}
+                        cy={cy.to_string()}
+                        r="20"
+                        fill={palette.error}
+                        stroke={palette.island_stroke}
+                        stroke-width="2"
+                    />
+                    <text
+                        x={cx.to_string()}
+                        y={cy.to_string()}
+                        text-anchor="middle"
+                        dominant-baseline="central"
+                        font-size="20"
+                        font-family="sans-serif"
+                        fill="#FFFFFF"
+                        pointer-events="none"
+                    >
+                        { label }
+                    </text>
+                </g>
+            }
+        })
+        .collect();
+
+    // the island itself becomes a lock toggle while the menu is open
+    let lock_toggle = if state.locked.contains(&island) || state.is_satisfied(island) {
+        let onclick = {
+            let state = state.clone();
+            Callback::from(move |_| {
+                let mut s = (*state).clone();
+                s.toggle_lock(island);
+                s.bridge_menu = None;
+                state.set(s);
+            })
+        };
+        let cx = island.x as i32 * 100;
+        let cy = island.y as i32 * 100;
+        html! {
+            <g onclick={onclick} style="cursor:pointer;">
+                <circle
+                    cx={cx.to_string()}
+                    cy={cy.to_string()}
+                    r="22"
+                    fill={palette.island_fill}
+                    stroke={palette.island_stroke}
+                    stroke-width="2"
+                />
+                <text
+                    x={cx.to_string()}
+                    y={cy.to_string()}
+                    text-anchor="middle"
+                    dominant-baseline="central"
+                    font-size="20"
+                    pointer-events="none"
+                >
+                    { if state.locked.contains(&island) { "🔓" } else { "🔒" } }
+                </text>
+            </g>
+        }
+    } else {
+        html! {}
+    };
+
+    html! {
+        <>
+            // catches taps outside the menu to close it
+            <rect
+                x="-100"
+                y="-100"
+                width={(state.grid.width as i32 * 100 + 100).to_string()}
+                height={(state.grid.height as i32 * 100 + 100).to_string()}
+                fill="transparent"
+                onclick={on_close}
+            />
+            { entries }
+            { lock_toggle }
+        </>
+    }
+}
+
+/// Invisible tap targets along every line a bridge could run, each stepping its bridge round
+fn render_cycle_targets(
+    state: &UseStateHandle<GameState>,
+    hit: HitSizes,
+    on_cycle: Callback<BridgeLine>,
+) -> Html {
+    state
+        .grid
+        .candidate_bridges()
+        .into_iter()
+        .flat_map(|line| {
+            line_segments(&state.grid, &line)
+                .into_iter()
+                .filter_map(move |segment| hit.bridge_hit(segment, !line.wraps))
+                .map(move |segment| (line, segment))
+        })
+        .map(|(line, (x1, y1, x2, y2))| {
+            let onclick = {
+                let on_cycle = on_cycle.clone();
+                Callback::from(move |_| on_cycle.emit(line))
+            };
+            html! {
+                <line
+                    x1={x1.to_string()}
+                    y1={y1.to_string()}
+                    x2={x2.to_string()}
+                    y2={y2.to_string()}
+                    stroke="transparent"
+                    stroke-width={hit.bridge_width.to_string()}
+                    style="cursor:pointer;"
+                    role="button"
+                    aria-label={format!(
+                        "Change the bridge between {} and {}",
+                        place_name(line.start),
+                        place_name(line.end)
+                    )}
+                    {onclick}
+                />
+            }
+        })
+        .collect()
+}
+
+fn render_bridges(
+    state: &UseStateHandle<GameState>,
+    palette: &Palette,
+    press: &Rc<RefCell<IslandPress>>,
+    hit: HitSizes,
+    look: BridgeLook,
+    confirm_removal: bool,
+) -> Html {
+    let fading: Html = state
+        .last_removed
+        .iter()
+        .flat_map(|(bridge_line, bridge_type)| {
+            bridge_strands(&state.grid, bridge_line, bridge_type, look)
+                .into_iter()
+                .enumerate()
+                .map(move |(i, (x1, y1, x2, y2))| {
+                    html! {
+                        <line
+                            key={format!("fade-{:?}-{:?}-{}", bridge_line, bridge_type, i)}
+                            class="bridge-removed"
+                            x1={x1.to_string()}
+                            y1={y1.to_string()}
+                            x2={x2.to_string()}
+                            y2={y2.to_string()}
+                            stroke={palette.bridge}
+                            stroke-width={look.stroke_width(bridge_type).to_string()}
+                            stroke-linecap="round"
+                            pointer-events="none"
+                        />
+                    }
+                })
+        })
+        .collect();
+
+    // on the winning move every bridge flashes, the one just built most of all
+    let won = state.is_won();
+    let final_line = state.last_placed().filter(|_| won);
+
+    let placed: Html = state
+        .grid
+        .bridges
+        .iter()
+        .flat_map(|(bridge_line, bridge_type)| {
+            let solved_class = if final_line == Some(*bridge_line) {
+                Some("bridge-final")
+            } else {
+                won.then_some("bridge-solved")
+            };
+            let armed_class = state
+                .armed_removal
+                .is_some_and(|(armed, _)| armed == *bridge_line)
+                .then_some("bridge-armed");
+            let locked = state.grid.locked_bridges.contains(bridge_line);
+            bridge_strands(&state.grid, bridge_line, bridge_type, look)
+                .into_iter()
+                .enumerate()
+                .map(move |(i, (x1, y1, x2, y2))| {
+                    let key = bridge_line.to_owned();
+                    let onclick = {
+                        let state = state.clone();
+                        let press = press.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            // the click ending a long press only asked about the bridge
+                            if state.surrendered || std::mem::take(&mut press.borrow_mut().fired) {
+                                return;
+                            }
+                            let mut s = (*state).clone();
+                            settle_shudder(&press, &mut s);
+                            s.click_bridge(key, confirm_removal, e.time_stamp());
+                            state.set(s);
+                        })
+                    };
+
+                    // touch screens have no hover, holding a finger on a bridge shows its tooltip instead
+                    let onpointerdown = {
+                        let state = state.clone();
+                        let press = press.clone();
+                        Callback::from(move |e: PointerEvent| {
+                            if e.pointer_type() != "touch" {
+                                return;
+                            }
+                            let state = state.clone();
+                            let fired = press.clone();
+                            let timer = Timeout::new(LONG_PRESS_MS, move || {
+                                fired.borrow_mut().fired = true;
+                                let mut s = (*state).clone();
+                                let count = s.inspected.map_or(0, |(count, _)| count);
+                                s.inspected = Some((count + 1, key));
+                                state.set(s);
+                            });
+                            let mut press = press.borrow_mut();
+                            press.fired = false;
+                            press.timer = Some(timer);
+                        })
+                    };
+                    let cancel_long_press = {
+                        let press = press.clone();
+                        Callback::from(move |_: PointerEvent| {
+                            press.borrow_mut().timer = None;
+                        })
+                    };
+
+                    // a double's strands are one bridge to a screen reader, named on the first
+                    let label = (i == 0).then(|| {
+                        format!(
+                            "{} between {} and {}, {}",
+                            bridge_name(Some(*bridge_type)),
+                            place_name(bridge_line.start),
+                            place_name(bridge_line.end),
+                            if locked { "came with the puzzle" } else { "remove one" }
+                        )
+                    });
+
+                    let (hx1, hy1, hx2, hy2) = hit
+                        .bridge_hit((x1, y1, x2, y2), !bridge_line.wraps)
+                        .unwrap_or((x1, y1, x1, y1));
+
+                    // keyed on the bridge type too, so going single <-> double redraws every strand
+                    html! {
+                        <g key={format!("{:?}-{:?}-{}", bridge_line, bridge_type, i)}>
+                            <line
+                                class={classes!("bridge-placed", solved_class, armed_class, locked.then_some("bridge-locked"))}
+                                pathLength="1"
+                                x1={x1.to_string()}
+                                y1={y1.to_string()}
+                                x2={x2.to_string()}
+                                y2={y2.to_string()}
+                                stroke={palette.bridge}
+                                stroke-width={look.stroke_width(bridge_type).to_string()}
+                                stroke-linecap="round"
+                                style="cursor:pointer;"
+                            />
+                            <line
+                                x1={hx1.to_string()}
+                                y1={hy1.to_string()}
+                                x2={hx2.to_string()}
+                                y2={hy2.to_string()}
+                                stroke="transparent"
+                                stroke-width={hit.bridge_width.to_string()}
+                                stroke-linecap="butt"
+                                style="cursor:pointer;"
+                                role={label.is_some().then_some("button")}
+                                aria-label={label}
+                                aria-hidden={(i > 0).then_some("true")}
+                                {onclick}
+                                {onpointerdown}
+                                onpointerup={cancel_long_press.clone()}
+                                onpointerleave={cancel_long_press}
+                                oncontextmenu={Callback::from(|e: MouseEvent| e.prevent_default())}
+                            >
+                                <title>{ bridge_tooltip(*bridge_line, *bridge_type) }</title>
+                            </line>
+                        </g>
+                    }
+                })
+        })
+        .collect();
+
+    let marks: Html = state
+        .grid
+        .bridges
+        .iter()
+        .filter(|(_, bridge_type)| **bridge_type == hashi::BridgeType::Double)
+        .map(|(bridge_line, _)| {
+            let DoubleMarks { bars, label } = double_marks(&state.grid, bridge_line, look);
+            html! {
+                <g key={format!("marks-{:?}", bridge_line)} pointer-events="none">
+                    { for bars.into_iter().map(|(x1, y1, x2, y2)| html! {
+                        <line
+                            x1={x1.to_string()}
+                            y1={y1.to_string()}
+                            x2={x2.to_string()}
+                            y2={y2.to_string()}
+                            stroke={palette.bridge}
+                            stroke-width={look.double_width.to_string()}
+                            stroke-linecap="round"
+                        />
+                    }) }
+                    { for label.map(|(x, y)| html! {
+                        <text
+                            x={x.to_string()}
+                            y={y.to_string()}
+                            text-anchor="middle"
+                            dominant-baseline="central"
+                            font-size={(look.double_gap * 0.6).to_string()}
+                            font-family="sans-serif"
+                            fill={palette.bridge}
+                        >
+                            {"×2"}
+                        </text>
+                    }) }
+                </g>
+            }
+        })
+        .collect();
+
+    html! {
+        <>
+            { fading }
+            { placed }
+            { marks }
+        </>
+    }
+}
+
+/// Wrap a navigation so it first asks before discarding an unfinished puzzle's bridges
+fn confirm_leave(
+    state: &UseStateHandle<GameState>,
+    leave: Callback<MouseEvent>,
+) -> Callback<MouseEvent> {
+    let has_progress = state.has_progress();
+    Callback::from(move |e: MouseEvent| {
+        let confirmed = !has_progress
+            || web_sys::window()
+                .and_then(|w| {
+                    w.confirm_with_message("Leave this puzzle? Your bridges will be lost.")
+                        .ok()
+                })
+                .unwrap_or(true);
+        if confirmed {
+            leave.emit(e);
+        }
+    })
+}
+
+/// Absolute link to a route with a query string, none without a window to take the origin from
+fn absolute_link(basename: &str, route: &Route, query: &str) -> Option<String> {
+    let origin = web_sys::window()?.location().origin().ok()?;
+    Some(format!(
+        "{}{}{}?{}",
+        origin,
+        basename,
+        route.to_path(),
+        query
+    ))
+}
+
+/// Callback copying an absolute link to a route, with a query string, to the clipboard
+fn copy_link(navigator: &Navigator, route: Route, query: String) -> Callback<MouseEvent> {
+    let basename = navigator.basename().unwrap_or_default().to_string();
+    Callback::from(move |_| {
+        if let Some(link) = absolute_link(&basename, &route, &query) {
+            copy_to_clipboard(link);
+        }
+    })
+}
+
+/// Open the system share sheet with a result and its link, attaching the image where the
+/// browser takes files, or copy the link where there's no share sheet at all. Returns whether
+/// the link was copied, so the caller can confirm it like any other copied link
+fn share_result(text: &str, url: String, image: &str) -> bool {
+    use web_sys::js_sys::{Array, Function, Promise, Reflect};
+    use web_sys::wasm_bindgen::{JsCast, JsValue};
+
+    let Some(window) = web_sys::window() else {
+        return false;
+    };
+    let navigator = window.navigator();
+    // share and canShare are still marked unstable in web-sys, so they're looked up by name
+    let method = |name: &str| {
+        Reflect::get(&navigator, &JsValue::from_str(name))
+            .ok()
+            .and_then(|method| method.dyn_into::<Function>().ok())
+    };
+    let Some(share) = method("share") else {
+        copy_to_clipboard(url);
+        return true;
+    };
+
+    let data = web_sys::ShareData::new();
+    data.set_title("Hashi");
+    data.set_text(text);
+    data.set_url(&url);
+    let options = web_sys::FilePropertyBag::new();
+    options.set_type("image/svg+xml");
+    if let Ok(file) = web_sys::File::new_with_str_sequence_and_options(
+        &Array::of1(&JsValue::from_str(image)),
+        "hashi-result.svg",
+        &options,
+    ) {
+        data.set_files(&Array::of1(&file));
+        let takes_files = method("canShare")
+            .and_then(|can_share| can_share.call1(&navigator, &data).ok())
+            .is_some_and(|answer| answer.is_truthy());
+        if !takes_files {
+            data.set_files(&JsValue::UNDEFINED);
+        }
+    }
+
+    let Ok(promise) = share
+        .call1(&navigator, &data)
+        .and_then(|promise| promise.dyn_into::<Promise>())
+    else {
+        return false;
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        // closing the share sheet rejects the promise, which needs no handling
+        let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+    });
+    false
+}
+
+/// Write text to the system clipboard, ignoring failures (e.g. permission denied)
+pub(crate) fn copy_to_clipboard(text: String) {
+    if let Some(window) = web_sys::window() {
+        let promise = window.navigator().clipboard().write_text(&text);
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+        });
+    }
+}
+
+/// Save text to a file through a temporary link, the browser decides where it goes
+fn download(file_name: &str, contents: &str, mime_type: &str) {
+    use web_sys::wasm_bindgen::JsCast;
+
+    let url = gloo::file::ObjectUrl::from(gloo::file::Blob::new_with_options(
+        contents,
+        Some(mime_type),
+    ));
+    let Ok(link) = gloo::utils::document().create_element("a") else {
+        return;
+    };
+    let link: web_sys::HtmlAnchorElement = link.unchecked_into();
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+    // some browsers read the link after the click returns, revoke it (by dropping) once they have
+    Timeout::new(1000, move || drop(url)).forget();
+}
+
+fn format_time(seconds: u32) -> String {
+    let mins = seconds / 60;
+    let secs = seconds % 60;
+    format!("{:02}:{:02}", mins, secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashi::Island;
+
+    fn two_island_state() -> GameState {
+        let grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[(Position { x: 1, y: 2 }, 1), (Position { x: 4, y: 2 }, 2)],
+        )
+        .unwrap();
+
+        GameState {
+            grid,
+            ..GameState::default()
+        }
+    }
+
+    #[test]
+    fn test_remove_one_bridge_remembers_removed_strands() {
+        // Test: Removing a bridge keeps what was there so it can fade out
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state.grid.bridges.insert(line, hashi::BridgeType::Double);
+
+        state.remove_one_bridge(line);
+        assert_eq!(state.last_removed, Some((line, hashi::BridgeType::Double)));
+
+        state.remove_one_bridge(line);
+        assert_eq!(state.last_removed, Some((line, hashi::BridgeType::Single)));
+    }
+
+    #[test]
+    fn test_hit_sizes_grow_on_small_screens() {
+        // Test: Hit shapes keep their drawn size on a roomy board and grow, up to a cap, when it is shrunk
+        let roomy =
+            HitSizes::for_board(units_per_px((-100.0, -100.0, 600.0, 1100.0), (1200, 2200)));
+        assert_eq!(roomy.island_radius, 35.0);
+        assert_eq!(roomy.bridge_width, 35.0);
+
+        // an 8x16 board squeezed onto a phone is about a third of a pixel per unit
+        let phone = HitSizes::for_board(units_per_px((-100.0, -100.0, 900.0, 1700.0), (300, 566)));
+        assert_eq!(phone.island_radius, 50.0);
+        assert!(phone.bridge_width > 35.0 * 2.0);
+        assert!(phone.bridge_width <= 90.0);
+
+        // before the board is laid out there is nothing to scale by
+        assert_eq!(units_per_px((-100.0, -100.0, 900.0, 1700.0), (0, 0)), 0.0);
+        assert_eq!(HitSizes::for_board(0.0), roomy);
+    }
+
+    #[test]
+    fn test_bridge_hit_stays_clear_of_islands() {
+        // Test: A bridge's hit line stops at the islands' hit circles, so a tap beside an island isn't on the bridge
+        let hit = HitSizes::for_board(0.0);
+        assert_eq!(
+            hit.bridge_hit((100.0, 200.0, 400.0, 200.0), true),
+            Some((135.0, 200.0, 365.0, 200.0))
+        );
+        // a tap 20 units out from the island at (100, 200) used to land on both
+        let tap = 120.0;
+        let (start, _, end, _) = hit.bridge_hit((100.0, 200.0, 400.0, 200.0), true).unwrap();
+        assert!(!(start..=end).contains(&tap));
+
+        // the piece running off the edge of a toroidal board is only trimmed at its island
+        assert_eq!(
+            hit.bridge_hit((200.0, 100.0, 200.0, -50.0), false),
+            Some((200.0, 65.0, 200.0, -50.0))
+        );
+        // two hit circles that meet leave nothing of the bridge between them
+        let wide = HitSizes::for_board(3.0);
+        assert_eq!(wide.bridge_hit((0.0, 0.0, 100.0, 0.0), true), None);
+    }
+
+    fn victory_props(
+        challenge_time: Option<u32>,
+        challenge_moves: Option<u32>,
+    ) -> VictoryOverlayProps {
+        VictoryOverlayProps {
+            next_width: 5,
+            next_height: 10,
+            puzzle_id: 42,
+            difficulty: Difficulty::Hard,
+            elapsed_seconds: 90,
+            challenge_time,
+            challenge_from_first_move: false,
+            timer_on_first_move: false,
+            show_timer: true,
+            record: true,
+            moves: 20,
+            challenge_moves,
+            solution_moves: 15,
+            replay: AttrValue::default(),
+            note: None,
+            embed: false,
+            assisted: false,
+            code: None,
+            variant: Variant::Classic,
+            seed_settings: SeedSettings::default(),
+            grid: HashiGrid::placeholder(),
+            result_image: AttrValue::default(),
+            puzzle_key: AttrValue::default(),
+        }
+    }
+
+    #[test]
+    fn test_bridge_tooltip_names_ends_and_type() {
+        // Test: The tooltip gives both ends in grid coordinates, the bridge type, and whether it wraps
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        assert_eq!(
+            bridge_tooltip(line, hashi::BridgeType::Double),
+            "(1,2)–(4,2) double"
+        );
+
+        let wrapped =
+            BridgeLine::wrapping(Position { x: 0, y: 1 }, Position { x: 0, y: 5 }).unwrap();
+        assert_eq!(
+            bridge_tooltip(wrapped, hashi::BridgeType::Single),
+            "(0,1)–(0,5) single, round the edge"
+        );
+    }
+
+    #[test]
+    fn test_leaving_direction_follows_wrapped_bridges() {
+        // Test: A bridge menu entry sits towards the other island, or towards the edge a wrapped bridge leaves by
+        let grid = HashiGrid::new(6, 6).unwrap();
+        let (top, bottom) = (Position { x: 0, y: 1 }, Position { x: 0, y: 5 });
+        let straight = BridgeLine::new(top, bottom).unwrap();
+        let wrapped = BridgeLine::wrapping(top, bottom).unwrap();
+
+        assert_eq!(leaving_direction(&grid, &straight, top), (0, 1));
+        assert_eq!(leaving_direction(&grid, &straight, bottom), (0, -1));
+        assert_eq!(leaving_direction(&grid, &wrapped, top), (0, -1));
+        assert_eq!(leaving_direction(&grid, &wrapped, bottom), (0, 1));
+    }
+
+    #[test]
+    fn test_result_summary_leaves_out_hidden_time() {
+        // Test: The shared result gives the time only when the timer was showing
+        assert_eq!(
+            result_summary(7, 7, Some(125), 18),
+            "Solved a 7x7 Hashi in 02:05 with 18 moves"
+        );
+        assert_eq!(
+            result_summary(7, 7, None, 18),
+            "Solved a 7x7 Hashi with 18 moves"
+        );
+    }
+
+    #[test]
+    fn test_challenge_summary_needs_both_targets() {
+        // Test: Old time-only links keep their single line result, with no combined summary
+        assert_eq!(challenge_summary(&victory_props(Some(100), None)), None);
+        assert_eq!(challenge_summary(&victory_props(None, Some(25))), None);
+    }
+
+    #[test]
+    fn test_challenge_summary_mixed_result() {
+        // Test: Time and moves are judged separately
+        assert_eq!(
+            challenge_summary(&victory_props(Some(100), Some(15))).unwrap(),
+            "Beat time ✅, missed moves ❌"
+        );
+        assert_eq!(
+            challenge_summary(&victory_props(Some(60), Some(20))).unwrap(),
+            "Missed time ❌, beat moves ✅"
+        );
+    }
+
+    #[test]
+    fn test_challenge_notes_timer_mode() {
+        // Test: A challenge timed from its first move says so in its link, and the result says
+        // whose time was counted that way when the two solves were timed differently
+        let query = QueryParams::challenge(83, 21, Difficulty::Hard, SeedSettings::default(), true);
+        let params: QueryParams = serde_urlencoded::from_str(&query).unwrap();
+        assert_eq!(params.timer_on_first_move, Some(1));
+
+        let mut props = victory_props(Some(100), Some(25));
+        assert_eq!(
+            challenge_summary(&props).unwrap(),
+            "Beat time ✅, beat moves ✅"
+        );
+        props.challenge_from_first_move = true;
+        assert_eq!(
+            challenge_summary(&props).unwrap(),
+            "Beat time ✅ (theirs timed from the first move), beat moves ✅"
+        );
+        props.timer_on_first_move = true;
+        assert_eq!(timer_mode_note(&props), "");
+        props.challenge_from_first_move = false;
+        assert_eq!(
+            timer_mode_note(&props),
+            " (yours timed from the first move)"
+        );
+    }
+
+    #[test]
+    fn test_challenge_query_matches_params() {
+        // Test: A challenge link uses the same parameter names and difficulty spelling that QueryParams reads
+        let query =
+            QueryParams::challenge(83, 21, Difficulty::Hard, SeedSettings::default(), false);
+        let params: QueryParams = serde_json::from_value(serde_json::json!({
+            "challenge_time": 83,
+            "challenge_moves": 21,
+            "difficulty": "Hard",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            query,
+            "challenge_time=83&challenge_moves=21&difficulty=Hard"
+        );
+        assert_eq!(params.difficulty, Some(Difficulty::Hard));
+    }
+
+    #[test]
+    fn test_query_params_survive_bad_values() {
+        // Test: A value that doesn't parse only loses that parameter, and unknown parameters are ignored
+        let params: QueryParams = serde_urlencoded::from_str(
+            "challenge_time=abc&challenge_moves=21&difficulty=Hard&utm_source=chat",
+        )
+        .unwrap();
+        assert_eq!(params.challenge_time, None);
+        assert_eq!(params.challenge_moves, Some(21));
+        assert_eq!(params.difficulty, Some(Difficulty::Hard));
+
+        let params: QueryParams =
+            serde_urlencoded::from_str("challenge_time=83&difficulty=Impossible&embed=-1").unwrap();
+        assert_eq!(
+            params,
+            QueryParams {
+                challenge_time: Some(83),
+                ..QueryParams::default()
+            }
+        );
+
+        assert_eq!(
+            serde_urlencoded::from_str::<QueryParams>(&QueryParams::challenge(
+                83,
+                21,
+                Difficulty::Easy,
+                SeedSettings::default(),
+                false
+            ))
+            .unwrap(),
+            QueryParams {
+                challenge_time: Some(83),
+                challenge_moves: Some(21),
+                difficulty: Some(Difficulty::Easy),
+                ..QueryParams::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_generator_travels_in_links() {
+        // Test: A puzzle made with other generator settings says so in its next, challenge and replay
+        // links, and a link naming none is a standard puzzle
+        let spanning = SeedSettings {
+            generator: GenAlgo::SpanningTree,
+            symmetric: true,
+            vary_clues: true,
+        };
+        let (_, next) = next_puzzle(8, 16, Difficulty::Hard, Variant::Classic, spanning);
+        assert_eq!(next.seed_settings(), spanning);
+
+        let challenge: QueryParams = serde_urlencoded::from_str(&QueryParams::challenge(
+            83,
+            21,
+            Difficulty::Hard,
+            spanning,
+            false,
+        ))
+        .unwrap();
+        assert_eq!(challenge.seed_settings(), spanning);
+
+        let bare: QueryParams = serde_urlencoded::from_str("difficulty=Hard").unwrap();
+        assert_eq!(bare.seed_settings(), SeedSettings::default());
+        assert_eq!(
+            QueryParams::new_puzzle(Difficulty::Hard, spanning).to_query(),
+            "difficulty=Hard&generator=SpanningTree&symmetric=1&vary_clues=1"
+        );
+        assert_eq!(QueryParams::seeded(SeedSettings::default()).to_query(), "");
+        assert!(
+            serde_json::to_value(QueryParams::new_puzzle(
+                Difficulty::Hard,
+                SeedSettings::default()
+            ))
+            .unwrap()
+            .get("generator")
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_page_query_names_its_difficulty() {
+        // Test: A page link carries its difficulty and nothing else, and an older link without one gets the default
+        let query = QueryParams::page(Difficulty::Hard).to_query();
+        let params: QueryParams = serde_urlencoded::from_str(&query).unwrap();
+        let bare: QueryParams = serde_urlencoded::from_str("").unwrap();
+
+        assert_eq!(query, "difficulty=Hard");
+        assert_eq!(params.difficulty(), Difficulty::Hard);
+        assert_eq!(bare.difficulty(), Difficulty::default());
+    }
+
+    #[test]
+    fn test_embed_query_params() {
+        // Test: embed=1&solved=1 is read, and links to the next puzzle don't carry it along
+        let params: QueryParams = serde_urlencoded::from_str("embed=1&solved=1").unwrap();
+        let (_, next) = next_puzzle(
+            8,
+            16,
+            Difficulty::Hard,
+            Variant::Classic,
+            SeedSettings::default(),
+        );
+        let next = serde_json::to_value(next).unwrap();
+
+        assert_eq!((params.embed, params.solved), (Some(1), Some(1)));
+        assert!(next.get("embed").is_none());
+        assert!(next.get("solved").is_none());
+    }
+
+    #[test]
+    fn test_next_puzzle_keeps_size_and_difficulty() {
+        // Test: The next puzzle is a new seed on the same size and difficulty, without the old challenge
+        let (route, query) = next_puzzle(
+            8,
+            16,
+            Difficulty::Hard,
+            Variant::Classic,
+            SeedSettings::default(),
+        );
+
+        assert!(matches!(
+            route,
+            Route::Game {
+                width: 8,
+                height: 16,
+                ..
+            }
+        ));
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            serde_json::json!({ "difficulty": "Hard" })
+        );
+
+        let (route, _) = next_puzzle(
+            8,
+            16,
+            Difficulty::Hard,
+            Variant::Toroidal,
+            SeedSettings::default(),
+        );
+        assert!(matches!(
+            route,
+            Route::Torus {
+                width: 8,
+                height: 16,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_board_size_follows_grid() {
+        // Test: Navigation uses the generated grid's size, and the route's only before there is a grid
+        assert_eq!(GameState::default().board_size((6, 12)), (6, 12));
+
+        let state = two_island_state();
+        assert_eq!(state.board_size((6, 12)), (5, 5));
+        assert!(size_mismatch((6, 12), &state.grid).is_some());
+        assert_eq!(size_mismatch((5, 5), &state.grid), None);
+    }
+
+    #[test]
+    fn test_drag_bridge_single_and_double() {
+        // Test: A drag builds one more bridge, a two finger drag a double, or a single where a double won't fit
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        let mut state = two_island_state();
+        assert_eq!(
+            state.drag_bridge(line, false),
+            Ok(hashi::BridgeType::Single)
+        );
+        assert_eq!(state.moves, 1);
+
+        // the island at (1, 2) only needs one, so there's no room for the second strand
+        let mut state = two_island_state();
+        assert_eq!(state.drag_bridge(line, true), Ok(hashi::BridgeType::Single));
+        assert_eq!(state.moves, 1);
+
+        state
+            .grid
+            .islands
+            .get_mut(&line.start)
+            .unwrap()
+            .required_bridges = 2;
+        state.grid.remove_bridge(line);
+        assert_eq!(state.drag_bridge(line, true), Ok(hashi::BridgeType::Double));
+        assert_eq!(state.history.len(), 3);
+    }
+
+    #[test]
+    fn test_place_chosen_builds_doubles_in_one_go() {
+        // Test: With doubles chosen two picked islands get a double, where it fits, and restarting keeps the choice
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        let mut state = two_island_state();
+        assert_eq!(state.place_chosen(line), Ok(hashi::BridgeType::Single));
+
+        let mut state = two_island_state();
+        state.place_double = true;
+        // the island at (1, 2) only needs one
+        assert_eq!(state.place_chosen(line), Ok(hashi::BridgeType::Single));
+        state
+            .grid
+            .islands
+            .get_mut(&line.start)
+            .unwrap()
+            .required_bridges = 2;
+        state.grid.remove_bridge(line);
+        assert_eq!(state.place_chosen(line), Ok(hashi::BridgeType::Double));
+        assert_eq!(state.moves, 3);
+        assert!(state.restart().place_double);
+    }
+
+    #[test]
+    fn test_island_key_round_trip() {
+        // Test: The attribute marking an island's drawing reads back as its position
+        let position = Position { x: 12, y: 3 };
+        assert_eq!(parse_island_key(&island_key(position)), Some(position));
+        assert_eq!(parse_island_key("12"), None);
+        assert_eq!(parse_island_key("a,3"), None);
+    }
+
+    #[test]
+    fn test_cycle_bridge_steps_round() {
+        // Test: Cycling a line goes single, double, then none, recording each move for replays
+        let mut state = two_island_state();
+        state
+            .grid
+            .islands
+            .get_mut(&Position { x: 1, y: 2 })
+            .unwrap()
+            .required_bridges = 2;
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        state.cycle_bridge(line).unwrap();
+        assert_eq!(
+            state.grid.bridges.get(&line),
+            Some(&hashi::BridgeType::Single)
+        );
+        state.cycle_bridge(line).unwrap();
+        assert_eq!(
+            state.grid.bridges.get(&line),
+            Some(&hashi::BridgeType::Double)
+        );
+        state.cycle_bridge(line).unwrap();
+        assert!(state.grid.bridges.is_empty());
+
+        assert_eq!(state.moves, 3);
+        let kinds: Vec<MoveKind> = state.history.iter().map(|m| m.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![MoveKind::Add, MoveKind::Upgrade, MoveKind::Clear]
+        );
+    }
+
+    #[test]
+    fn test_cycle_bridge_skips_impossible_double() {
+        // Test: A single that can't become a double, as an island is full, cycles straight to none
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        state.cycle_bridge(line).unwrap();
+        state.cycle_bridge(line).unwrap();
+        assert!(state.grid.bridges.is_empty());
+        assert_eq!(state.last_removed, Some((line, hashi::BridgeType::Single)));
+    }
+
+    #[test]
+    fn test_moves_count_placed_and_removed_bridges() {
+        // Test: Taking a bridge away counts as a move, removing nothing does not
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        state.remove_one_bridge(line);
+        assert_eq!(state.moves, 0);
+
+        state.grid.bridges.insert(line, hashi::BridgeType::Single);
+        state.time_elapsed = 9;
+        state.remove_one_bridge(line);
+        assert_eq!(state.moves, 1);
+        assert_eq!(
+            state.history,
+            vec![Move {
+                line,
+                kind: MoveKind::Remove,
+                at_seconds: 9
+            }]
+        );
+    }
+
+    #[test]
+    fn test_last_placed_ignores_removals() {
+        // Test: The last placed line is the latest bridge built, and nothing once the latest move took one away
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        assert_eq!(state.last_placed(), None);
+
+        state.place_bridge(line).unwrap();
+        assert_eq!(state.last_placed(), Some(line));
+
+        state.remove_one_bridge(line);
+        assert_eq!(state.last_placed(), None);
+    }
+
+    #[test]
+    fn test_deselect_clears_selection_and_shudder() {
+        // Test: Tapping away from the islands drops the selection and the shudder, nothing else
+        let mut state = two_island_state();
+        state.selected = Some(Position { x: 1, y: 2 });
+        state.shuddered_island = Some(Position { x: 1, y: 2 });
+        state.hovered = Some(Position { x: 4, y: 2 });
+
+        state.deselect();
+
+        assert_eq!(state.selected, None);
+        assert_eq!(state.shuddered_island, None);
+        assert_eq!(state.hovered, Some(Position { x: 4, y: 2 }));
+    }
+
+    #[test]
+    fn test_toggle_annotation() {
+        // Test: Marking a line twice clears the mark, and marks never count as bridges
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        state.toggle_annotation(line);
+        assert!(state.annotations.contains(&line));
+        assert!(state.grid.bridges.is_empty());
+        assert_eq!(state.satisfied_count(), (0, 2));
+
+        state.toggle_annotation(line);
+        assert!(state.annotations.is_empty());
+    }
+
+    #[test]
+    fn test_surrender_reveals_solution() {
+        // Test: Surrendering replaces the player's bridges with a complete solution
+        let mut state = two_island_state();
+        state
+            .grid
+            .islands
+            .get_mut(&Position { x: 1, y: 2 })
+            .unwrap()
+            .required_bridges = 2;
+        state.selected = Some(Position { x: 1, y: 2 });
+
+        state.surrender();
+
+        assert!(state.surrendered);
+        assert!(!state.solution_missing);
+        assert!(state.grid.is_complete());
+        assert_eq!(state.selected, None);
+    }
+
+    #[test]
+    fn test_hint_circles_island_then_builds_bridge() {
+        // Test: The first hint only circles a forced island, the second builds one of its bridges as a move
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state
+            .grid
+            .islands
+            .get_mut(&Position { x: 1, y: 2 })
+            .unwrap()
+            .required_bridges = 2;
+
+        state.take_hint();
+        assert_eq!(state.hint, Some(Hint::Island(Position { x: 1, y: 2 })));
+        assert!(state.grid.bridges.is_empty());
+
+        state.take_hint();
+        assert_eq!(
+            state.grid.bridges.get(&line),
+            Some(&hashi::BridgeType::Single)
+        );
+        assert_eq!(state.moves, 1);
+        assert_eq!(state.hint, None);
+    }
+
+    #[test]
+    fn test_hint_with_nothing_forced() {
+        // Test: A solved board has no forced island left to point at
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state
+            .grid
+            .islands
+            .get_mut(&Position { x: 4, y: 2 })
+            .unwrap()
+            .required_bridges = 1;
+        state.place_bridge(line).unwrap();
+
+        assert_eq!(state.next_forced_island(), None);
+        state.take_hint();
+        assert_eq!(state.hint, Some(Hint::NoneFound));
+    }
+
+    #[test]
+    fn test_auto_lock_on_satisfaction() {
+        // Test: With auto-lock on, an island is locked the moment its clue is met and unlocked if a bridge is taken away
+        let mut state = two_island_state();
+        state.auto_lock = true;
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        state.place_bridge(line).unwrap();
+        assert_eq!(state.locked, BTreeSet::from([Position { x: 1, y: 2 }]));
+        assert!(state.touches_locked(line));
+
+        state.remove_one_bridge(line);
+        assert!(state.locked.is_empty());
+    }
+
+    #[test]
+    fn test_announcement_describes_last_move() {
+        // Test: Screen readers hear the bridge now on the line, and which islands it completed
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        assert_eq!(state.announcement(), "");
+
+        state.place_bridge(line).unwrap();
+        assert_eq!(
+            state.announcement(),
+            "Single bridge between column 1 row 2 and column 4 row 2. \
+             Island at column 1 row 2 is complete."
+        );
+
+        state.remove_one_bridge(line);
+        assert_eq!(
+            state.announcement(),
+            "No bridge between column 1 row 2 and column 4 row 2."
+        );
+    }
+
+    #[test]
+    fn test_component_of_follows_bridges() {
+        // Test: An island's network is everything reachable over placed bridges, and just itself when unbridged
+        let grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[
+                (Position { x: 0, y: 0 }, 1),
+                (Position { x: 3, y: 0 }, 2),
+                (Position { x: 3, y: 3 }, 1),
+                (Position { x: 0, y: 3 }, 0),
+            ],
+        )
+        .unwrap();
+        let mut state = GameState {
+            grid,
+            ..GameState::default()
+        };
+        for (a, b) in [((0, 0), (3, 0)), ((3, 0), (3, 3))] {
+            let line =
+                BridgeLine::new(Position { x: a.0, y: a.1 }, Position { x: b.0, y: b.1 }).unwrap();
+            state.place_bridge(line).unwrap();
+        }
+
+        assert_eq!(
+            state.component_of(Position { x: 0, y: 0 }),
+            BTreeSet::from([
+                Position { x: 0, y: 0 },
+                Position { x: 3, y: 0 },
+                Position { x: 3, y: 3 },
+            ])
+        );
+        assert_eq!(
+            state.component_of(Position { x: 0, y: 3 }),
+            BTreeSet::from([Position { x: 0, y: 3 }])
+        );
+    }
+
+    #[test]
+    fn test_island_label() {
+        // Test: An island's accessible name gives its place, its clue and the bridges it has
+        assert_eq!(
+            island_label(Position { x: 3, y: 5 }, 4, 2),
+            "Island at column 3 row 5, needs 4 bridges, has 2"
+        );
+        assert_eq!(
+            island_label(Position { x: 0, y: 0 }, 1, 0),
+            "Island at column 0 row 0, needs 1 bridge, has 0"
+        );
+    }
+
+    #[test]
+    fn test_toggle_lock_only_satisfied() {
+        // Test: Islands can be locked by hand once satisfied, and unlocked again
+        let mut state = two_island_state();
+        let island = Position { x: 1, y: 2 };
+        state.toggle_lock(island);
+        assert!(state.locked.is_empty());
+
+        state
+            .place_bridge(BridgeLine::new(island, Position { x: 4, y: 2 }).unwrap())
+            .unwrap();
+        assert!(state.locked.is_empty());
+        state.toggle_lock(island);
+        assert!(state.locked.contains(&island));
+        state.toggle_lock(island);
+        assert!(state.locked.is_empty());
+    }
+
+    #[test]
+    fn test_fill_opening_builds_free_bridges() {
+        // Test: Opening bridges are built as replayable moves without counting as the player's, and come back on restart
+        let mut state = two_island_state();
+        state
+            .grid
+            .islands
+            .get_mut(&Position { x: 1, y: 2 })
+            .unwrap()
+            .required_bridges = 2;
+        state.fill_opening();
+
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        assert_eq!(
+            state.grid.bridges.get(&line),
+            Some(&hashi::BridgeType::Double)
+        );
+        assert_eq!(state.moves, 0);
+        assert_eq!(state.history.len(), 2);
+        assert!(state.opening_filled);
+
+        let restarted = state.restart();
+        assert_eq!(restarted.grid.bridges, state.grid.bridges);
+        assert!(restarted.opening_filled);
+    }
+
+    #[test]
+    fn test_has_progress_only_mid_solve() {
+        // Test: Only an unfinished board with bridges on it counts as progress worth confirming
+        let mut state = two_island_state();
+        assert!(!state.has_progress());
+
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state.place_bridge(line).unwrap();
+        assert!(state.has_progress());
+
+        state.surrender();
+        assert!(!state.has_progress());
+    }
+
+    #[test]
+    fn test_restart_clears_progress_only() {
+        // Test: Restarting keeps the puzzle, seed and challenge but clears bridges, moves and time
+        let mut state = two_island_state();
+        state.puzzle_id = 42;
+        state.challenge_time = Some(90);
+        state.time_elapsed = 30;
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state.place_bridge(line).unwrap();
+
+        let restarted = state.restart();
+
+        assert!(restarted.grid.bridges.is_empty());
+        assert_eq!(restarted.grid.islands, state.grid.islands);
+        assert_eq!(restarted.puzzle_id, 42);
+        assert_eq!(restarted.challenge_time, Some(90));
+        assert_eq!((restarted.moves, restarted.time_elapsed), (0, 0));
+        assert!(restarted.history.is_empty());
+    }
+
+    #[test]
+    fn test_surrender_unsolvable_puzzle() {
+        // Test: Surrendering on a puzzle with no solution flags it instead of changing the board
+        let mut state = two_island_state();
+
+        state.surrender();
+
+        assert!(state.surrendered);
+        assert!(state.solution_missing);
+        assert!(state.grid.bridges.is_empty());
+    }
+
+    #[test]
+    fn test_colorblind_palette_avoids_red_green() {
+        // Test: The colorblind palette does not use the default green/red pairing
+        let default = Palette::for_settings(&Settings::default());
+        let colorblind = Palette::for_settings(&Settings {
+            colorblind: true,
+            ..Settings::default()
+        });
+
+        assert_ne!(colorblind.satisfied, default.satisfied);
+        assert_ne!(colorblind.error, default.error);
+    }
+
+    #[test]
+    fn test_dark_palette_changes_board_colors() {
+        // Test: Dark mode swaps the island and bridge colors but keeps the accent colors
+        let light = Palette::for_settings(&Settings::default());
+        let dark = Palette::for_settings(&Settings {
+            dark_mode: true,
+            ..Settings::default()
+        });
+
+        assert_ne!(dark.island_fill, light.island_fill);
+        assert_ne!(dark.bridge, light.bridge);
+        assert_eq!(dark.selected, light.selected);
+    }
+
+    #[test]
+    fn test_completion_report_lists_unsatisfied_islands() {
+        // Test: The report names every island that is short of bridges
+        let state = two_island_state();
+        let report = state.completion_report();
+
+        assert_eq!(
+            report.unsatisfied,
+            vec![Position { x: 1, y: 2 }, Position { x: 4, y: 2 }]
+        );
+        assert!(!report.connected);
+        assert!(!report.is_complete());
+    }
+
+    #[test]
+    fn test_completion_report_connected_but_unsatisfied() {
+        // Test: A connected network can still have islands with the wrong count
+        let mut state = two_island_state();
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state.grid.add_bridge(bridge).unwrap();
+
+        let report = state.completion_report();
+        assert_eq!(report.unsatisfied, vec![Position { x: 4, y: 2 }]);
+        assert!(report.connected);
+    }
+
+    #[test]
+    fn test_buildable_islands_none_selected() {
+        // Test: Nothing is highlighted until an island is selected
+        let state = two_island_state();
+        assert!(state.buildable_islands().is_empty());
+    }
+
+    #[test]
+    fn test_buildable_islands_respects_capacity() {
+        // Test: Islands in line are reachable until a clue would be exceeded
+        let mut state = two_island_state();
+        state.selected = Some(Position { x: 4, y: 2 });
+        assert_eq!(state.buildable_islands(), vec![Position { x: 1, y: 2 }]);
+
+        // the clue-1 island is now full, so a second bridge is not possible
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state.grid.add_bridge(bridge).unwrap();
+        assert!(state.buildable_islands().is_empty());
+    }
+
+    #[test]
+    fn test_buildable_islands_excludes_diagonal() {
+        // Test: Islands that are not in line with the selection are never reachable
+        let mut state = two_island_state();
+        state.grid.islands.insert(
+            Position { x: 2, y: 4 },
+            Island {
+                required_bridges: 1,
+            },
+        );
+        state.selected = Some(Position { x: 1, y: 2 });
+        assert_eq!(state.buildable_islands(), vec![Position { x: 4, y: 2 }]);
+    }
+
+    #[test]
+    fn test_click_bridge_can_need_a_double_click() {
+        // Test: Without confirming a click removes a strand, with it the first click only arms the bridge
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        let mut state = two_island_state();
+        state.place_bridge(line).unwrap();
+
+        state.click_bridge(line, true, 1000.0);
+        assert_eq!(state.armed_removal, Some((line, 1000.0)));
+        assert!(state.grid.bridges.contains_key(&line));
+
+        // too slow, so it only arms again
+        state.click_bridge(line, true, 2000.0);
+        assert!(state.grid.bridges.contains_key(&line));
+
+        state.click_bridge(line, true, 2300.0);
+        assert_eq!(state.armed_removal, None);
+        assert!(state.grid.bridges.is_empty());
+
+        state.place_bridge(line).unwrap();
+        state.click_bridge(line, false, 0.0);
+        assert!(state.grid.bridges.is_empty());
+    }
+
+    #[test]
+    fn test_locked_bridge_ignores_clicks() {
+        // Test: Clicking or cycling a bridge that came with the puzzle explains why instead of removing it
+        let mut state = two_island_state();
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state.grid.add_bridge(line).unwrap();
+        state.grid.lock_bridge(line).unwrap();
+
+        state.click_bridge(line, false, 0.0);
+        assert!(state.grid.bridges.contains_key(&line));
+        assert_eq!(state.moves, 0);
+        assert_eq!(state.rejection.map(|(count, _)| count), Some(1));
+
+        assert_eq!(
+            state.cycle_bridge(line),
+            Err(HashiError::LockedBridge { line })
+        );
+        assert!(state.grid.bridges.contains_key(&line));
+    }
+
+    #[test]
+    fn test_puzzle_key_tells_puzzles_apart() {
+        // Test: The same seed at another difficulty is another puzzle, a hand-made one goes by its code
+        let state = two_island_state();
+        let harder = GameState {
+            difficulty: Difficulty::Hard,
+            ..state.clone()
+        };
+        assert_ne!(state.puzzle_key(), harder.puzzle_key());
+        assert_eq!(state.puzzle_key(), state.clone().puzzle_key());
+
+        let hand_made = GameState {
+            hand_made: true,
+            ..state.clone()
+        };
+        assert_eq!(hand_made.puzzle_key(), state.grid.to_code());
+
+        let from_first_move = GameState {
+            timer_on_first_move: true,
+            ..state.clone()
+        };
+        assert_ne!(state.puzzle_key(), from_first_move.puzzle_key());
+
+        let spanning = GameState {
+            seed_settings: SeedSettings {
+                generator: GenAlgo::SpanningTree,
+                ..SeedSettings::default()
+            },
+            ..state.clone()
+        };
+        assert_ne!(state.puzzle_key(), spanning.puzzle_key());
+        let symmetric = GameState {
+            seed_settings: SeedSettings {
+                symmetric: true,
+                ..SeedSettings::default()
+            },
+            ..state.clone()
+        };
+        assert_ne!(state.puzzle_key(), symmetric.puzzle_key());
+        assert_ne!(spanning.puzzle_key(), symmetric.puzzle_key());
+        let varied = GameState {
+            seed_settings: SeedSettings {
+                vary_clues: true,
+                ..SeedSettings::default()
+            },
+            ..state.clone()
+        };
+        assert_ne!(state.puzzle_key(), varied.puzzle_key());
+        assert_ne!(symmetric.puzzle_key(), varied.puzzle_key());
+
+        // keys stay as they were saved before, so best times already kept still match
+        assert!(!state.puzzle_key().contains('&'));
+        assert!(
+            GameState {
+                timer_on_first_move: true,
+                ..spanning.clone()
+            }
+            .puzzle_key()
+            .ends_with("/Classic&generator=SpanningTree&timer_on_first_move=1")
+        );
+        assert_eq!(
+            from_first_move.puzzle_key(),
+            format!("{}&timer_on_first_move=1", state.puzzle_key())
+        );
+    }
+
+    #[test]
+    fn test_timer_waits_for_first_move() {
+        // Test: With the setting on the timer holds until a bridge is built, the opening bridges don't count
+        let mut state = two_island_state();
+        assert!(state.is_timing());
+
+        state.timer_on_first_move = true;
+        state.fill_opening();
+        assert!(!state.is_timing());
+
+        state.grid.bridges.clear();
+        state
+            .place_bridge(
+                BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap(),
+            )
+            .unwrap();
+        assert!(state.is_timing());
+        assert!(!state.restart().is_timing());
+    }
+
+    #[test]
+    fn test_wrong_move_warning() {
+        // Test: With the setting on, a bridge no solution has is built but warned about, others pass quietly
+        let at = |x, y| Position { x, y };
+        let grid = HashiGrid::from_layout(
+            5,
+            3,
+            &[(at(0, 0), 1), (at(2, 0), 2), (at(4, 0), 2), (at(4, 2), 1)],
+        )
+        .unwrap();
+        let mut state = GameState {
+            grid,
+            warn_wrong_moves: true,
+            ..GameState::default()
+        };
+        let middle = BridgeLine::new(at(2, 0), at(4, 0)).unwrap();
+
+        state.place_bridge(middle).unwrap();
+        assert_eq!(state.rejection, None);
+        // the 2s each need their other bridge, so a double between them is wrong
+        assert_eq!(state.place_bridge(middle), Ok(hashi::BridgeType::Double));
+        assert_eq!(state.rejection.map(|(count, _)| count), Some(1));
+
+        state.warn_wrong_moves = false;
+        state.rejection = None;
+        state.grid.bridges.clear();
+        state.place_bridge(middle).unwrap();
+        state.place_bridge(middle).unwrap();
+        assert_eq!(state.rejection, None);
+    }
+
+    #[test]
+    fn test_rejected_bridge_explains_why() {
+        // Test: A refused bridge gives the player the rule it broke, numbering each refusal so repeats show again
+        let mut state = two_island_state();
+        let diagonal =
+            BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 3 }).unwrap_err();
+        state.reject(&diagonal);
+        assert_eq!(state.rejection, Some((1, rejection_message(&diagonal))));
+        state.reject(&diagonal);
+        assert_eq!(state.rejection.map(|(count, _)| count), Some(2));
+
+        let position = Position { x: 2, y: 2 };
+        let messages = [
+            diagonal,
+            HashiError::CrossesBridge { position },
+            HashiError::CrossesIsland { position },
+            HashiError::IslandFull { position },
+            HashiError::Overwrite { position },
+        ]
+        .map(|error| rejection_message(&error));
+        assert_eq!(
+            messages.iter().collect::<BTreeSet<_>>().len(),
+            messages.len()
+        );
+    }
+
+    #[test]
+    fn test_remove_one_bridge_steps_down() {
+        // Test: Removing from a double leaves a single, removing again clears the line
+        let mut state = two_island_state();
+        state
+            .grid
+            .islands
+            .get_mut(&Position { x: 1, y: 2 })
+            .unwrap()
+            .required_bridges = 2;
+        let line = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state.grid.add_bridge(line).unwrap();
+        state.grid.add_bridge(line).unwrap();
+
+        state.remove_one_bridge(line);
+        assert_eq!(
+            state.grid.bridges.get(&line),
+            Some(&hashi::BridgeType::Single)
+        );
+
+        state.remove_one_bridge(line);
+        assert!(state.grid.bridges.is_empty());
+    }
+
+    #[test]
+    fn test_blocked_preview_only_for_invalid_bridges() {
+        // Test: A preview is only shown when the hovered island cannot be bridged to
+        let mut state = two_island_state();
+        state.selected = Some(Position { x: 1, y: 2 });
+        state.hovered = Some(Position { x: 4, y: 2 });
+        assert_eq!(state.blocked_preview(), None);
+
+        // an island in the way blocks the bridge
+        state.grid.islands.insert(
+            Position { x: 2, y: 2 },
+            Island {
+                required_bridges: 1,
+            },
+        );
+        assert_eq!(
+            state.blocked_preview(),
+            Some(BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_blocked_preview_ignores_diagonal() {
+        // Test: Islands out of line with the selection get no preview line
+        let mut state = two_island_state();
+        state.selected = Some(Position { x: 1, y: 2 });
+        state.hovered = Some(Position { x: 3, y: 4 });
+        assert_eq!(state.blocked_preview(), None);
+    }
+
+    #[test]
+    fn test_encouragement_follows_progress() {
+        // Test: Messages appear at half and most of the islands satisfied, and go once dismissed or solved
+        let at = |x, y| Position { x, y };
+        let grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[
+                (at(0, 0), 1),
+                (at(2, 0), 1),
+                (at(0, 2), 1),
+                (at(2, 2), 2),
+                (at(4, 2), 2),
+            ],
+        )
+        .unwrap();
+        let mut state = GameState::new(grid, 0, Difficulty::default());
+        let build = |state: &mut GameState, a, b| {
+            state.place_bridge(BridgeLine::new(a, b).unwrap()).unwrap();
+        };
+
+        build(&mut state, at(0, 0), at(2, 0));
+        assert_eq!(state.encouragement(), None);
+
+        build(&mut state, at(0, 2), at(2, 2));
+        assert_eq!(state.encouragement(), Some("Halfway there!"));
+
+        build(&mut state, at(2, 2), at(4, 2));
+        assert_eq!(state.encouragement(), Some("Almost done!"));
+
+        state.encouragement_dismissed = true;
+        assert_eq!(state.encouragement(), None);
+        assert!(state.restart().encouragement_dismissed);
+    }
+
+    #[test]
+    fn test_satisfied_count_no_bridges() {
+        // Test: With no bridges placed, no islands are satisfied
+        let state = two_island_state();
+        assert_eq!(state.satisfied_count(), (0, 2));
+    }
+
+    #[test]
+    fn test_satisfied_count_partial() {
+        // Test: A single bridge satisfies the clue-1 island but not the clue-2 island
+        let mut state = two_island_state();
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        state.grid.add_bridge(bridge).unwrap();
+
+        assert_eq!(state.satisfied_count(), (1, 2));
+    }
 }
@@ -1,33 +1,125 @@
 use crate::Route;
+use crate::components::daily::{DailyDate, DailyStreak};
+use crate::components::game::QueryParams;
+use crate::components::preview::PuzzlePreview;
+use crate::hashi::MIN_GENERATED_SIZE;
+use crate::settings::Settings;
+use std::sync::atomic::{AtomicBool, Ordering};
 use yew::prelude::*;
 use yew_router::prelude::*;
 
 const BUILD_DATE: &str = env!("BUILD_DATE");
 
+pub(super) const MIN_BOARD_SIZE: u8 = MIN_GENERATED_SIZE;
+pub(super) const MAX_BOARD_SIZE: u8 = 30;
+
+/// Parse a custom board size typed by the player, returning a message to show if it is unusable
+pub(super) fn parse_board_size(width: &str, height: &str) -> Result<(u8, u8), String> {
+    let parse = |value: &str, name: &str| -> Result<u8, String> {
+        let value: u8 = value
+            .trim()
+            .parse()
+            .map_err(|_| format!("{} must be a whole number", name))?;
+        if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&value) {
+            return Err(format!(
+                "{} must be between {} and {}",
+                name, MIN_BOARD_SIZE, MAX_BOARD_SIZE
+            ));
+        }
+        Ok(value)
+    };
+
+    Ok((parse(width, "Width")?, parse(height, "Height")?))
+}
+
+/// Set once a first visit has been sent to the tutorial, so if settings can't be saved the
+/// player isn't sent back there every time they come home
+static TUTORIAL_OFFERED: AtomicBool = AtomicBool::new(false);
+
 #[function_component(Home)]
 pub fn home() -> Html {
     let navigator = use_navigator().unwrap();
+    // seeds are picked up front so each button's preview is the puzzle it starts
+    let id_5x10 = *use_state(rand::random::<u64>);
+    let id_8x16 = *use_state(rand::random::<u64>);
+    let id_custom = *use_state(rand::random::<u64>);
+
+    let settings = Settings::load();
+    // links to new puzzles carry the generator settings, so each stays the puzzle its preview showed
+    let new_puzzle_query = QueryParams::new_puzzle(settings.difficulty, settings.seed_settings());
 
     let on_new_game_5x10 = {
         let navigator = navigator.clone();
+        let query = new_puzzle_query.clone();
         Callback::from(move |_| {
-            navigator.push(&Route::Game {
+            let route = Route::Game {
                 width: 5,
                 height: 10,
-                id: rand::random::<u64>(),
-            });
+                id: id_5x10,
+            };
+            let _ = navigator.push_with_query(&route, &query);
         })
     };
     let on_new_game_8x16 = {
         let navigator = navigator.clone();
+        let query = new_puzzle_query.clone();
         Callback::from(move |_| {
-            navigator.push(&Route::Game {
+            let route = Route::Game {
                 width: 8,
                 height: 16,
-                id: rand::random::<u64>(),
-            });
+                id: id_8x16,
+            };
+            let _ = navigator.push_with_query(&route, &query);
         })
     };
+    let first_visit = !settings.seen_tutorial && !TUTORIAL_OFFERED.swap(true, Ordering::Relaxed);
+    let streak = DailyStreak::load().current(DailyDate::today());
+    let custom_width = use_state(|| settings.board_width.to_string());
+    let custom_height = use_state(|| settings.board_height.to_string());
+    let custom_error = use_state(|| None::<String>);
+
+    let on_width_input = {
+        let custom_width = custom_width.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            custom_width.set(input.value());
+        })
+    };
+    let on_height_input = {
+        let custom_height = custom_height.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            custom_height.set(input.value());
+        })
+    };
+    let on_new_game_custom = {
+        let navigator = navigator.clone();
+        let custom_width = custom_width.clone();
+        let custom_height = custom_height.clone();
+        let custom_error = custom_error.clone();
+        let query = new_puzzle_query.clone();
+        Callback::from(
+            move |_| match parse_board_size(&custom_width, &custom_height) {
+                Ok((width, height)) => {
+                    custom_error.set(None);
+                    let route = Route::Game {
+                        width,
+                        height,
+                        id: id_custom,
+                    };
+                    let _ = navigator.push_with_query(&route, &query);
+                }
+                Err(message) => custom_error.set(Some(message)),
+            },
+        )
+    };
+    let on_daily = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| {
+            navigator.push(&Route::Daily);
+        })
+    };
+
     let on_rules = {
         let navigator = navigator.clone();
         Callback::from(move |_| {
@@ -35,6 +127,76 @@ pub fn home() -> Html {
         })
     };
 
+    let on_settings = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| {
+            navigator.push(&Route::Settings);
+        })
+    };
+
+    // a worksheet of the default size and difficulty, with the answers on the next page
+    let on_print = {
+        let navigator = navigator.clone();
+        let (width, height) = (settings.board_width, settings.board_height);
+        let query = QueryParams::page(settings.difficulty);
+        Callback::from(move |_| {
+            let route = Route::Print {
+                width,
+                height,
+                count: 6,
+                seed: rand::random::<u64>(),
+            };
+            let _ = navigator.push_with_query(&route, &query);
+        })
+    };
+
+    // a few small boards to play side by side, at the default difficulty
+    let on_gallery = {
+        let navigator = navigator.clone();
+        let query = QueryParams::page(settings.difficulty);
+        Callback::from(move |_| {
+            let route = Route::Gallery {
+                width: 5,
+                height: 5,
+                seed: rand::random::<u64>(),
+            };
+            let _ = navigator.push_with_query(&route, &query);
+        })
+    };
+
+    // the variant where bridges can wrap round the edges, at the default size
+    let on_torus = {
+        let navigator = navigator.clone();
+        let (width, height) = (settings.board_width, settings.board_height);
+        let query = new_puzzle_query.clone();
+        Callback::from(move |_| {
+            let route = Route::Torus {
+                width,
+                height,
+                id: rand::random::<u64>(),
+            };
+            let _ = navigator.push_with_query(&route, &query);
+        })
+    };
+
+    let on_create = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| {
+            navigator.push(&Route::Create);
+        })
+    };
+
+    let on_tutorial = {
+        let navigator = navigator.clone();
+        Callback::from(move |_| {
+            navigator.push(&Route::Tutorial);
+        })
+    };
+
+    if first_visit {
+        return html! { <Redirect<Route> to={Route::Tutorial} /> };
+    }
+
     html! {
         <div class="home-container">
             <h1 class="home-title">{"Hashi!"}</h1>
@@ -43,14 +205,72 @@ pub fn home() -> Html {
             </p>
             <div class="home-buttons">
                 <button onclick={on_new_game_5x10} class="btn btn-primary">
+                    <PuzzlePreview width={5} height={10} puzzle_id={id_5x10} />
                     {"5x10"}
                 </button>
                 <button onclick={on_new_game_8x16} class="btn btn-primary">
+                    <PuzzlePreview width={8} height={16} puzzle_id={id_8x16} />
                     {"8x16"}
                 </button>
+                <button onclick={on_daily} class="btn btn-primary">
+                    {"📅 Daily Puzzle"}
+                    { if streak > 0 { format!(" · 🔥 {}", streak) } else { String::new() } }
+                </button>
+                <div class="home-custom-size">
+                    <input
+                        type="number"
+                        min={MIN_BOARD_SIZE.to_string()}
+                        max={MAX_BOARD_SIZE.to_string()}
+                        value={(*custom_width).clone()}
+                        oninput={on_width_input}
+                        aria-label="Width"
+                        class="size-input"
+                    />
+                    <span class="size-separator">{"x"}</span>
+                    <input
+                        type="number"
+                        min={MIN_BOARD_SIZE.to_string()}
+                        max={MAX_BOARD_SIZE.to_string()}
+                        value={(*custom_height).clone()}
+                        oninput={on_height_input}
+                        aria-label="Height"
+                        class="size-input"
+                    />
+                    <button onclick={on_new_game_custom} class="btn btn-primary">
+                        { if let Ok((width, height)) = parse_board_size(&custom_width, &custom_height) {
+                            html! { <PuzzlePreview {width} {height} puzzle_id={id_custom} /> }
+                        } else {
+                            html! {}
+                        }}
+                        {"Custom"}
+                    </button>
+                </div>
+                { if let Some(message) = &*custom_error {
+                    html! { <div class="size-error">{ message }</div> }
+                } else {
+                    html! {}
+                }}
                 <button onclick={on_rules} class="btn btn-success">
                     {"View Rules"}
                 </button>
+                <button onclick={on_print} class="btn btn-success">
+                    {"🖨 Print a worksheet"}
+                </button>
+                <button onclick={on_gallery} class="btn btn-success">
+                    {"🧩 Practice gallery"}
+                </button>
+                <button onclick={on_torus} class="btn btn-success">
+                    {"🍩 Wrap-around puzzle"}
+                </button>
+                <button onclick={on_create} class="btn btn-success">
+                    {"✏️ Make a puzzle"}
+                </button>
+                <button onclick={on_tutorial} class="btn btn-success">
+                    {"🎓 Tutorial"}
+                </button>
+                <button onclick={on_settings} class="btn btn-success">
+                    {"⚙️ Settings"}
+                </button>
             </div>
             <footer class="home-footer">
                 <a href="https://github.com/tomos-evans/hashi" target="_blank" rel="noopener noreferrer" class="github-link">
@@ -61,3 +281,32 @@ pub fn home() -> Html {
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_board_size_valid() {
+        // Test: Sizes within the allowed range are accepted, ignoring surrounding whitespace
+        assert_eq!(parse_board_size("6", " 12 "), Ok((6, 12)));
+        assert_eq!(
+            parse_board_size("3", "30"),
+            Ok((MIN_BOARD_SIZE, MAX_BOARD_SIZE))
+        );
+    }
+
+    #[test]
+    fn test_parse_board_size_out_of_range() {
+        // Test: Sizes outside the allowed range are rejected
+        assert!(parse_board_size("2", "10").is_err());
+        assert!(parse_board_size("10", "31").is_err());
+    }
+
+    #[test]
+    fn test_parse_board_size_not_a_number() {
+        // Test: Non-numeric input is rejected rather than silently defaulted
+        assert!(parse_board_size("", "10").is_err());
+        assert!(parse_board_size("5", "ten").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use crate::components::board::line_segments;
+use crate::hashi::HashiGrid;
+use yew::prelude::*;
+
+/// Furthest the board can be zoomed in
+const MAX_ZOOM: f64 = 4.0;
+
+/// The part of the board on show when zoomed in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// 1 shows the whole board
+    pub zoom: f64,
+    /// Point the view is centered on, in SVG units
+    pub center: (f64, f64),
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            zoom: 1.0,
+            center: (0.0, 0.0),
+        }
+    }
+}
+
+impl Viewport {
+    /// The `(x, y, width, height)` viewBox showing this part of the board, kept within the
+    /// board's `extent` as given by `board_extent`
+    pub fn view_box(&self, extent: (f64, f64, f64, f64)) -> (f64, f64, f64, f64) {
+        let (left, top, full_width, full_height) = extent;
+        let width = full_width / self.zoom;
+        let height = full_height / self.zoom;
+        let x = (self.center.0 - width / 2.0).clamp(left, left + full_width - width);
+        let y = (self.center.1 - height / 2.0).clamp(top, top + full_height - height);
+        (x, y, width, height)
+    }
+
+    /// Zoom in (factor above 1) or out, keeping the middle of the current view in place
+    pub fn zoom_by(&mut self, factor: f64, extent: (f64, f64, f64, f64)) {
+        let (x, y, width, height) = self.view_box(extent);
+        self.center = (x + width / 2.0, y + height / 2.0);
+        self.zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > 1.0
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MinimapProps {
+    pub grid: HashiGrid,
+    pub viewport: Viewport,
+    /// The part of the board the game shows, which the viewport is kept within
+    pub extent: (f64, f64, f64, f64),
+    /// Called with the board point that was tapped, in SVG units
+    pub on_recenter: Callback<(f64, f64)>,
+}
+
+/// The whole board in miniature with the zoomed-in view outlined, tapping it moves the view there
+#[function_component(Minimap)]
+pub fn minimap(props: &MinimapProps) -> Html {
+    let grid = &props.grid;
+    let full_width = grid.width as f64 * 100.0 + 100.0;
+    let full_height = grid.height as f64 * 100.0 + 100.0;
+    let (x, y, width, height) = props.viewport.view_box(props.extent);
+
+    let onclick = {
+        let on_recenter = props.on_recenter.clone();
+        Callback::from(move |e: MouseEvent| {
+            let target: web_sys::Element = e.target_unchecked_into();
+            let Some(svg) = target.closest("svg").ok().flatten() else {
+                return;
+            };
+            let rect = svg.get_bounding_client_rect();
+            if rect.width() == 0.0 || rect.height() == 0.0 {
+                return;
+            }
+            on_recenter.emit((
+                (e.client_x() as f64 - rect.left()) / rect.width() * full_width - 100.0,
+                (e.client_y() as f64 - rect.top()) / rect.height() * full_height - 100.0,
+            ));
+        })
+    };
+
+    let bridges: Html = grid
+        .bridges
+        .keys()
+        .flat_map(|line| line_segments(grid, line))
+        .map(|(x1, y1, x2, y2)| {
+            html! {
+                <line
+                    x1={x1.to_string()}
+                    y1={y1.to_string()}
+                    x2={x2.to_string()}
+                    y2={y2.to_string()}
+                    class="minimap-bridge"
+                    stroke-width="20"
+                />
+            }
+        })
+        .collect();
+
+    let islands: Html = grid
+        .islands
+        .keys()
+        .map(|position| {
+            html! {
+                <circle
+                    cx={(position.x as i32 * 100).to_string()}
+                    cy={(position.y as i32 * 100).to_string()}
+                    r="35"
+                    class="minimap-island"
+                />
+            }
+        })
+        .collect();
+
+    html! {
+        <svg
+            viewBox={format!("-100 -100 {} {}", full_width, full_height)}
+            preserveAspectRatio="none"
+            class="minimap"
+            style={format!("aspect-ratio: {} / {};", full_width, full_height)}
+            aria-label="Board overview, tap to move the view"
+            {onclick}
+        >
+            { bridges }
+            { islands }
+            <rect
+                x={x.to_string()}
+                y={y.to_string()}
+                width={width.to_string()}
+                height={height.to_string()}
+                class="minimap-viewport"
+                stroke-width="25"
+            />
+        </svg>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extent of a whole 8x16 board
+    const BOARD_8X16: (f64, f64, f64, f64) = (-100.0, -100.0, 900.0, 1700.0);
+
+    #[test]
+    fn test_unzoomed_view_is_whole_board() {
+        // Test: At zoom 1 the view is the full board wherever the center is
+        let viewport = Viewport {
+            zoom: 1.0,
+            center: (900.0, -50.0),
+        };
+        assert_eq!(
+            viewport.view_box(BOARD_8X16),
+            (-100.0, -100.0, 900.0, 1700.0)
+        );
+    }
+
+    #[test]
+    fn test_zoomed_view_stays_on_board() {
+        // Test: A view centered near an edge is pushed back so it never shows past the board's margin
+        let mut viewport = Viewport::default();
+        viewport.zoom_by(2.0, BOARD_8X16);
+        assert_eq!(viewport.view_box(BOARD_8X16), (125.0, 325.0, 450.0, 850.0));
+
+        viewport.center = (10_000.0, -10_000.0);
+        assert_eq!(viewport.view_box(BOARD_8X16), (350.0, -100.0, 450.0, 850.0));
+    }
+
+    #[test]
+    fn test_zoom_is_clamped() {
+        // Test: Zooming can't go past the whole board or beyond the maximum
+        let mut viewport = Viewport::default();
+        viewport.zoom_by(0.5, BOARD_8X16);
+        assert_eq!(viewport.zoom, 1.0);
+        assert!(!viewport.is_zoomed());
+
+        for _ in 0..10 {
+            viewport.zoom_by(2.0, BOARD_8X16);
+        }
+        assert_eq!(viewport.zoom, MAX_ZOOM);
+    }
+}
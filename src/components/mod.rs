@@ -1,4 +1,15 @@
+pub mod board;
+pub mod create;
+pub mod daily;
+pub mod gallery;
 pub mod game;
 pub mod home;
+pub mod minimap;
+pub mod modal;
 pub mod not_found;
+pub mod preview;
+pub mod print;
+pub mod replay;
 pub mod rules;
+pub mod settings;
+pub mod tutorial;
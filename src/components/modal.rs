@@ -0,0 +1,73 @@
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+use yew::prelude::*;
+
+/// Elements that Tab stops on
+const FOCUSABLE: &str = "button, a[href], input, select, [tabindex]:not([tabindex='-1'])";
+
+/// The controls inside a modal that Tab can reach, in page order
+fn focusable(modal: &NodeRef) -> Vec<HtmlElement> {
+    let Some(modal) = modal.cast::<Element>() else {
+        return Vec::new();
+    };
+    let Ok(nodes) = modal.query_selector_all(FOCUSABLE) else {
+        return Vec::new();
+    };
+    (0..nodes.length())
+        .filter_map(|n| nodes.item(n)?.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Keyboard handling for a modal, given the `NodeRef` and `onkeydown` to put on its outermost
+/// element. Opening it moves focus to its first control, Tab and Shift+Tab cycle through its
+/// controls without leaving it, Escape calls `on_escape`, and closing it hands focus back to
+/// whatever had it before
+#[hook]
+pub fn use_modal_keys(on_escape: Callback<()>) -> (NodeRef, Callback<KeyboardEvent>) {
+    let modal = use_node_ref();
+
+    {
+        let modal = modal.clone();
+        use_effect_with((), move |_| {
+            let previous = gloo::utils::document().active_element();
+            if let Some(first) = focusable(&modal).first() {
+                let _ = first.focus();
+            }
+            move || {
+                if let Some(previous) = previous.and_then(|e| e.dyn_into::<HtmlElement>().ok()) {
+                    let _ = previous.focus();
+                }
+            }
+        });
+    }
+
+    let onkeydown = {
+        let modal = modal.clone();
+        Callback::from(move |e: KeyboardEvent| match e.key().as_str() {
+            "Escape" => {
+                e.prevent_default();
+                on_escape.emit(());
+            }
+            "Tab" => {
+                let controls = focusable(&modal);
+                let (Some(first), Some(last)) = (controls.first(), controls.last()) else {
+                    e.prevent_default();
+                    return;
+                };
+                let active = gloo::utils::document().active_element();
+                let is_active = |control: &HtmlElement| active.as_ref() == Some(&**control);
+                // only the ends need help, Tab moves between the controls in between by itself
+                let wrap_to = match e.shift_key() {
+                    true if is_active(first) => last,
+                    false if is_active(last) => first,
+                    _ => return,
+                };
+                e.prevent_default();
+                let _ = wrap_to.focus();
+            }
+            _ => {}
+        })
+    };
+
+    (modal, onkeydown)
+}
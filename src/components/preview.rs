@@ -0,0 +1,50 @@
+use crate::hashi::HashiGrid;
+use crate::settings::Settings;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct PuzzlePreviewProps {
+    pub width: u8,
+    pub height: u8,
+    pub puzzle_id: u64,
+}
+
+/// The islands of the puzzle a button leads to, as plain dots, so its shape is visible before starting it
+#[function_component(PuzzlePreview)]
+pub fn puzzle_preview(props: &PuzzlePreviewProps) -> Html {
+    let grid = use_memo(
+        (props.width, props.height, props.puzzle_id),
+        |&(width, height, id)| {
+            let settings = Settings::load();
+            let options = settings.generation_options(settings.difficulty);
+            HashiGrid::generate_with_options(width, height, id, &options).ok()
+        },
+    );
+    let Some(grid) = grid.as_ref() else {
+        return html! {};
+    };
+
+    let islands: Html = grid
+        .islands
+        .keys()
+        .map(|position| {
+            html! {
+                <circle
+                    cx={(position.x as i32 * 100).to_string()}
+                    cy={(position.y as i32 * 100).to_string()}
+                    r="30"
+                />
+            }
+        })
+        .collect();
+
+    html! {
+        <svg
+            viewBox={format!("-50 -50 {} {}", grid.width as i32 * 100, grid.height as i32 * 100)}
+            class="puzzle-preview"
+            aria-hidden="true"
+        >
+            { islands }
+        </svg>
+    }
+}
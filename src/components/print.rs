@@ -0,0 +1,162 @@
+use crate::Route;
+use crate::components::board::{BridgeLook, board_svg};
+use crate::components::game::{Palette, QueryParams};
+use crate::hashi::{GenerationOptions, HashiError, HashiGrid};
+use crate::settings::Settings;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+/// Most puzzles on one sheet, so a mistyped link can't stall the page generating hundreds
+pub const MAX_SHEET_PUZZLES: u8 = 24;
+
+#[derive(Properties, PartialEq)]
+pub struct PrintSheetProps {
+    pub width: u8,
+    pub height: u8,
+    pub count: u8,
+    pub seed: u64,
+}
+
+/// One puzzle on a printed sheet: the bare clues, and the same islands with a solution drawn in
+#[derive(Debug, Clone, PartialEq)]
+struct SheetPuzzle {
+    puzzle: HashiGrid,
+    answer: HashiGrid,
+}
+
+/// Generate the puzzles for a sheet, the nth from `seed + n` so the same link always prints the
+/// same sheet. Answers come from the solver rather than the generator, like a person checking them would.
+fn build_sheet(
+    width: u8,
+    height: u8,
+    count: u8,
+    seed: u64,
+    options: &GenerationOptions,
+) -> Result<Vec<SheetPuzzle>, HashiError> {
+    (0..count.min(MAX_SHEET_PUZZLES) as u64)
+        .map(|n| {
+            let generated =
+                HashiGrid::generate_with_options(width, height, seed.wrapping_add(n), options)?;
+            let puzzle = generated.clone().wipe_bridges();
+            let mut answer = puzzle.clone();
+            // every generated puzzle has a solution, the generator's own is a fallback that should never be needed
+            answer.bridges = puzzle.solve().unwrap_or(generated.bridges);
+            Ok(SheetPuzzle { puzzle, answer })
+        })
+        .collect()
+}
+
+/// A printable page of puzzles with their answers on the page after
+#[function_component(PrintSheet)]
+pub fn print_sheet(props: &PrintSheetProps) -> Html {
+    let navigator = use_navigator().unwrap();
+    let settings = use_state(Settings::load);
+    let difficulty = use_location()
+        .and_then(|loc| loc.query::<QueryParams>().ok())
+        .unwrap_or_default()
+        .difficulty();
+    let sheet = use_memo(
+        (
+            props.width,
+            props.height,
+            props.count,
+            props.seed,
+            difficulty,
+        ),
+        |&(width, height, count, seed, difficulty)| {
+            build_sheet(width, height, count, seed, &difficulty.options())
+        },
+    );
+
+    let on_back = Callback::from(move |_| {
+        navigator.push(&Route::Home);
+    });
+
+    let on_print = Callback::from(|_| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.print();
+        }
+    });
+
+    let puzzles = match sheet.as_ref() {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            return html! {
+                <div class="rules-container">
+                    <p>{ format!("This sheet couldn't be generated: {}", e) }</p>
+                    <button onclick={on_back} class="btn btn-back">{"Back to Home"}</button>
+                </div>
+            };
+        }
+    };
+
+    // paper is white whatever the screen theme
+    let palette = Palette::for_settings(&Settings {
+        dark_mode: false,
+        ..(*settings).clone()
+    });
+    let look = BridgeLook::new(settings.bridge_style, 0.0);
+    let page = |grids: Vec<&HashiGrid>| -> Html {
+        grids
+            .into_iter()
+            .enumerate()
+            .map(|(i, grid)| {
+                html! {
+                    <figure class="print-puzzle">
+                        { Html::from_html_unchecked(board_svg(grid, &palette, look).into()) }
+                        <figcaption>{ format!("#{}", i + 1) }</figcaption>
+                    </figure>
+                }
+            })
+            .collect()
+    };
+
+    html! {
+        <div class="print-sheet">
+            <div class="print-controls no-print">
+                <button onclick={on_back} class="btn btn-back">{"← Back"}</button>
+                <button onclick={on_print} class="btn btn-primary">{"🖨 Print"}</button>
+            </div>
+            <section class="print-page">
+                <h1>{ format!("Hashi {}x{} {}", props.width, props.height, difficulty) }</h1>
+                <div class="print-grid">
+                    { page(puzzles.iter().map(|p| &p.puzzle).collect()) }
+                </div>
+            </section>
+            <section class="print-page print-answers">
+                <h1>{"Answers"}</h1>
+                <div class="print-grid">
+                    { page(puzzles.iter().map(|p| &p.answer).collect()) }
+                </div>
+            </section>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sheet_is_repeatable_and_solved() {
+        // Test: A sheet is the same for the same seed, its puzzles differ, and every answer meets the clues
+        let options = GenerationOptions::default();
+        let sheet = build_sheet(5, 10, 4, 7, &options).unwrap();
+
+        assert_eq!(sheet, build_sheet(5, 10, 4, 7, &options).unwrap());
+        assert_eq!(sheet.len(), 4);
+        assert_ne!(sheet[0].puzzle, sheet[1].puzzle);
+        for entry in &sheet {
+            assert!(entry.puzzle.bridges.is_empty());
+            assert_eq!(entry.answer.islands, entry.puzzle.islands);
+            assert!(entry.answer.is_complete());
+        }
+    }
+
+    #[test]
+    fn test_build_sheet_caps_count() {
+        // Test: Asking for more puzzles than a sheet holds gives the maximum
+        let sheet = build_sheet(5, 5, u8::MAX, 0, &GenerationOptions::default()).unwrap();
+        assert_eq!(sheet.len(), MAX_SHEET_PUZZLES as usize);
+    }
+}
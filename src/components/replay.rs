@@ -0,0 +1,358 @@
+use crate::Route;
+use crate::components::board::BoardView;
+use crate::components::game::QueryParams;
+use crate::hashi::{BridgeLine, Difficulty, HashiGrid, Position};
+use crate::settings::SeedSettings;
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+use yew_hooks::use_interval;
+use yew_router::prelude::*;
+
+/// What a recorded move did to its bridge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveKind {
+    /// A new single bridge
+    Add,
+    /// A single bridge made double
+    Upgrade,
+    /// One bridge taken away
+    Remove,
+    /// Every bridge on the line taken away at once
+    Clear,
+}
+
+/// A successful change to the board, recorded so the solve can be played back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub line: BridgeLine,
+    pub kind: MoveKind,
+    /// Seconds into the game the move was made
+    pub at_seconds: u32,
+}
+
+impl Move {
+    /// Redo the move on a grid showing the board as it was before it
+    pub fn apply(&self, grid: &mut HashiGrid) {
+        match self.kind {
+            MoveKind::Add | MoveKind::Upgrade => {
+                let _ = grid.add_bridge(self.line);
+            }
+            MoveKind::Remove => {
+                grid.downgrade_bridge(self.line);
+            }
+            MoveKind::Clear => {
+                grid.remove_bridge(self.line);
+            }
+        }
+    }
+}
+
+/// Pack moves into a URL safe string, e.g. `a12.1.2.4.2` for adding a bridge from (1,2) to (4,2) 12 seconds in
+pub fn encode_moves(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .map(|m| {
+            let kind = match m.kind {
+                MoveKind::Add => 'a',
+                MoveKind::Upgrade => 'u',
+                MoveKind::Remove => 'r',
+                MoveKind::Clear => 'c',
+            };
+            format!(
+                "{}{}.{}.{}.{}.{}",
+                kind, m.at_seconds, m.line.start.x, m.line.start.y, m.line.end.x, m.line.end.y
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Read moves back from `encode_moves`, or `None` if any part is malformed
+pub fn decode_moves(encoded: &str) -> Option<Vec<Move>> {
+    if encoded.is_empty() {
+        return Some(Vec::new());
+    }
+
+    encoded
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            let kind = match chars.next()? {
+                'a' => MoveKind::Add,
+                'u' => MoveKind::Upgrade,
+                'r' => MoveKind::Remove,
+                'c' => MoveKind::Clear,
+                _ => return None,
+            };
+            let numbers: Vec<u32> = chars
+                .as_str()
+                .split('.')
+                .map(|n| n.parse().ok())
+                .collect::<Option<_>>()?;
+            let [at_seconds, sx, sy, ex, ey] = numbers[..] else {
+                return None;
+            };
+            let position = |x: u32, y: u32| -> Option<Position> {
+                Some(Position {
+                    x: x.try_into().ok()?,
+                    y: y.try_into().ok()?,
+                })
+            };
+            let line = BridgeLine::new(position(sx, sy)?, position(ex, ey)?).ok()?;
+            Some(Move {
+                line,
+                kind,
+                at_seconds,
+            })
+        })
+        .collect()
+}
+
+/// The moves of a replay link, which names its puzzle with the same parameters as a link to play it
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct ReplayParams {
+    moves: Option<String>,
+}
+
+/// Query string for a link replaying the given moves
+pub fn replay_query(difficulty: Difficulty, seed_settings: SeedSettings, moves: &[Move]) -> String {
+    let moves = ReplayParams {
+        moves: Some(encode_moves(moves)),
+    };
+    format!(
+        "{}&{}",
+        QueryParams::new_puzzle(difficulty, seed_settings).to_query(),
+        serde_urlencoded::to_string(moves).expect("query parameters are plain values")
+    )
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ReplayProps {
+    pub puzzle_id: u64,
+    pub width: u8,
+    pub height: u8,
+}
+
+/// Plays back a shared solve on the same seeded puzzle, with a scrubber to step through the moves
+#[function_component(Replay)]
+pub fn replay(props: &ReplayProps) -> Html {
+    let navigator = use_navigator().unwrap();
+    let location = use_location();
+    let link = location
+        .as_ref()
+        .and_then(|loc| loc.query::<QueryParams>().ok())
+        .unwrap_or_default();
+    let params = location
+        .and_then(|loc| loc.query::<ReplayParams>().ok())
+        .unwrap_or_default();
+    let difficulty = link.difficulty();
+    let seed_settings = link.seed_settings();
+    let moves = params
+        .moves
+        .as_deref()
+        .and_then(decode_moves)
+        .unwrap_or_default();
+    let step = use_state(|| 0_usize);
+    let playing = use_state(|| false);
+
+    let puzzle = use_memo(
+        (
+            props.width,
+            props.height,
+            props.puzzle_id,
+            difficulty,
+            seed_settings,
+        ),
+        |&(width, height, id, difficulty, seed_settings)| {
+            HashiGrid::generate_with_options(width, height, id, &seed_settings.options(difficulty))
+                .ok()
+                .map(HashiGrid::wipe_bridges)
+        },
+    );
+
+    {
+        let step = step.clone();
+        let playing = playing.clone();
+        let total = moves.len();
+        let millis = if *playing { 600 } else { 0 };
+        use_interval(
+            move || {
+                if *step < total {
+                    step.set(*step + 1);
+                } else {
+                    playing.set(false);
+                }
+            },
+            millis,
+        );
+    }
+
+    let on_back = Callback::from(move |_| {
+        navigator.push(&Route::Home);
+    });
+
+    let Some(puzzle) = (*puzzle).clone() else {
+        return html! {
+            <div class="rules-container">
+                <p>{"This replay's puzzle couldn't be generated."}</p>
+                <button onclick={on_back} class="btn btn-back">{"Back to Home"}</button>
+            </div>
+        };
+    };
+
+    let mut grid = puzzle;
+    for m in moves.iter().take(*step) {
+        m.apply(&mut grid);
+    }
+
+    let on_scrub = {
+        let step = step.clone();
+        let playing = playing.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            playing.set(false);
+            step.set(input.value().parse().unwrap_or(0));
+        })
+    };
+
+    let on_play = {
+        let step = step.clone();
+        let playing = playing.clone();
+        let total = moves.len();
+        Callback::from(move |_| {
+            if *step >= total {
+                step.set(0);
+            }
+            playing.set(!*playing);
+        })
+    };
+
+    let status = match step.checked_sub(1).and_then(|i| moves.get(i)) {
+        Some(m) => format!("Move {} / {} at {}s", *step, moves.len(), m.at_seconds),
+        None => format!("Move 0 / {}", moves.len()),
+    };
+
+    html! {
+        <div class="game-wrapper">
+            <div class="game-controls">
+                <button onclick={on_back} class="btn btn-game-large">
+                    {"← Back"}
+                </button>
+                <button onclick={on_play} class="btn btn-game-large success">
+                    { if *playing { "⏸ Pause" } else { "▶ Play" } }
+                </button>
+                <input
+                    type="range"
+                    class="replay-scrubber"
+                    min="0"
+                    max={moves.len().to_string()}
+                    value={step.to_string()}
+                    oninput={on_scrub}
+                    aria-label="Replay position"
+                />
+                <div class="game-progress">{ status }</div>
+            </div>
+            <div class="game-container">
+                <BoardView grid={grid} />
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashi::GenAlgo;
+
+    fn line(sx: u8, sy: u8, ex: u8, ey: u8) -> BridgeLine {
+        BridgeLine::new(Position { x: sx, y: sy }, Position { x: ex, y: ey }).unwrap()
+    }
+
+    #[test]
+    fn test_moves_round_trip() {
+        // Test: Encoded moves decode back to exactly the same list
+        let moves = vec![
+            Move {
+                line: line(1, 2, 4, 2),
+                kind: MoveKind::Add,
+                at_seconds: 3,
+            },
+            Move {
+                line: line(1, 2, 4, 2),
+                kind: MoveKind::Upgrade,
+                at_seconds: 7,
+            },
+            Move {
+                line: line(0, 0, 0, 12),
+                kind: MoveKind::Remove,
+                at_seconds: 65,
+            },
+            Move {
+                line: line(1, 2, 4, 2),
+                kind: MoveKind::Clear,
+                at_seconds: 70,
+            },
+        ];
+
+        let encoded = encode_moves(&moves);
+        assert_eq!(encoded, "a3.1.2.4.2_u7.1.2.4.2_r65.0.0.0.12_c70.1.2.4.2");
+        assert_eq!(decode_moves(&encoded), Some(moves));
+    }
+
+    #[test]
+    fn test_decode_moves_rejects_malformed() {
+        // Test: Garbage, diagonal lines and out of range coordinates are rejected rather than half-applied
+        assert_eq!(decode_moves(""), Some(Vec::new()));
+        assert_eq!(decode_moves("x1.1.2.4.2"), None);
+        assert_eq!(decode_moves("a1.1.2.4"), None);
+        assert_eq!(decode_moves("a1.1.2.4.3"), None);
+        assert_eq!(decode_moves("a1.1.2.400.2"), None);
+    }
+
+    #[test]
+    fn test_replay_query_carries_generator() {
+        // Test: A replay link names the generator settings its puzzle was made with, so it replays on the same puzzle
+        let spanning = SeedSettings {
+            generator: GenAlgo::SpanningTree,
+            symmetric: true,
+            vary_clues: true,
+        };
+        let query = replay_query(Difficulty::Hard, spanning, &[]);
+        let puzzle: QueryParams = serde_urlencoded::from_str(&query).unwrap();
+        let params: ReplayParams = serde_urlencoded::from_str(&query).unwrap();
+
+        assert_eq!(puzzle.difficulty(), Difficulty::Hard);
+        assert_eq!(puzzle.seed_settings(), spanning);
+        assert_eq!(params.moves.as_deref(), Some(""));
+        assert_eq!(
+            replay_query(Difficulty::Hard, SeedSettings::default(), &[]),
+            "difficulty=Hard&moves="
+        );
+    }
+
+    #[test]
+    fn test_apply_moves_rebuilds_board() {
+        // Test: Playing moves back in order reproduces the final board
+        let mut grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[(Position { x: 1, y: 2 }, 2), (Position { x: 4, y: 2 }, 2)],
+        )
+        .unwrap();
+        let bridge = line(1, 2, 4, 2);
+
+        for kind in [MoveKind::Add, MoveKind::Upgrade, MoveKind::Remove] {
+            Move {
+                line: bridge,
+                kind,
+                at_seconds: 0,
+            }
+            .apply(&mut grid);
+        }
+
+        assert_eq!(
+            grid.bridges.get(&bridge),
+            Some(&crate::hashi::BridgeType::Single)
+        );
+    }
+}
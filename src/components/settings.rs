@@ -0,0 +1,248 @@
+use crate::Route;
+use crate::components::home::{MAX_BOARD_SIZE, MIN_BOARD_SIZE, parse_board_size};
+use crate::hashi::{Difficulty, GenAlgo};
+use crate::settings::{BridgeStyle, Settings};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[function_component(SettingsPage)]
+pub fn settings_page() -> Html {
+    let navigator = use_navigator().unwrap();
+    let settings = use_state(Settings::load);
+    let board_width = use_state(|| settings.board_width.to_string());
+    let board_height = use_state(|| settings.board_height.to_string());
+    let size_error = use_state(|| None::<String>);
+
+    // apply a change to the settings and store it straight away
+    let update = {
+        let settings = settings.clone();
+        move |change: fn(&mut Settings, &web_sys::HtmlInputElement)| {
+            let settings = settings.clone();
+            Callback::from(move |e: Event| {
+                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                let mut s = (*settings).clone();
+                change(&mut s, &input);
+                s.save();
+                settings.set(s);
+            })
+        }
+    };
+
+    let on_colorblind = update(|s, input| s.colorblind = input.checked());
+    let on_dark_mode = update(|s, input| s.dark_mode = input.checked());
+    let on_show_timer = update(|s, input| s.show_timer = input.checked());
+    let on_sound_effects = update(|s, input| s.sound_effects = input.checked());
+    let on_show_remaining = update(|s, input| s.show_remaining = input.checked());
+    let on_tap_to_cycle = update(|s, input| s.tap_to_cycle = input.checked());
+    let on_show_grid = update(|s, input| s.show_grid = input.checked());
+    let on_hide_clues = update(|s, input| s.hide_clues = input.checked());
+    let on_symmetric = update(|s, input| s.symmetric = input.checked());
+    let on_vary_clues = update(|s, input| s.vary_clues = input.checked());
+    let on_lock_satisfied = update(|s, input| s.lock_satisfied = input.checked());
+    let on_fill_opening = update(|s, input| s.fill_opening = input.checked());
+    let on_double_click_remove = update(|s, input| s.double_click_remove = input.checked());
+    let on_warn_wrong_moves = update(|s, input| s.warn_wrong_moves = input.checked());
+    let on_timer_on_first_move = update(|s, input| s.timer_on_first_move = input.checked());
+
+    let on_difficulty = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut s = (*settings).clone();
+            if let Some(difficulty) = Difficulty::ALL.get(select.selected_index() as usize) {
+                s.difficulty = *difficulty;
+            }
+            s.save();
+            settings.set(s);
+        })
+    };
+
+    let on_bridge_style = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut s = (*settings).clone();
+            if let Some(style) = BridgeStyle::ALL.get(select.selected_index() as usize) {
+                s.bridge_style = *style;
+            }
+            s.save();
+            settings.set(s);
+        })
+    };
+
+    let on_generator = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut s = (*settings).clone();
+            if let Some(generator) = GenAlgo::ALL.get(select.selected_index() as usize) {
+                s.generator = *generator;
+            }
+            s.save();
+            settings.set(s);
+        })
+    };
+
+    let on_size_input =
+        |size: UseStateHandle<String>, other: UseStateHandle<String>, is_width: bool| {
+            let settings = settings.clone();
+            let size_error = size_error.clone();
+            Callback::from(move |e: InputEvent| {
+                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                size.set(input.value());
+
+                let (width, height) = if is_width {
+                    (input.value(), (*other).clone())
+                } else {
+                    ((*other).clone(), input.value())
+                };
+                match parse_board_size(&width, &height) {
+                    Ok((width, height)) => {
+                        let mut s = (*settings).clone();
+                        s.board_width = width;
+                        s.board_height = height;
+                        s.save();
+                        settings.set(s);
+                        size_error.set(None);
+                    }
+                    Err(message) => size_error.set(Some(message)),
+                }
+            })
+        };
+    let on_width_input = on_size_input(board_width.clone(), board_height.clone(), true);
+    let on_height_input = on_size_input(board_height.clone(), board_width.clone(), false);
+
+    let on_back = Callback::from(move |_| {
+        navigator.push(&Route::Home);
+    });
+
+    html! {
+        <div class="settings-container">
+            <h1>{"Settings"}</h1>
+            <div class="settings-form">
+                <label class="settings-row">
+                    <span>{"Difficulty"}</span>
+                    <select onchange={on_difficulty}>
+                        { for Difficulty::ALL.iter().map(|difficulty| html! {
+                            <option selected={*difficulty == settings.difficulty}>
+                                { difficulty.to_string() }
+                            </option>
+                        }) }
+                    </select>
+                </label>
+                <label class="settings-row">
+                    <span>{"Default board size"}</span>
+                    <span class="home-custom-size">
+                        <input
+                            type="number"
+                            min={MIN_BOARD_SIZE.to_string()}
+                            max={MAX_BOARD_SIZE.to_string()}
+                            value={(*board_width).clone()}
+                            oninput={on_width_input}
+                            aria-label="Width"
+                            class="size-input"
+                        />
+                        <span class="size-separator">{"x"}</span>
+                        <input
+                            type="number"
+                            min={MIN_BOARD_SIZE.to_string()}
+                            max={MAX_BOARD_SIZE.to_string()}
+                            value={(*board_height).clone()}
+                            oninput={on_height_input}
+                            aria-label="Height"
+                            class="size-input"
+                        />
+                    </span>
+                </label>
+                { if let Some(message) = &*size_error {
+                    html! { <div class="size-error">{ message }</div> }
+                } else {
+                    html! {}
+                }}
+                <label class="settings-row">
+                    <span>{"Puzzle generator"}</span>
+                    <select onchange={on_generator}>
+                        { for GenAlgo::ALL.iter().map(|generator| html! {
+                            <option selected={*generator == settings.generator}>
+                                { generator.to_string() }
+                            </option>
+                        }) }
+                    </select>
+                </label>
+                <label class="settings-row">
+                    <span>{"Symmetric puzzles"}</span>
+                    <input type="checkbox" checked={settings.symmetric} onchange={on_symmetric} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Varied clues"}</span>
+                    <input type="checkbox" checked={settings.vary_clues} onchange={on_vary_clues} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Bridge style"}</span>
+                    <select onchange={on_bridge_style}>
+                        { for BridgeStyle::ALL.iter().map(|style| html! {
+                            <option selected={*style == settings.bridge_style}>
+                                { style.to_string() }
+                            </option>
+                        }) }
+                    </select>
+                </label>
+                <label class="settings-row">
+                    <span>{"Tap between islands to cycle bridges"}</span>
+                    <input type="checkbox" checked={settings.tap_to_cycle} onchange={on_tap_to_cycle} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Lock islands once they have all their bridges"}</span>
+                    <input type="checkbox" checked={settings.lock_satisfied} onchange={on_lock_satisfied} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Double-click a bridge to remove it"}</span>
+                    <input type="checkbox" checked={settings.double_click_remove} onchange={on_double_click_remove} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Warn when a bridge can't be part of any solution"}</span>
+                    <input type="checkbox" checked={settings.warn_wrong_moves} onchange={on_warn_wrong_moves} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Start with the obvious bridges built (solves won't count for best scores)"}</span>
+                    <input type="checkbox" checked={settings.fill_opening} onchange={on_fill_opening} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Show bridges still needed on each island"}</span>
+                    <input type="checkbox" checked={settings.show_remaining} onchange={on_show_remaining} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Show grid lines and coordinates"}</span>
+                    <input type="checkbox" checked={settings.show_grid} onchange={on_show_grid} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Hide clue numbers (for screenshots)"}</span>
+                    <input type="checkbox" checked={settings.hide_clues} onchange={on_hide_clues} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Show timer (off for zen mode)"}</span>
+                    <input type="checkbox" checked={settings.show_timer} onchange={on_show_timer} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Start the timer on the first bridge"}</span>
+                    <input type="checkbox" checked={settings.timer_on_first_move} onchange={on_timer_on_first_move} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Sound effects"}</span>
+                    <input type="checkbox" checked={settings.sound_effects} onchange={on_sound_effects} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Dark mode"}</span>
+                    <input type="checkbox" checked={settings.dark_mode} onchange={on_dark_mode} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Colorblind mode"}</span>
+                    <input type="checkbox" checked={settings.colorblind} onchange={on_colorblind} />
+                </label>
+            </div>
+            <button onclick={on_back} class="btn btn-back">
+                {"Back to Home"}
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,146 @@
+use crate::components::game::Game;
+use crate::hashi::{BridgeLine, BridgeType, HashiGrid, Position};
+use crate::settings::Settings;
+use yew::prelude::*;
+
+pub const TUTORIAL_WIDTH: u8 = 4;
+pub const TUTORIAL_HEIGHT: u8 = 4;
+
+/// The island the player is walked through first, a 3 in the top left corner
+const FIRST: Position = Position { x: 0, y: 0 };
+/// The 2 to its right, which must be joined to the first island with a double bridge
+const SECOND: Position = Position { x: 3, y: 0 };
+/// The 1 below the first island
+const THIRD: Position = Position { x: 0, y: 3 };
+
+/// The fixed puzzle played in the tutorial, with a single solution
+pub fn tutorial_grid() -> HashiGrid {
+    // safe unwrap, the islands are distinct and on the grid
+    HashiGrid::from_layout(
+        TUTORIAL_WIDTH,
+        TUTORIAL_HEIGHT,
+        &[(FIRST, 3), (SECOND, 2), (THIRD, 1)],
+    )
+    .unwrap()
+}
+
+/// Where the player is in the tutorial, moved along by watching the game's state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    SelectIsland,
+    BuildBridge,
+    AddDouble,
+    RemoveBridge,
+    Finish,
+}
+
+impl TutorialStep {
+    /// The step that follows once the board shows this one has been done, or the same step if not
+    pub fn advance(self, grid: &HashiGrid, selected: Option<Position>) -> Self {
+        let line = BridgeLine::new(FIRST, SECOND).unwrap(); // safe unwrap, the islands are in line
+        let bridge = grid.bridges.get(&line);
+        match self {
+            TutorialStep::SelectIsland if selected == Some(FIRST) => TutorialStep::BuildBridge,
+            TutorialStep::BuildBridge if bridge.is_some() => TutorialStep::AddDouble,
+            TutorialStep::AddDouble if bridge == Some(&BridgeType::Double) => {
+                TutorialStep::RemoveBridge
+            }
+            TutorialStep::RemoveBridge if bridge != Some(&BridgeType::Double) => {
+                TutorialStep::Finish
+            }
+            step => step,
+        }
+    }
+
+    pub fn prompt(self) -> &'static str {
+        match self {
+            TutorialStep::SelectIsland => "Tap the island marked 3 to select it",
+            TutorialStep::BuildBridge => "Now tap the 2 to build a bridge between them",
+            TutorialStep::AddDouble => "Build the same bridge again to make it a double",
+            TutorialStep::RemoveBridge => "Tap a bridge to take one away",
+            TutorialStep::Finish => {
+                "Now finish the puzzle: every island needs exactly its number of bridges"
+            }
+        }
+    }
+
+    /// The island the player should tap next, if the step is about a particular island
+    pub fn highlight(self, selected: Option<Position>) -> Option<Position> {
+        match self {
+            TutorialStep::SelectIsland => Some(FIRST),
+            TutorialStep::BuildBridge => Some(SECOND),
+            TutorialStep::AddDouble if selected == Some(FIRST) => Some(SECOND),
+            TutorialStep::AddDouble => Some(FIRST),
+            TutorialStep::RemoveBridge | TutorialStep::Finish => None,
+        }
+    }
+}
+
+#[function_component(Tutorial)]
+pub fn tutorial() -> Html {
+    // starting the tutorial counts as having seen it, so skipping it doesn't bring it back
+    use_effect_with((), |_| {
+        let mut settings = Settings::load();
+        if !settings.seen_tutorial {
+            settings.seen_tutorial = true;
+            settings.save();
+        }
+        || ()
+    });
+
+    html! {
+        <Game
+            width={TUTORIAL_WIDTH}
+            height={TUTORIAL_HEIGHT}
+            puzzle_id={0}
+            title={AttrValue::from("Tutorial")}
+            tutorial={true}
+        />
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tutorial_grid_has_one_solution() {
+        // Test: The tutorial puzzle can only be finished one way
+        assert_eq!(tutorial_grid().count_solutions(2), 1);
+    }
+
+    #[test]
+    fn test_tutorial_steps_follow_the_board() {
+        // Test: Each step only moves on once the player has done what it asks
+        let mut grid = tutorial_grid();
+        let line = BridgeLine::new(FIRST, SECOND).unwrap();
+
+        let step = TutorialStep::SelectIsland;
+        assert_eq!(step.advance(&grid, Some(SECOND)), step);
+        let step = step.advance(&grid, Some(FIRST));
+        assert_eq!(step, TutorialStep::BuildBridge);
+
+        assert_eq!(step.advance(&grid, Some(FIRST)), step);
+        grid.add_bridge(line).unwrap();
+        let step = step.advance(&grid, None);
+        assert_eq!(step, TutorialStep::AddDouble);
+
+        grid.add_bridge(line).unwrap();
+        let step = step.advance(&grid, None);
+        assert_eq!(step, TutorialStep::RemoveBridge);
+
+        assert_eq!(step.advance(&grid, None), step);
+        grid.bridges.insert(line, BridgeType::Single);
+        let step = step.advance(&grid, None);
+        assert_eq!(step, TutorialStep::Finish);
+        assert_eq!(step.advance(&grid, None), TutorialStep::Finish);
+    }
+
+    #[test]
+    fn test_tutorial_highlight_follows_selection() {
+        // Test: The double bridge step points at whichever island is still to be tapped
+        assert_eq!(TutorialStep::AddDouble.highlight(None), Some(FIRST));
+        assert_eq!(TutorialStep::AddDouble.highlight(Some(FIRST)), Some(SECOND));
+        assert_eq!(TutorialStep::Finish.highlight(None), None);
+    }
+}
@@ -1,11 +1,43 @@
 use rand::SeedableRng;
 use rand::prelude::*;
-use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use thiserror::Error;
+
+mod compact;
+mod solver;
 // use web_sys::console;
 // use web_sys::wasm_bindgen::JsValue;
 // console::log_1(&JsValue::from_str("game.rs loaded"));
 
+/// Smallest width or height the generator can build a meaningful puzzle in
+pub const MIN_GENERATED_SIZE: u8 = 3;
+
+/// Most island placement steps one call to generate may take across all its attempts and retries
+const GENERATION_BUDGET: usize = 300_000;
+
+/// Most search steps `from_code` may take checking a code's locked bridges, far more than any
+/// real puzzle needs, so a code made up to stall the page is refused instead
+const CODE_CHECK_BUDGET: usize = 100_000;
+
+/// Chance each extra bridge between islands already in sight is tried, closing loops in the
+/// network. Difficulties are told apart by other options, so it's the same for all of them
+const CHANCE_OF_LOOP: f64 = 0.3;
+
+/// A splitmix64 step, scattering neighbouring numbers far apart so they make unrelated seeds
+pub fn splitmix64(z: u64) -> u64 {
+    let mut z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Seed for a numbered retry or attempt made from `seed`, mixed so it never lands on another
+/// puzzle's own seed the way `seed + attempt` would
+fn attempt_seed(seed: u64, attempt: u32) -> u64 {
+    splitmix64(seed.wrapping_add((attempt as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)))
+}
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum HashiError {
     #[error("Invalid grid size")]
@@ -28,15 +60,60 @@ pub enum HashiError {
         line: BridgeLine,
         position: Position,
     },
+
+    #[error("Islands could not be joined into a single network")]
+    Disconnected,
+
+    #[error("Bridge would cross the island at {position:?}")]
+    CrossesIsland { position: Position },
+
+    #[error("Bridge would cross another bridge at {position:?}")]
+    CrossesBridge { position: Position },
+
+    #[error("Island at {position:?} already has all the bridges it needs")]
+    IslandFull { position: Position },
+
+    #[error("Ran out of time generating a puzzle")]
+    GenerationBudget,
+
+    #[error("Could not generate a puzzle with exactly {target} islands")]
+    IslandTarget { target: usize },
+
+    #[error("Every puzzle in {attempts} attempts needed guessing")]
+    NoLogicalPuzzle { attempts: u32 },
+
+    #[error("Not a valid puzzle code")]
+    InvalidCode,
+
+    #[error("Ran out of time solving the puzzle")]
+    SolveBudget,
+
+    #[error("Bridges can only wrap round the edge of a toroidal puzzle")]
+    WrappedBridge,
+
+    #[error("The bridge along {line:?} came with the puzzle and can't be changed")]
+    LockedBridge { line: BridgeLine },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+impl HashiError {
+    /// Where on the grid a rejected bridge ran into something, if anywhere
+    pub fn conflict_position(&self) -> Option<Position> {
+        match self {
+            HashiError::CrossesIsland { position } | HashiError::CrossesBridge { position } => {
+                Some(*position)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct Position {
     pub x: u8,
     pub y: u8,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum BridgeType {
     Single,
     Double,
@@ -48,11 +125,45 @@ pub enum BridgeDirection {
     Right,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+/// Stored as just its two ends, so a saved line goes back through `BridgeLine::new` and can't be diagonal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(into = "SavedLine", try_from = "SavedLine")]
 pub struct BridgeLine {
     pub start: Position,
     pub end: Position,
     pub direction: BridgeDirection,
+    /// Runs out of the board past `start` and back in at the opposite edge to reach `end`,
+    /// instead of across the cells between them. Only toroidal puzzles allow it
+    pub wraps: bool,
+}
+
+/// A line's two ends, with a third `true` for a line that wraps round the board
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SavedLine {
+    Wrapped(Position, Position, bool),
+    Straight(Position, Position),
+}
+
+impl From<BridgeLine> for SavedLine {
+    fn from(line: BridgeLine) -> Self {
+        if line.wraps {
+            SavedLine::Wrapped(line.start, line.end, true)
+        } else {
+            SavedLine::Straight(line.start, line.end)
+        }
+    }
+}
+
+impl TryFrom<SavedLine> for BridgeLine {
+    type Error = HashiError;
+
+    fn try_from(saved: SavedLine) -> Result<Self, Self::Error> {
+        match saved {
+            SavedLine::Wrapped(start, end, wraps) => BridgeLine::between(start, end, wraps),
+            SavedLine::Straight(start, end) => BridgeLine::new(start, end),
+        }
+    }
 }
 
 impl BridgeLine {
@@ -73,12 +184,14 @@ impl BridgeLine {
                     start: end,
                     end: start,
                     direction: BridgeDirection::Down,
+                    wraps: false,
                 })
             } else {
                 Ok(Self {
                     start,
                     end,
                     direction: BridgeDirection::Down,
+                    wraps: false,
                 })
             }
         } else {
@@ -88,17 +201,49 @@ impl BridgeLine {
                     start: end,
                     end: start,
                     direction: BridgeDirection::Right,
+                    wraps: false,
                 })
             } else {
                 Ok(Self {
                     start,
                     end,
                     direction: BridgeDirection::Right,
+                    wraps: false,
                 })
             }
         }
     }
 
+    /// The line between two positions the other way round the board, out past one edge and in at the opposite one
+    pub fn wrapping(start: Position, end: Position) -> Result<Self, HashiError> {
+        Ok(Self {
+            wraps: true,
+            ..Self::new(start, end)?
+        })
+    }
+
+    fn between(start: Position, end: Position, wraps: bool) -> Result<Self, HashiError> {
+        if wraps {
+            Self::wrapping(start, end)
+        } else {
+            Self::new(start, end)
+        }
+    }
+
+    /// Whether the line passes the given column of a horizontal line, or row of a vertical one,
+    /// ends included. A wrapped line covers everything outside its ends instead of between them
+    fn spans(&self, along: u8) -> bool {
+        let (low, high) = match self.direction {
+            BridgeDirection::Down => (self.start.y, self.end.y),
+            BridgeDirection::Right => (self.start.x, self.end.x),
+        };
+        if self.wraps {
+            along <= low || along >= high
+        } else {
+            along >= low && along <= high
+        }
+    }
+
     fn intersects(&self, other: &BridgeLine) -> Option<Position> {
         if self.direction == other.direction {
             // both vertical or both horizontal, cannot intersect
@@ -121,48 +266,220 @@ impl BridgeLine {
             return None;
         }
 
-        // if the y of the horizontal line is within the vertical line's y range
-        if horiz.start.y >= vert.start.y && horiz.start.y <= vert.end.y {
-            // if the x of the vertical line is within the horizontal line's x range
-            if vert.start.x >= horiz.start.x && vert.start.x <= horiz.end.x {
-                return Some(Position {
-                    x: vert.start.x,
-                    y: horiz.start.y,
-                });
-            }
+        // if the y of the horizontal line is within the vertical line's y range,
+        // and the x of the vertical line is within the horizontal line's x range
+        if vert.spans(horiz.start.y) && horiz.spans(vert.start.x) {
+            return Some(Position {
+                x: vert.start.x,
+                y: horiz.start.y,
+            });
         }
 
         None
     }
 
     fn crosses(&self, position: Position) -> bool {
-        // if the bridge is vertical and the position is on the same x
-        if self.start.x == self.end.x && position.x == self.start.x {
-            let miny = self.start.y.min(self.end.y);
-            let maxy = self.start.y.max(self.end.y);
-
-            if position.y >= miny && position.y <= maxy {
-                return true;
-            }
+        match self.direction {
+            BridgeDirection::Down => position.x == self.start.x && self.spans(position.y),
+            BridgeDirection::Right => position.y == self.start.y && self.spans(position.x),
         }
+    }
+}
 
-        // if the bridge is horizontal and the position is on the same y
-        if self.start.y == self.end.y && position.y == self.start.y {
-            let minx = self.start.x.min(self.end.x);
-            let maxx = self.start.x.max(self.end.x);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Island {
+    pub required_bridges: u8,
+}
 
-            if position.x >= minx && position.x <= maxx {
-                return true;
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Only single bridges, for players still learning the rules
+    Beginner,
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Beginner,
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+    ];
+
+    pub fn options(self) -> GenerationOptions {
+        // the medium puzzle, which the others adjust
+        let base = GenerationOptions {
+            min_gap: 2,
+            max_degree: 8,
+            double_chance: 0.3,
+            algorithm: GenAlgo::Growth,
+            allow_guessing: false,
+            symmetric: false,
+            variant: Variant::Classic,
+            min_unsaturated: 0.2,
+            max_bridge_length: Some(6),
+            target_islands: None,
+            vary_clues: false,
+        };
+        match self {
+            Difficulty::Beginner => GenerationOptions {
+                min_gap: 3,
+                max_degree: 4,
+                double_chance: 0.0,
+                ..base
+            },
+            Difficulty::Easy => GenerationOptions { min_gap: 3, ..base },
+            Difficulty::Medium => base,
+            // fully doubled 4-way islands solve themselves, leave them out of hard puzzles
+            Difficulty::Hard => GenerationOptions {
+                max_degree: 6,
+                // the odd spot that takes trial and error is part of the challenge
+                allow_guessing: true,
+                min_unsaturated: 0.25,
+                ..base
+            },
         }
+    }
+}
 
-        false
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        };
+        write!(f, "{}", name)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Island {
-    pub required_bridges: u8,
+/// How a puzzle's islands and solution are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GenAlgo {
+    /// Islands are added one at a time, each bridged to an island already on the board
+    #[default]
+    Growth,
+    /// Islands are scattered first, then joined by a random spanning tree with extra loops
+    SpanningTree,
+}
+
+impl GenAlgo {
+    pub const ALL: [GenAlgo; 2] = [GenAlgo::Growth, GenAlgo::SpanningTree];
+}
+
+/// Rules of the board that change where bridges can go
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    Classic,
+    /// Bridges may also leave one edge and come back in at the opposite one, as if the board
+    /// were wrapped round a torus
+    Toroidal,
+}
+
+impl Variant {
+    fn is_classic(&self) -> bool {
+        *self == Variant::Classic
+    }
+}
+
+impl std::fmt::Display for GenAlgo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            GenAlgo::Growth => "Growth",
+            GenAlgo::SpanningTree => "Spanning tree",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Knobs controlling the shape of generated puzzles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenerationOptions {
+    /// Minimum distance between a newly placed island and any island sharing its row or column
+    pub min_gap: u8,
+    /// Largest clue any island may end up with. Below 2 the islands cannot form a single network
+    pub max_degree: u8,
+    /// Chance of each bridge being made a double, 0 gives puzzles solved with single bridges only
+    pub double_chance: f64,
+    pub algorithm: GenAlgo,
+    /// Keep puzzles that can't be solved by deduction alone, instead of retrying for one that can
+    pub allow_guessing: bool,
+    /// Lay the puzzle out with half-turn symmetry, every island and bridge matched by one
+    /// rotated about the center. Symmetric puzzles are always grown, whichever algorithm is chosen
+    pub symmetric: bool,
+    /// Rules the puzzle is played under, which decide where its bridges can run
+    pub variant: Variant,
+    /// Smallest share of islands whose clue is below twice the islands they can see. Were every
+    /// clue at that most, the puzzle would be nothing but doubles to every neighbor
+    pub min_unsaturated: f64,
+    /// Longest bridge generation may lay, in cells from one island to the other, so islands stay
+    /// near their neighbors instead of being strung across empty stretches. None leaves it unlimited
+    pub max_bridge_length: Option<u8>,
+    /// Exact number of islands the puzzle must have, an error if no seed tried gives it. None
+    /// aims for one island per five cells and settles for what the budget allows
+    pub target_islands: Option<usize>,
+    /// Afterwards turn singles into doubles or back where it gives islands side by side in a row
+    /// or column different clues, so boards aren't long runs of the same number
+    pub vary_clues: bool,
+}
+
+impl GenerationOptions {
+    /// How many islands generation places on a board of this size
+    fn island_target(&self, width: u8, height: u8) -> usize {
+        self.target_islands
+            .unwrap_or_else(|| HashiGrid::target_island_count(width, height))
+    }
+
+    /// Whether a bridge this many cells long is short enough for `max_bridge_length`
+    fn within_reach(&self, length: u8) -> bool {
+        self.max_bridge_length.is_none_or(|max| length <= max)
+    }
+}
+
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        Difficulty::default().options()
+    }
+}
+
+/// Measurements of one generated puzzle, for comparing generator settings across many seeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GenStats {
+    pub islands_placed: usize,
+    /// Placement steps taken from the generation budget, over every attempt and retry
+    pub iterations_used: usize,
+    /// Bridges beyond the fewest that would join every island, each one closes a loop
+    pub loops_added: usize,
+    pub doubles_added: usize,
+    /// Separate networks in the result, 1 for any playable puzzle
+    pub components: usize,
+    /// Times the whole puzzle was thrown away and generated again from a seed mixed from the first
+    pub retries: u32,
+    /// Bridges of the solution locked in to come with the puzzle, as every retry still needed guessing
+    pub givens: usize,
+}
+
+/// The closest island in each direction from a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Neighbors {
+    pub up: Option<Position>,
+    pub down: Option<Position>,
+    pub left: Option<Position>,
+    pub right: Option<Position>,
+}
+
+impl Neighbors {
+    /// The islands that were found, in up, down, left, right order
+    pub fn iter(&self) -> impl Iterator<Item = Position> {
+        [self.up, self.down, self.left, self.right]
+            .into_iter()
+            .flatten()
+    }
 }
 
 enum Direction {
@@ -172,12 +489,54 @@ enum Direction {
     Right,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HashiGrid {
     pub width: u8,
     pub height: u8,
+    #[serde(with = "map_as_pairs")]
     pub islands: BTreeMap<Position, Island>,
+    #[serde(with = "map_as_pairs")]
     pub bridges: BTreeMap<BridgeLine, BridgeType>,
+    /// Left out of classic grids, so they save just as they did before variants
+    #[serde(default, skip_serializing_if = "Variant::is_classic")]
+    pub variant: Variant,
+    /// Lines whose bridges come with the puzzle, built before play starts and fixed: they can't
+    /// be added to, taken away or wiped. Left out when there are none, like `variant`
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub locked_bridges: BTreeSet<BridgeLine>,
+}
+
+/// JSON object keys must be strings, so maps keyed by positions or lines are stored as a list of pairs
+mod map_as_pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<K, V, S>(map: &BTreeMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Vec::<(K, V)>::deserialize(deserializer).map(|pairs| pairs.into_iter().collect())
+    }
 }
 
 impl HashiGrid {
@@ -187,6 +546,8 @@ impl HashiGrid {
             height: 0,
             islands: BTreeMap::new(),
             bridges: BTreeMap::new(),
+            variant: Variant::Classic,
+            locked_bridges: BTreeSet::new(),
         }
     }
     pub fn new(width: u8, height: u8) -> Result<Self, HashiError> {
@@ -198,32 +559,419 @@ impl HashiGrid {
             height,
             islands: BTreeMap::new(),
             bridges: BTreeMap::new(),
+            variant: Variant::Classic,
+            locked_bridges: BTreeSet::new(),
         })
     }
 
-    #[allow(dead_code)]
-    pub fn generate(width: u8, height: u8) -> Result<Self, HashiError> {
-        // make random number generator. Make a StdRng from the default random source
-        let mut rng = rand::rng();
-        let mut rng = rand::rngs::StdRng::from_rng(&mut rng);
+    /// Build a puzzle from exact island positions and clues, for tests and hand-made puzzles.
+    /// Fails if an island is off the grid or two share a position.
+    pub fn from_layout(
+        width: u8,
+        height: u8,
+        islands: &[(Position, u8)],
+    ) -> Result<Self, HashiError> {
+        let mut grid = Self::new(width, height)?;
+        for &(position, required_bridges) in islands {
+            grid.can_add_island(position)?;
+            grid.islands.insert(position, Island { required_bridges });
+        }
+        Ok(grid)
+    }
+
+    /// Short text for a puzzle's islands and clues, for sharing hand-made puzzles in a link:
+    /// the size, then every cell row by row, a clue digit for an island and a letter for a run
+    /// of empty cells (`a` is one, `z` twenty six). Trailing empty cells are left off.
+    /// A 3x2 grid with a 2 in each corner of its top row is `3x2-2a2`.
+    ///
+    /// Locked bridges follow, each as `.`, the number of the cell it starts from (counting row
+    /// by row from 0) and `r` or `d` for a single running right or down, `R` or `D` for a double.
+    /// With a locked single joining its two islands that grid is `3x2-2a2.0r`. Other bridges are left off.
+    pub fn to_code(&self) -> String {
+        let mut code = format!("{}x{}-", self.width, self.height);
+        let mut empty = 0u32;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let Some(island) = self.islands.get(&Position { x, y }) else {
+                    empty += 1;
+                    continue;
+                };
+                while empty > 0 {
+                    let run = empty.min(26);
+                    code.push((b'a' + run as u8 - 1) as char);
+                    empty -= run;
+                }
+                code.push_str(&island.required_bridges.to_string());
+            }
+        }
+        for line in &self.locked_bridges {
+            let cell = line.start.y as usize * self.width as usize + line.start.x as usize;
+            let direction = match (line.direction, self.bridges.get(line)) {
+                (BridgeDirection::Right, Some(BridgeType::Double)) => 'R',
+                (BridgeDirection::Down, Some(BridgeType::Double)) => 'D',
+                (BridgeDirection::Right, _) => 'r',
+                (BridgeDirection::Down, _) => 'd',
+            };
+            code.push_str(&format!(".{}{}", cell, direction));
+        }
+        code
+    }
+
+    /// Read a puzzle back from `to_code`, or from `to_compact_code`. Clues must be 1 to 8, a code can't
+    /// describe anything else, and each locked bridge has to run from an island to the next one along
+    /// without crossing another. Locked bridges must also be part of some solution without
+    /// already making one, or the puzzle could never be won or would be won before it started.
+    /// A code whose solution takes too long to find to check that is refused as well.
+    pub fn from_code(code: &str) -> Result<Self, HashiError> {
+        let grid = match code.strip_prefix(compact::COMPACT_PREFIX) {
+            Some(compact) => Self::from_compact_code(compact)?,
+            None => Self::from_text_code(code)?,
+        };
+        if !grid.locked_bridges.is_empty()
+            && (grid.is_complete() || !matches!(grid.solve_within(CODE_CHECK_BUDGET), Ok(Some(_))))
+        {
+            return Err(HashiError::InvalidCode);
+        }
+        Ok(grid)
+    }
+
+    /// Read a puzzle back from `to_code`, checking only that its locked bridges fit the board
+    fn from_text_code(code: &str) -> Result<Self, HashiError> {
+        let (size, rest) = code.split_once('-').ok_or(HashiError::InvalidCode)?;
+        let mut parts = rest.split('.');
+        // safe unwrap, split always gives at least one part
+        let cells = parts.next().unwrap();
+        let (width, height) = size.split_once('x').ok_or(HashiError::InvalidCode)?;
+        let width: u8 = width.parse().map_err(|_| HashiError::InvalidCode)?;
+        let height: u8 = height.parse().map_err(|_| HashiError::InvalidCode)?;
+        let mut grid = Self::new(width, height)?;
+
+        let mut cell = 0usize;
+        for c in cells.chars() {
+            match c {
+                '1'..='8' => {
+                    let position = Position {
+                        x: (cell % width as usize) as u8,
+                        y: (cell / width as usize) as u8,
+                    };
+                    if cell >= width as usize * height as usize {
+                        return Err(HashiError::InvalidCode);
+                    }
+                    grid.set_clue(position, c as u8 - b'0')?;
+                    cell += 1;
+                }
+                'a'..='z' => cell += (c as u8 - b'a') as usize + 1,
+                _ => return Err(HashiError::InvalidCode),
+            }
+        }
+
+        for locked in parts {
+            let (direction, strands) = match locked.chars().last() {
+                Some('r') => (Direction::Right, 1),
+                Some('d') => (Direction::Down, 1),
+                Some('R') => (Direction::Right, 2),
+                Some('D') => (Direction::Down, 2),
+                _ => return Err(HashiError::InvalidCode),
+            };
+            // the letter is ASCII, so one byte
+            let cell: usize = locked[..locked.len() - 1]
+                .parse()
+                .map_err(|_| HashiError::InvalidCode)?;
+            if cell >= width as usize * height as usize {
+                return Err(HashiError::InvalidCode);
+            }
+            let start = Position {
+                x: (cell % width as usize) as u8,
+                y: (cell / width as usize) as u8,
+            };
+            grid.add_locked_bridge(start, &direction, strands)?;
+        }
+        Ok(grid)
+    }
+
+    /// Build and lock a bridge read from a code, running from an island to the next one along
+    fn add_locked_bridge(
+        &mut self,
+        start: Position,
+        direction: &Direction,
+        strands: u8,
+    ) -> Result<(), HashiError> {
+        let line = self
+            .islands
+            .contains_key(&start)
+            .then(|| self.line_towards(start, direction))
+            .flatten()
+            .filter(|line| !self.locked_bridges.contains(line))
+            .ok_or(HashiError::InvalidCode)?;
+        for _ in 0..strands {
+            self.add_bridge(line).map_err(|_| HashiError::InvalidCode)?;
+        }
+        self.locked_bridges.insert(line);
+        Ok(())
+    }
 
-        Self::_generate(width, height, &mut rng)
+    pub fn generate(width: u8, height: u8) -> Result<Self, HashiError> {
+        Self::generate_with_options(
+            width,
+            height,
+            rand::random::<u64>(),
+            &GenerationOptions::default(),
+        )
     }
 
     pub fn generate_with_seed(width: u8, height: u8, seed: u64) -> Result<Self, HashiError> {
-        // seed the random number generator
-        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        Self::generate_with_options(width, height, seed, &GenerationOptions::default())
+    }
+
+    pub fn generate_with_options(
+        width: u8,
+        height: u8,
+        seed: u64,
+        options: &GenerationOptions,
+    ) -> Result<Self, HashiError> {
+        Self::generate_with_retries(width, height, seed, options).map(|(grid, _retries)| grid)
+    }
+
+    /// Generate a puzzle, re-running with a seed mixed from this one whenever the result is degenerate, has
+    /// too many islands taking all the bridges they could, or needs guessing when the options don't allow it.
+    /// Returns the grid alongside how many retries it took, the final attempt is kept even if it is still degenerate.
+    /// If it still needs guessing where the options don't allow it, some of its bridges are locked in
+    /// to come with it, as few as it takes to solve the rest by deduction.
+    /// All attempts share one budget of placement steps so a bad seed can't stall the page, once
+    /// it runs out the latest grid is kept as it is.
+    pub fn generate_with_retries(
+        width: u8,
+        height: u8,
+        seed: u64,
+        options: &GenerationOptions,
+    ) -> Result<(Self, u32), HashiError> {
+        Self::generate_with_stats(width, height, seed, options)
+            .map(|(grid, stats)| (grid, stats.retries))
+    }
+
+    /// `generate_with_retries`, measuring the puzzle and the work that went into it
+    pub fn generate_with_stats(
+        width: u8,
+        height: u8,
+        seed: u64,
+        options: &GenerationOptions,
+    ) -> Result<(Self, GenStats), HashiError> {
+        const MAX_QUALITY_RETRIES: u32 = 10;
+
+        let mut budget = GENERATION_BUDGET;
+        let mut retries = 0;
+        loop {
+            // the first try is the seed itself, each retry a seed mixed from it
+            let attempt = if retries == 0 {
+                seed
+            } else {
+                attempt_seed(seed, retries)
+            };
+            let mut rng = rand::rngs::StdRng::seed_from_u64(attempt);
+            let mut grid = Self::_generate(width, height, options, &mut rng, &mut budget)?;
+            if options.vary_clues {
+                grid.vary_equal_clues(options, &mut rng);
+            }
+
+            let target = options.island_target(width, height);
+            let missed_target = options.target_islands.is_some() && grid.islands.len() != target;
+            let rejected = grid.is_degenerate(target)
+                || missed_target
+                || grid.unsaturated_share() < options.min_unsaturated
+                || (!options.allow_guessing && grid.requires_guessing());
+            if !rejected || retries == MAX_QUALITY_RETRIES || budget == 0 {
+                // any other shortfall is better than no puzzle, but an exact count was asked for
+                if missed_target {
+                    return Err(HashiError::IslandTarget { target });
+                }
+                let givens = if rejected && !options.allow_guessing {
+                    grid.give_bridges_until_deducible(&mut rng)
+                } else {
+                    0
+                };
+                let components = grid.components().len();
+                let stats = GenStats {
+                    islands_placed: grid.islands.len(),
+                    iterations_used: GENERATION_BUDGET - budget,
+                    // a forest of n islands in k networks needs n - k bridges, the rest make loops
+                    loops_added: (grid.bridges.len() + components)
+                        .saturating_sub(grid.islands.len()),
+                    doubles_added: grid
+                        .bridges
+                        .values()
+                        .filter(|&&bridge_type| bridge_type == BridgeType::Double)
+                        .count(),
+                    components,
+                    retries,
+                    givens,
+                };
+                return Ok((grid, stats));
+            }
+            retries += 1;
+        }
+    }
+
+    /// Generate a puzzle with the given options that can be finished by deduction alone with no
+    /// bridges given, trying up to `max_attempts` seeds derived from `seed` in turn. The same seed
+    /// always gives the same puzzle
+    pub fn generate_logic_solvable(
+        width: u8,
+        height: u8,
+        seed: u64,
+        options: &GenerationOptions,
+        max_attempts: u32,
+    ) -> Result<Self, HashiError> {
+        let options = GenerationOptions {
+            allow_guessing: false,
+            ..*options
+        };
+        for attempt in 0..max_attempts {
+            let grid =
+                Self::generate_with_options(width, height, attempt_seed(seed, attempt), &options)?;
+            // a puzzle that still needed guessing once retries ran out comes with bridges given
+            if grid.locked_bridges.is_empty() {
+                return Ok(grid);
+            }
+        }
+        Err(HashiError::NoLogicalPuzzle {
+            attempts: max_attempts,
+        })
+    }
+
+    /// Lock bridges of the solution in, one at a time on lines deduction can't decide, until the
+    /// rest of the puzzle solves by deduction alone. Returns how many were locked
+    fn give_bridges_until_deducible(&mut self, rng: &mut rand::rngs::StdRng) -> usize {
+        let mut given = 0;
+        loop {
+            let open: Vec<BridgeLine> = solver::Solver::new(self)
+                .undecided()
+                .into_iter()
+                .filter(|line| {
+                    self.bridges.contains_key(line) && !self.locked_bridges.contains(line)
+                })
+                .collect();
+            let Some(&line) = open.choose(rng) else {
+                return given;
+            };
+            self.locked_bridges.insert(line);
+            given += 1;
+        }
+    }
+
+    /// Pairs of islands that see each other along a row or column and have the same clue
+    pub fn equal_neighbor_count(&self) -> usize {
+        self.candidate_bridges()
+            .iter()
+            .filter(|line| {
+                self.islands[&line.start].required_bridges
+                    == self.islands[&line.end].required_bridges
+            })
+            .count()
+    }
+
+    /// Step bridges of a generated solution between single and double where that leaves fewer
+    /// `equal_neighbor_count` pairs. A change is only kept within the options' largest clue and,
+    /// when they don't allow guessing, if the puzzle still solves by deduction. The bridges stay
+    /// a solution to the new clues, so it can't become unsolvable
+    fn vary_equal_clues(&mut self, options: &GenerationOptions, rng: &mut rand::rngs::StdRng) {
+        const MAX_VARY_PASSES: usize = 3;
+
+        for _pass in 0..MAX_VARY_PASSES {
+            let mut lines: Vec<BridgeLine> = self.bridges.keys().copied().collect();
+            lines.shuffle(rng);
+            let mut changed = false;
+            for line in lines {
+                let equal = self.equal_neighbor_count();
+                if equal == 0 {
+                    return;
+                }
+                let varied = match self.bridges[&line] {
+                    // puzzles meant to have only singles keep them
+                    BridgeType::Single if options.double_chance > 0.0 => BridgeType::Double,
+                    BridgeType::Double => BridgeType::Single,
+                    BridgeType::Single => continue,
+                };
+                let before = self.clone();
+                self.bridges.insert(line, varied);
+                self.set_clues_from_bridges();
+                let kept = [line.start, line.end]
+                    .iter()
+                    .all(|end| self.islands[end].required_bridges <= options.max_degree)
+                    && self.equal_neighbor_count() < equal
+                    && (options.allow_guessing || !self.requires_guessing());
+                if kept {
+                    changed = true;
+                } else {
+                    *self = before;
+                }
+            }
+            if !changed {
+                return;
+            }
+        }
+    }
+
+    /// How many islands generation aims to place on a board of this size
+    fn target_island_count(width: u8, height: u8) -> usize {
+        ((width as usize * height as usize) / 5).max(8)
+    }
+
+    /// Share of the islands whose clue is below twice the islands they can see, so working out
+    /// their bridges takes more than doubling up to every neighbor. 1 for a grid with no islands
+    pub fn unsaturated_share(&self) -> f64 {
+        if self.islands.is_empty() {
+            return 1.0;
+        }
+        let unsaturated = self
+            .islands
+            .iter()
+            .filter(|(position, island)| {
+                island.required_bridges < 2 * self.count_visible_neighbors(**position)
+            })
+            .count();
+        unsaturated as f64 / self.islands.len() as f64
+    }
 
-        Self::_generate(width, height, &mut rng)
+    /// A generated puzzle is degenerate if it has under half the intended islands, is split into
+    /// separate networks, has an island that needs no bridges at all, or has a clue that can't be met
+    fn is_degenerate(&self, target_islands: usize) -> bool {
+        self.islands.len() < target_islands / 2
+            || !self.is_connected()
+            || self.validate_clues().is_err()
+            || self
+                .islands
+                .values()
+                .any(|island| island.required_bridges == 0)
     }
 
-    fn _generate(width: u8, height: u8, rng: &mut rand::rngs::StdRng) -> Result<Self, HashiError> {
+    fn _generate(
+        width: u8,
+        height: u8,
+        options: &GenerationOptions,
+        rng: &mut rand::rngs::StdRng,
+        budget: &mut usize,
+    ) -> Result<Self, HashiError> {
+        // 1 or 2 cell wide grids cannot hold spaced out islands, every attempt would leave clueless islands
+        if width < MIN_GENERATED_SIZE || height < MIN_GENERATED_SIZE {
+            return Err(HashiError::Size);
+        }
+
         const MAX_GENERATION_ATTEMPTS: usize = 100;
+        let mut best = None;
         for _attempt in 0..MAX_GENERATION_ATTEMPTS {
-            match Self::__generate(width, height, rng) {
+            if *budget == 0 {
+                break;
+            }
+            match Self::generate_once(width, height, options, rng, budget) {
                 Ok(grid) => {
-                    // ensure edges are all covered
-                    if !grid.is_full() {
+                    // ensure edges are all covered, and that covering them didn't overshoot an exact count
+                    let missed_target = options
+                        .target_islands
+                        .is_some_and(|target| grid.islands.len() != target);
+                    if !grid.is_full() || missed_target {
+                        if !grid.islands.is_empty() {
+                            best = Some(grid);
+                        }
                         continue;
                     }
 
@@ -236,6 +984,7 @@ impl HashiGrid {
                         }
                     }
                     if forced_corners as f32 > (grid.islands.len() as f32 * 0.4) {
+                        best = Some(grid);
                         continue;
                     }
 
@@ -247,30 +996,64 @@ impl HashiGrid {
             }
         }
 
-        Self::__generate(width, height, rng)
+        if *budget == 0 {
+            // out of time, settle for a grid that missed the checks above
+            return best.ok_or(HashiError::GenerationBudget);
+        }
+        Self::generate_once(width, height, options, rng, budget)
+    }
+
+    /// One run of the generator the options ask for, with no quality checks
+    fn generate_once(
+        width: u8,
+        height: u8,
+        options: &GenerationOptions,
+        rng: &mut rand::rngs::StdRng,
+        budget: &mut usize,
+    ) -> Result<Self, HashiError> {
+        match options.algorithm {
+            _ if options.symmetric => Self::__generate(width, height, options, rng, budget),
+            GenAlgo::Growth => Self::__generate(width, height, options, rng, budget),
+            GenAlgo::SpanningTree => {
+                Self::__generate_spanning_tree(width, height, options, rng, budget)
+            }
+        }
     }
 
-    fn __generate(width: u8, height: u8, rng: &mut rand::rngs::StdRng) -> Result<Self, HashiError> {
+    fn __generate(
+        width: u8,
+        height: u8,
+        options: &GenerationOptions,
+        rng: &mut rand::rngs::StdRng,
+        budget: &mut usize,
+    ) -> Result<Self, HashiError> {
         // Empty grid
         let mut grid = HashiGrid::new(width, height)?;
+        // islands are only grown across the board, the wrapped lines come in with the loops
+        grid.variant = options.variant;
 
-        // How many islands?
-        // TODO - change based on difficulty
-        let num_islands = ((width as u16 * height as u16) / 5).max(8) as u8;
+        let num_islands = options.island_target(width, height);
 
-        // place the first island randomly
-        let x = rng.random_range(0..width);
-        let y = rng.random_range(0..height);
-        let position = Position { x, y };
-        grid.add_island(position)?;
+        if options.symmetric {
+            grid.seed_symmetric(options, rng)?;
+        } else {
+            // place the first island randomly
+            let x = rng.random_range(0..width);
+            let y = rng.random_range(0..height);
+            let position = Position { x, y };
+            grid.add_island(position)?;
+        }
 
-        let mut max_remaining_iterations = num_islands as usize * 100;
+        let mut max_remaining_iterations = num_islands * 100;
 
         // place the remaining islands
-        while grid.islands.len() < num_islands as usize && max_remaining_iterations > 0 {
+        while grid.islands.len() < num_islands && max_remaining_iterations > 0 && *budget > 0 {
             max_remaining_iterations -= 1;
+            *budget -= 1;
 
-            // pick a random existing island - use index-based selection for determinism
+            // pick a random existing island - use index-based selection for determinism.
+            // The keys are in position order, but a uniform index gives every island the same
+            // chance, so growth isn't pulled towards the top left (tests/generation.rs checks it)
             let island_keys: Vec<Position> = grid.islands.keys().copied().collect();
             let index = rng.random_range(0..island_keys.len());
             let existing_island_pos = island_keys[index];
@@ -333,173 +1116,585 @@ impl HashiGrid {
                 None => continue,
             };
 
+            // keep islands spread out so bridges are never cramped, and close enough that they aren't stretched
+            if !grid.is_spaced(proposed_position, options.min_gap)
+                // the two share a row or column, so one of the differences is 0
+                || !options.within_reach(
+                    existing_island_pos.x.abs_diff(proposed_position.x)
+                        + existing_island_pos.y.abs_diff(proposed_position.y),
+                )
+            {
+                continue;
+            }
+
             // speculatively add the island
-            match grid.add_island(proposed_position) {
-                Ok(()) => {
-                    // successfully added island
-                    // can we add a bridge?
-                    let bridge_line = BridgeLine::new(existing_island_pos, proposed_position)?;
-                    match grid.add_bridge(bridge_line) {
-                        Ok(_) => {}
-                        Err(_) => {
-                            // remove the island we just added
-                            grid.islands.remove(&proposed_position);
-                        }
-                    }
-                }
-                Err(_e) => {
-                    // try again
-                    continue;
+            if !grid.add_island_with_twin(proposed_position, options) {
+                // try again
+                continue;
+            }
+            // successfully added island
+            // can we add a bridge?
+            let bridge_line = BridgeLine::new(existing_island_pos, proposed_position)?;
+            if !grid.add_generated_bridge(bridge_line, options) {
+                // remove the island we just added
+                grid.islands.remove(&proposed_position);
+                if options.symmetric {
+                    grid.islands.remove(&grid.rotated(proposed_position));
                 }
             }
         }
 
-        // todo - create loops
+        // make sure the islands reach all four edges of the play area
+        grid.extend_to_edges(options, rng);
 
-        let chance_of_loop = 0.3; // todo - change based on difficulty
+        // extra bridges from each island close loops
         let island_positions: Vec<Position> = grid.islands.keys().copied().collect();
 
         for island_pos in island_positions {
-            for direction in [
-                Direction::Up,
-                Direction::Down,
-                Direction::Left,
-                Direction::Right,
-            ] {
-                // find nearest island in that direction
-                let mut target_island: Option<Position> = None;
-                match direction {
-                    Direction::Up => {
-                        let mut y = island_pos.y;
-                        while y > 0 {
-                            y -= 1;
-                            let pos = Position { x: island_pos.x, y };
-
-                            if grid.islands.contains_key(&pos) {
-                                target_island = Some(pos);
-                                break;
-                            }
-                        }
-                    }
-                    Direction::Down => {
-                        let mut y = island_pos.y;
-                        while y < height - 1 {
-                            y += 1;
-                            let pos = Position { x: island_pos.x, y };
-                            if grid.islands.contains_key(&pos) {
-                                target_island = Some(pos);
-                                break;
-                            }
-                        }
-                    }
-                    Direction::Left => {
-                        let mut x = island_pos.x;
-                        while x > 0 {
-                            x -= 1;
-                            let pos = Position { x, y: island_pos.y };
-                            if grid.islands.contains_key(&pos) {
-                                target_island = Some(pos);
-                                break;
-                            }
-                        }
-                    }
-                    Direction::Right => {
-                        let mut x = island_pos.x;
-                        while x < width - 1 {
-                            x += 1;
-                            let pos = Position { x, y: island_pos.y };
-                            if grid.islands.contains_key(&pos) {
-                                target_island = Some(pos);
-                                break;
-                            }
-                        }
-                    }
+            for direction in Direction::ALL {
+                let Some(bridge_line) = grid.line_towards(island_pos, &direction) else {
+                    continue;
+                };
+                if rng.random::<f64>() > CHANCE_OF_LOOP {
+                    continue;
                 }
-
-                if let Some(target_pos) = target_island {
-                    if rng.random::<f64>() > chance_of_loop {
-                        continue;
-                    }
-                    // At this point if this fails it does not matter, it just means it would have crossed another bridge.
-                    // Safe to ignore the error
-                    let _ = grid.add_bridge(BridgeLine::new(island_pos, target_pos)?);
+                // adding along an existing bridge would double it
+                if options.double_chance == 0.0 && grid.bridges.contains_key(&bridge_line) {
+                    continue;
                 }
+                // At this point if this fails it does not matter, it just means it would have crossed another bridge.
+                grid.add_generated_bridge(bridge_line, options);
             }
         }
 
-        // double some bridges randomly
-        let bridge_lines_to_double: Vec<BridgeLine> = grid
-            .bridges
-            .iter()
-            .filter_map(|(bridge_line, bridge_type)| {
-                if *bridge_type == BridgeType::Single && rng.random::<f64>() < 0.3 {
-                    Some(*bridge_line)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        for bridge_line in bridge_lines_to_double {
-            let _ = grid.add_bridge(bridge_line);
-        }
+        // the speculative bridge adds above can leave separate clusters, join them up
+        grid.connect_components(options)?;
 
-        // count bridges per island
-        let island_positions: Vec<Position> = grid.islands.keys().copied().collect();
-        for island_pos in island_positions {
-            let mut bridge_count = 0;
-            for (bridge_line, bridge_type) in &grid.bridges {
-                if bridge_line.start == island_pos || bridge_line.end == island_pos {
-                    match bridge_type {
-                        BridgeType::Single => bridge_count += 1,
-                        BridgeType::Double => bridge_count += 2,
-                    }
-                }
-            }
-            if let Some(island) = grid.islands.get_mut(&island_pos) {
-                island.required_bridges = bridge_count;
-            }
-        }
+        grid.double_some_bridges(options, rng);
+        grid.remove_orphans();
+        grid.set_clues_from_bridges();
 
         Ok(grid)
     }
 
-    fn count_visible_neighbors(&self, pos: Position) -> u8 {
-        let mut count = 0;
-        // Check each direction for nearest island
-        // Up
-        for y in (0..pos.y).rev() {
-            let check_pos = Position { x: pos.x, y };
-            if self.islands.contains_key(&check_pos) {
-                count += 1;
-                break;
+    /// Alternative to `__generate`: scatter spaced out islands over the board first, join them
+    /// with a random spanning tree, then add extra bridges between visible islands to form loops
+    fn __generate_spanning_tree(
+        width: u8,
+        height: u8,
+        options: &GenerationOptions,
+        rng: &mut rand::rngs::StdRng,
+        budget: &mut usize,
+    ) -> Result<Self, HashiError> {
+        let mut grid = HashiGrid::new(width, height)?;
+        grid.variant = options.variant;
+        let num_islands = options.island_target(width, height);
+
+        // dart throwing: random cells are kept if no island touches them, even diagonally
+        let mut max_remaining_darts = num_islands * 20;
+        while grid.islands.len() < num_islands && max_remaining_darts > 0 && *budget > 0 {
+            max_remaining_darts -= 1;
+            *budget -= 1;
+
+            let position = Position {
+                x: rng.random_range(0..width),
+                y: rng.random_range(0..height),
+            };
+            let crowded = grid.islands.keys().any(|island| {
+                island.x.abs_diff(position.x) <= 1 && island.y.abs_diff(position.y) <= 1
+            });
+            if crowded || !grid.is_spaced(position, options.min_gap) {
+                continue;
             }
+            let _ = grid.add_island(position);
         }
-        // Down
-        for y in (pos.y + 1)..self.height {
-            let check_pos = Position { x: pos.x, y };
-            if self.islands.contains_key(&check_pos) {
-                count += 1;
+
+        // grow a random spanning tree out from one island, one bridge at a time
+        let lines = grid.candidate_bridges();
+        let Some(&start) = grid.islands.keys().choose(rng) else {
+            return Err(HashiError::Disconnected);
+        };
+        let mut joined = BTreeSet::from([start]);
+        loop {
+            let mut frontier: Vec<BridgeLine> = lines
+                .iter()
+                .filter(|line| joined.contains(&line.start) != joined.contains(&line.end))
+                .copied()
+                .collect();
+            frontier.shuffle(rng);
+            let Some(line) = frontier.into_iter().find(|&line| {
+                grid.has_room(line, options.max_degree)
+                    && options.within_reach(grid.line_length(line))
+                    && grid.add_bridge(line).is_ok()
+            }) else {
                 break;
+            };
+            joined.insert(line.start);
+            joined.insert(line.end);
+        }
+
+        // islands the tree couldn't reach have no bridges, and so no clue
+        grid.islands.retain(|position, _| joined.contains(position));
+
+        grid.extend_to_edges(options, rng);
+
+        // chords between islands that can see each other turn the tree into loops
+        for line in grid.candidate_bridges() {
+            if grid.bridges.contains_key(&line)
+                || rng.random::<f64>() > CHANCE_OF_LOOP
+                || !grid.has_room(line, options.max_degree)
+                || !options.within_reach(grid.line_length(line))
+            {
+                continue;
             }
+            // crossing another bridge is fine to skip
+            let _ = grid.add_bridge(line);
         }
-        // Left
-        for x in (0..pos.x).rev() {
-            let check_pos = Position { x, y: pos.y };
-            if self.islands.contains_key(&check_pos) {
-                count += 1;
-                break;
+
+        grid.connect_components(options)?;
+        grid.double_some_bridges(options, rng);
+        grid.remove_orphans();
+        grid.set_clues_from_bridges();
+
+        Ok(grid)
+    }
+
+    /// Make each single bridge a double with the options' chance, where both islands have room
+    fn double_some_bridges(&mut self, options: &GenerationOptions, rng: &mut rand::rngs::StdRng) {
+        if options.double_chance > 0.0 {
+            let bridge_lines_to_double: Vec<BridgeLine> = self
+                .bridges
+                .iter()
+                .filter_map(|(bridge_line, bridge_type)| {
+                    if *bridge_type == BridgeType::Single
+                        && rng.random::<f64>() < options.double_chance
+                    {
+                        Some(*bridge_line)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            for bridge_line in bridge_lines_to_double {
+                // with symmetry on, a line already doubled as the twin of an earlier one can't take another
+                self.add_generated_bridge(bridge_line, options);
             }
         }
-        // Right
-        for x in (pos.x + 1)..self.width {
-            let check_pos = Position { x, y: pos.y };
-            if self.islands.contains_key(&check_pos) {
-                count += 1;
-                break;
+    }
+
+    /// Drop islands that never got a bridge, they would otherwise be left with a clue of 0.
+    /// Every step that places an island already takes it back if its bridge fails, this is a last line of defence.
+    fn remove_orphans(&mut self) {
+        let orphans: Vec<Position> = self
+            .islands
+            .keys()
+            .copied()
+            .filter(|&position| self.degree(position) == 0)
+            .collect();
+        for position in orphans {
+            self.islands.remove(&position);
+        }
+    }
+
+    /// Give every island the clue its current bridges satisfy
+    fn set_clues_from_bridges(&mut self) {
+        let island_positions: Vec<Position> = self.islands.keys().copied().collect();
+        for island_pos in island_positions {
+            let mut bridge_count = 0;
+            for (bridge_line, bridge_type) in &self.bridges {
+                if bridge_line.start == island_pos || bridge_line.end == island_pos {
+                    match bridge_type {
+                        BridgeType::Single => bridge_count += 1,
+                        BridgeType::Double => bridge_count += 2,
+                    }
+                }
+            }
+            if let Some(island) = self.islands.get_mut(&island_pos) {
+                island.required_bridges = bridge_count;
+            }
+        }
+    }
+
+    /// For each edge with no island on it, add one connected to the nearest island in line with it
+    fn extend_to_edges(&mut self, options: &GenerationOptions, rng: &mut rand::rngs::StdRng) {
+        let edges: [(Vec<Position>, Direction); 4] = [
+            (
+                (0..self.width).map(|x| Position { x, y: 0 }).collect(),
+                Direction::Down,
+            ),
+            (
+                (0..self.width)
+                    .map(|x| Position {
+                        x,
+                        y: self.height - 1,
+                    })
+                    .collect(),
+                Direction::Up,
+            ),
+            (
+                (0..self.height).map(|y| Position { x: 0, y }).collect(),
+                Direction::Right,
+            ),
+            (
+                (0..self.height)
+                    .map(|y| Position {
+                        x: self.width - 1,
+                        y,
+                    })
+                    .collect(),
+                Direction::Left,
+            ),
+        ];
+
+        for (mut edge, inward) in edges {
+            if edge.iter().any(|pos| self.islands.contains_key(pos)) {
+                continue;
+            }
+
+            // try the edge cells in a seeded random order so the result is deterministic
+            edge.shuffle(rng);
+            for edge_pos in edge {
+                let Some(line) = self.line_towards(edge_pos, &inward) else {
+                    continue;
+                };
+                let target = if line.start == edge_pos {
+                    line.end
+                } else {
+                    line.start
+                };
+                if self.degree(target) >= options.max_degree {
+                    continue;
+                }
+                if !self.is_spaced(edge_pos, options.min_gap)
+                    || !self.add_island_with_twin(edge_pos, options)
+                {
+                    continue;
+                }
+                // the twin of an island on this edge lands on the opposite one, covering it too
+                if self.add_generated_bridge(line, options) {
+                    break;
+                }
+                self.islands.remove(&edge_pos);
+                if options.symmetric {
+                    self.islands.remove(&self.rotated(edge_pos));
+                }
+            }
+        }
+    }
+
+    /// Group the islands into sets that are reachable from each other over the current bridges
+    pub fn components(&self) -> Vec<BTreeSet<Position>> {
+        let mut components: Vec<BTreeSet<Position>> = Vec::new();
+        let mut visited: BTreeSet<Position> = BTreeSet::new();
+
+        for &start in self.islands.keys() {
+            if visited.contains(&start) {
+                continue;
+            }
+
+            // depth first search from this island
+            let mut component = BTreeSet::new();
+            let mut stack = vec![start];
+            while let Some(pos) = stack.pop() {
+                if !visited.insert(pos) {
+                    continue;
+                }
+                component.insert(pos);
+                for (line, _) in self.bridges_ending_at(pos) {
+                    let other = if line.start == pos {
+                        line.end
+                    } else {
+                        line.start
+                    };
+                    if !visited.contains(&other) {
+                        stack.push(other);
+                    }
+                }
+            }
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Add bridges between the closest aligned islands of separate components until only one remains
+    fn connect_components(&mut self, options: &GenerationOptions) -> Result<(), HashiError> {
+        loop {
+            let components = self.components();
+            if components.len() <= 1 {
+                return Ok(());
+            }
+
+            // every bridge that would leave the first component, shortest first
+            let first = &components[0];
+            let mut candidates: Vec<BridgeLine> = Vec::new();
+            for &pos in first {
+                for direction in Direction::ALL {
+                    if let Some(line) = self.line_towards(pos, &direction)
+                        && !(first.contains(&line.start) && first.contains(&line.end))
+                    {
+                        candidates.push(line);
+                    }
+                }
+            }
+            candidates.sort_by_key(|&line| self.line_length(line));
+
+            if !candidates
+                .into_iter()
+                .any(|line| self.add_generated_bridge(line, options))
+            {
+                return Err(HashiError::Disconnected);
+            }
+        }
+    }
+
+    /// Whether one more bridge along the line keeps both of its islands within `max_degree` bridges
+    fn has_room(&self, line: BridgeLine, max_degree: u8) -> bool {
+        self.degree(line.start) < max_degree && self.degree(line.end) < max_degree
+    }
+
+    /// Add one bridge along the line if both islands have room, and along its rotated twin too
+    /// for symmetric puzzles. Either both are added or neither is.
+    fn add_generated_bridge(&mut self, line: BridgeLine, options: &GenerationOptions) -> bool {
+        if !self.has_room(line, options.max_degree)
+            || !options.within_reach(self.line_length(line))
+            || self.add_bridge(line).is_err()
+        {
+            return false;
+        }
+        if !options.symmetric {
+            return true;
+        }
+        let twin = self.rotated_line(line);
+        if twin != line
+            && !(self.has_room(twin, options.max_degree) && self.add_bridge(twin).is_ok())
+        {
+            self.downgrade_bridge(line);
+            return false;
+        }
+        true
+    }
+
+    /// Add an island, along with its rotated twin for symmetric puzzles. The twin has to be
+    /// spaced out like any other island; the center of an odd sized board is its own twin.
+    fn add_island_with_twin(&mut self, position: Position, options: &GenerationOptions) -> bool {
+        if self.add_island(position).is_err() {
+            return false;
+        }
+        let twin = self.rotated(position);
+        if !options.symmetric || twin == position {
+            return true;
+        }
+        if !self.is_spaced(twin, options.min_gap) || self.add_island(twin).is_err() {
+            self.islands.remove(&position);
+            return false;
+        }
+        true
+    }
+
+    /// A position turned half a turn about the center of the board
+    fn rotated(&self, position: Position) -> Position {
+        Position {
+            x: self.width - 1 - position.x,
+            y: self.height - 1 - position.y,
+        }
+    }
+
+    fn rotated_line(&self, line: BridgeLine) -> BridgeLine {
+        // safe unwrap, turning both ends keeps them in line and distinct
+        BridgeLine::between(self.rotated(line.start), self.rotated(line.end), line.wraps).unwrap()
+    }
+
+    /// Start a symmetric puzzle with islands that are already joined up: a bridge across the
+    /// center row or column of an odd sized board, or a ring of four bridges around the center.
+    /// An island and its twin on their own would grow into two mirrored networks that never meet.
+    fn seed_symmetric(
+        &mut self,
+        options: &GenerationOptions,
+        rng: &mut rand::rngs::StdRng,
+    ) -> Result<(), HashiError> {
+        let gap = options.min_gap.max(1);
+        // how far in from the edge each end of a line through the center can sit
+        let insets = |size: u8| (0..size / 2).filter(move |&i| size - 1 - 2 * i >= gap);
+
+        // each seed is the lines to build, every one along with its twin
+        let mut seeds: Vec<Vec<(Position, Position)>> = Vec::new();
+        for x in insets(self.width) {
+            for y in insets(self.height) {
+                let corner = Position { x, y };
+                let below = Position {
+                    x,
+                    y: self.height - 1 - y,
+                };
+                seeds.push(vec![(corner, below), (corner, self.rotated(below))]);
+            }
+        }
+        if self.width % 2 == 1 {
+            let x = self.width / 2;
+            seeds.extend(insets(self.height).map(|y| {
+                let end = Position { x, y };
+                vec![(end, self.rotated(end))]
+            }));
+        }
+        if self.height % 2 == 1 {
+            let y = self.height / 2;
+            seeds.extend(insets(self.width).map(|x| {
+                let end = Position { x, y };
+                vec![(end, self.rotated(end))]
+            }));
+        }
+
+        let seed = seeds.choose(rng).ok_or(HashiError::Size)?;
+        for &(start, end) in seed {
+            for position in [start, end] {
+                if !self.islands.contains_key(&position)
+                    && !self.add_island_with_twin(position, options)
+                {
+                    return Err(HashiError::Disconnected);
+                }
+            }
+            if !self.add_generated_bridge(BridgeLine::new(start, end)?, options) {
+                return Err(HashiError::Disconnected);
+            }
+        }
+        Ok(())
+    }
+
+    /// The nearest island in each of the four directions from a position, which need not be an island itself
+    pub fn neighbors(&self, position: Position) -> Neighbors {
+        Neighbors {
+            up: self.nearest_island(position, &Direction::Up),
+            down: self.nearest_island(position, &Direction::Down),
+            left: self.nearest_island(position, &Direction::Left),
+            right: self.nearest_island(position, &Direction::Right),
+        }
+    }
+
+    /// Every line a bridge could run along, between each pair of islands that can see each other.
+    /// Current bridges are ignored, so lines that would cross an existing bridge are included.
+    pub fn candidate_bridges(&self) -> Vec<BridgeLine> {
+        // each island looks right and down only, so every pair is found exactly once
+        self.islands
+            .keys()
+            .flat_map(|&position| {
+                [Direction::Right, Direction::Down]
+                    .into_iter()
+                    .filter_map(move |direction| self.line_towards(position, &direction))
+            })
+            .collect()
+    }
+
+    /// The line a bridge between two islands would take. On a toroidal board that's whichever
+    /// way round has a bridge already, then whichever isn't blocked by another island, shortest first
+    pub fn line_between(&self, a: Position, b: Position) -> Result<BridgeLine, HashiError> {
+        let direct = BridgeLine::new(a, b)?;
+        if self.variant == Variant::Classic {
+            return Ok(direct);
+        }
+
+        let mut lines = [direct, BridgeLine::wrapping(a, b)?];
+        lines.sort_by_key(|&line| self.line_length(line));
+        let blocked = |line: &&BridgeLine| {
+            self.islands
+                .keys()
+                .any(|&position| position != a && position != b && line.crosses(position))
+        };
+        Ok(*lines
+            .iter()
+            .find(|line| self.bridges.contains_key(line))
+            .or_else(|| lines.iter().find(|line| !blocked(line)))
+            .unwrap_or(&direct))
+    }
+
+    /// How many cells a line runs across, going round the edge for a wrapped line
+    fn line_length(&self, line: BridgeLine) -> u8 {
+        let (low, high, size) = match line.direction {
+            BridgeDirection::Down => (line.start.y, line.end.y, self.height),
+            BridgeDirection::Right => (line.start.x, line.end.x, self.width),
+        };
+        if line.wraps {
+            size - (high - low)
+        } else {
+            high - low
+        }
+    }
+
+    /// The line from a position to the closest island in the given direction, wrapping round
+    /// the edge on a toroidal board when that's how the island is reached
+    fn line_towards(&self, from: Position, direction: &Direction) -> Option<BridgeLine> {
+        let target = self.nearest_island(from, direction)?;
+        let wraps = match direction {
+            Direction::Up => target.y > from.y,
+            Direction::Down => target.y < from.y,
+            Direction::Left => target.x > from.x,
+            Direction::Right => target.x < from.x,
+        };
+        // safe unwrap, target is in line and distinct
+        Some(BridgeLine::between(from, target, wraps).unwrap())
+    }
+
+    /// Find the closest island from a position (exclusive) travelling in the given direction,
+    /// carrying on from the opposite edge on a toroidal board
+    fn nearest_island(&self, from: Position, direction: &Direction) -> Option<Position> {
+        let wraps = self.variant == Variant::Toroidal;
+        let mut pos = from;
+        loop {
+            pos = match direction {
+                Direction::Up if pos.y > 0 => Position {
+                    x: pos.x,
+                    y: pos.y - 1,
+                },
+                Direction::Down if pos.y + 1 < self.height => Position {
+                    x: pos.x,
+                    y: pos.y + 1,
+                },
+                Direction::Left if pos.x > 0 => Position {
+                    x: pos.x - 1,
+                    y: pos.y,
+                },
+                Direction::Right if pos.x + 1 < self.width => Position {
+                    x: pos.x + 1,
+                    y: pos.y,
+                },
+                Direction::Up if wraps => Position {
+                    x: pos.x,
+                    y: self.height - 1,
+                },
+                Direction::Down if wraps => Position { x: pos.x, y: 0 },
+                Direction::Left if wraps => Position {
+                    x: self.width - 1,
+                    y: pos.y,
+                },
+                Direction::Right if wraps => Position { x: 0, y: pos.y },
+                _ => return None,
+            };
+
+            // all the way round without meeting another island
+            if pos == from {
+                return None;
+            }
+            if self.islands.contains_key(&pos) {
+                return Some(pos);
             }
         }
-        count
+    }
+
+    /// Check that no island sharing the position's row or column is closer than `min_gap` cells
+    fn is_spaced(&self, position: Position, min_gap: u8) -> bool {
+        self.islands.keys().all(|island| {
+            if island.x == position.x {
+                island.y.abs_diff(position.y) >= min_gap
+            } else if island.y == position.y {
+                island.x.abs_diff(position.x) >= min_gap
+            } else {
+                true
+            }
+        })
+    }
+
+    fn count_visible_neighbors(&self, pos: Position) -> u8 {
+        self.neighbors(pos).iter().count() as u8
     }
 
     fn can_add_island(&self, position: Position) -> Result<(), HashiError> {
@@ -520,6 +1715,23 @@ impl HashiGrid {
         Ok(())
     }
 
+    /// Give the island at a position a new clue, putting an island there first if there isn't one
+    pub fn set_clue(&mut self, position: Position, required_bridges: u8) -> Result<(), HashiError> {
+        if !self.islands.contains_key(&position) {
+            self.add_island(position)?;
+        }
+        // safe unwrap, the island is there now
+        self.islands.get_mut(&position).unwrap().required_bridges = required_bridges;
+        Ok(())
+    }
+
+    /// Take the island at a position off the grid, along with any bridges ending at it
+    pub fn remove_island(&mut self, position: Position) -> Option<Island> {
+        self.bridges
+            .retain(|line, _| line.start != position && line.end != position);
+        self.islands.remove(&position)
+    }
+
     fn add_island(&mut self, position: Position) -> Result<(), HashiError> {
         self.can_add_island(position)?;
 
@@ -533,22 +1745,23 @@ impl HashiGrid {
         Ok(())
     }
 
-    fn count_brdges_ending_at(&self, position: Position) -> u8 {
-        let mut count = 0;
-
-        for (bridge_line, bridge_type) in self.bridges_ending_at(position) {
-            if bridge_line.start == position || bridge_line.end == position {
-                match bridge_type {
-                    BridgeType::Single => count += 1,
-                    BridgeType::Double => count += 2,
-                }
-            }
-        }
+    /// The island at a position, if there is one
+    pub fn island_at(&self, position: Position) -> Option<&Island> {
+        self.islands.get(&position)
+    }
 
-        count
+    /// How many bridges end at a position, counting a double as two, to compare with its clue
+    pub fn degree(&self, position: Position) -> u8 {
+        self.bridges_ending_at(position)
+            .into_iter()
+            .map(|(_, bridge_type)| match bridge_type {
+                BridgeType::Single => 1,
+                BridgeType::Double => 2,
+            })
+            .sum()
     }
 
-    fn bridges_ending_at(&self, position: Position) -> Vec<(&BridgeLine, &BridgeType)> {
+    pub fn bridges_ending_at(&self, position: Position) -> Vec<(&BridgeLine, &BridgeType)> {
         let mut result = Vec::new();
 
         for (bridge_line, bridge_type) in &self.bridges {
@@ -560,7 +1773,10 @@ impl HashiGrid {
         result
     }
 
-    fn can_bridge(&self, bridge: BridgeLine) -> Result<BridgeType, HashiError> {
+    pub fn can_bridge(&self, bridge: BridgeLine) -> Result<BridgeType, HashiError> {
+        if self.locked_bridges.contains(&bridge) {
+            return Err(HashiError::LockedBridge { line: bridge });
+        }
         match self.bridges.get(&bridge) {
             Some(BridgeType::Double) => {
                 // already a double, cannot add more
@@ -572,11 +1788,11 @@ impl HashiGrid {
                 // Check if the islands have capacity for another bridge
                 for end in [bridge.start, bridge.end] {
                     let island = self.islands.get(&end).unwrap(); // safe unwrap, validated when bridge was first added
-                    let existing_bridges = self.count_brdges_ending_at(end);
+                    let existing_bridges = self.degree(end);
                     if island.required_bridges != 0
                         && island.required_bridges < existing_bridges + 1
                     {
-                        return Err(HashiError::Overwrite { position: end });
+                        return Err(HashiError::IslandFull { position: end });
                     }
                 }
 
@@ -585,6 +1801,9 @@ impl HashiGrid {
             }
             None => {
                 // does not exist yet, proceed with validation
+                if bridge.wraps && self.variant != Variant::Toroidal {
+                    return Err(HashiError::WrappedBridge);
+                }
 
                 // Check that both ends of the bridgeline are connected to islands
                 for end in [bridge.start, bridge.end] {
@@ -597,11 +1816,11 @@ impl HashiGrid {
 
                     // Check that the islands have capacity for another bridge
                     let island = self.islands.get(&end).unwrap(); // safe unwrap, validated when bridge was first added
-                    let existing_bridges = self.count_brdges_ending_at(end);
+                    let existing_bridges = self.degree(end);
                     if island.required_bridges != 0
                         && island.required_bridges < existing_bridges + 1
                     {
-                        return Err(HashiError::Overwrite { position: end });
+                        return Err(HashiError::IslandFull { position: end });
                     }
                 }
 
@@ -611,7 +1830,7 @@ impl HashiGrid {
                         && island_pos != bridge.end
                         && bridge.crosses(island_pos)
                     {
-                        return Err(HashiError::Overwrite {
+                        return Err(HashiError::CrossesIsland {
                             position: island_pos,
                         });
                     }
@@ -620,7 +1839,7 @@ impl HashiGrid {
                 // check that the bridge does not cross any existing bridges
                 for &existing_bridge in self.bridges.keys() {
                     if let Some(collision) = bridge.intersects(&existing_bridge) {
-                        return Err(HashiError::Overwrite {
+                        return Err(HashiError::CrossesBridge {
                             position: collision,
                         });
                     }
@@ -638,11 +1857,52 @@ impl HashiGrid {
         Ok(suitable_bridge_type)
     }
 
+    /// Remove every bridge along a line, returning what was there. Locked bridges stay put
+    pub fn remove_bridge(&mut self, line: BridgeLine) -> Option<BridgeType> {
+        if self.locked_bridges.contains(&line) {
+            return None;
+        }
+        self.bridges.remove(&line)
+    }
+
+    /// Take one bridge away from a line: a double becomes a single and a single is removed.
+    /// Returns what was there before, or `None` if the line had no bridge or its bridge is locked
+    pub fn downgrade_bridge(&mut self, line: BridgeLine) -> Option<BridgeType> {
+        if self.locked_bridges.contains(&line) {
+            return None;
+        }
+        let previous = *self.bridges.get(&line)?;
+        match previous {
+            BridgeType::Double => {
+                self.bridges.insert(line, BridgeType::Single);
+            }
+            BridgeType::Single => {
+                self.bridges.remove(&line);
+            }
+        }
+        Some(previous)
+    }
+
+    /// The puzzle as it's played from the start: no bridges but the locked ones
     pub fn wipe_bridges(mut self) -> Self {
-        self.bridges.clear();
+        let locked = &self.locked_bridges;
+        self.bridges.retain(|line, _| locked.contains(line));
         self
     }
 
+    /// Fix the bridge already built along a line, so it comes with the puzzle when its bridges
+    /// are wiped and the player can't change it, e.g. to start a tutorial partly solved
+    pub fn lock_bridge(&mut self, line: BridgeLine) -> Result<(), HashiError> {
+        if !self.bridges.contains_key(&line) {
+            return Err(HashiError::UnconnectedBridge {
+                line,
+                position: line.start,
+            });
+        }
+        self.locked_bridges.insert(line);
+        Ok(())
+    }
+
     pub fn is_complete(&self) -> bool {
         // No island should have no bridges, at this point. If it does, something is wrong and we should errror.
         for island in self.islands.values() {
@@ -668,28 +1928,182 @@ impl HashiGrid {
             }
         }
 
-        true
+        // satisfied clues are not enough, separate closed networks are not a solution
+        self.is_connected()
     }
 
-    /// Check that the generated islands fully cover the grid, meaning all edges have at least one island in their row/column
-    fn is_full(&self) -> bool {
-        let top_covered = (0..self.width).any(|x| self.islands.contains_key(&Position { x, y: 0 }));
-        let bottom_covered = (0..self.width).any(|x| {
-            self.islands.contains_key(&Position {
-                x,
-                y: self.height - 1,
+    /// Whether the placed bridges join every island into a single network
+    pub fn is_connected(&self) -> bool {
+        self.components().len() <= 1
+    }
+
+    /// Check every clue could be met, at most a double bridge to each visible neighbor.
+    /// Returns the islands needing more bridges than their neighbors can give.
+    pub fn validate_clues(&self) -> Result<(), Vec<Position>> {
+        let impossible: Vec<Position> = self
+            .islands
+            .iter()
+            .filter(|(position, island)| {
+                island.required_bridges > 2 * self.count_visible_neighbors(**position)
             })
+            .map(|(position, _)| *position)
+            .collect();
+
+        if impossible.is_empty() {
+            Ok(())
+        } else {
+            Err(impossible)
+        }
+    }
+
+    /// Find a bridge layout that satisfies every clue as a single network.
+    /// Bridges currently on the grid are ignored, only the islands and their clues matter.
+    pub fn solve(&self) -> Option<BTreeMap<BridgeLine, BridgeType>> {
+        solver::Solver::new(self).solve()
+    }
+
+    /// `solve` giving up after `steps` steps of its search, for puzzles that come from outside
+    /// and could take any time at all
+    pub fn solve_within(
+        &self,
+        steps: usize,
+    ) -> Result<Option<BTreeMap<BridgeLine, BridgeType>>, HashiError> {
+        let solver = solver::Solver::new(self).within(steps);
+        match solver.solve() {
+            None if solver.ran_out() => Err(HashiError::SolveBudget),
+            solution => Ok(solution),
+        }
+    }
+
+    /// Whether the puzzle can't be finished by deduction alone, at some point a player has to try
+    /// a bridge and back out if it goes wrong. Bridges on the grid are ignored.
+    pub fn requires_guessing(&self) -> bool {
+        solver::Solver::new(self).requires_guessing()
+    }
+
+    /// Rough 0 to 100 estimate of how hard the puzzle is to solve, from the islands and clues only.
+    /// Bigger puzzles rate higher, as do ones that give little away at the start, lean on doubles,
+    /// take many rounds of deduction or need a guess somewhere.
+    pub fn difficulty_rating(&self) -> u32 {
+        let islands = self.islands.len();
+        if islands < 2 {
+            return 0;
+        }
+        let needed: u32 = self
+            .islands
+            .values()
+            .map(|island| island.required_bridges as u32)
+            .sum::<u32>()
+            / 2;
+        let opening: u32 = self.opening_bridges().values().map(|&n| n as u32).sum();
+        let (lines, doubles) = self.solve().map_or((0, 0), |solution| {
+            let doubles = solution
+                .values()
+                .filter(|&&bridge_type| bridge_type == BridgeType::Double)
+                .count();
+            (solution.len(), doubles)
         });
-        let left_covered =
-            (0..self.height).any(|y| self.islands.contains_key(&Position { x: 0, y }));
-        let right_covered = (0..self.height).any(|y| {
-            self.islands.contains_key(&Position {
-                x: self.width - 1,
-                y,
+
+        // up to 30 for size, levelling off at 60 islands
+        let size = islands.min(60) as f64 / 2.0;
+        // up to 20 for how few bridges are clear from the clues alone
+        let hidden = 20.0 * (1.0 - opening.min(needed) as f64 / needed.max(1) as f64);
+        // up to 15 for doubles, every line with one could carry one or two
+        let doubled = 15.0 * doubles as f64 / lines.max(1) as f64;
+        let solver = solver::Solver::new(self);
+        // up to 10 for long chains of reasoning
+        let depth = solver.deduction_passes().min(10) as f64;
+        let guessing = if solver.requires_guessing() {
+            25.0
+        } else {
+            0.0
+        };
+
+        (size + hidden + doubled + depth + guessing).round() as u32
+    }
+
+    /// Whether some solution has this bridge along the line, or a double where it's a single, so
+    /// building it isn't a mistake that will have to be undone. Bridges on the grid are ignored.
+    pub fn is_bridge_in_some_solution(&self, line: BridgeLine, bridge_type: BridgeType) -> bool {
+        let count = match bridge_type {
+            BridgeType::Single => 1,
+            BridgeType::Double => 2,
+        };
+        solver::Solver::new(self).allows(line, count)
+    }
+
+    /// Count the distinct solutions of the puzzle, stopping once `cap` have been found.
+    /// A cap of 2 is enough to tell whether the solution is unique.
+    pub fn count_solutions(&self, cap: usize) -> usize {
+        solver::Solver::new(self).count_solutions(cap)
+    }
+
+    /// How many bridges the clues force onto each line, for lines where that can be worked out without guessing.
+    /// Lines that can be ruled out map to 0. Bridges on the grid are taken as given while they can still be
+    /// part of a solution.
+    pub fn forced_bridges(&self) -> BTreeMap<BridgeLine, u8> {
+        solver::Solver::new(self).forced(&self.bridges)
+    }
+
+    /// The bridges a solver would build at a glance, each forced by one island's clue alone with no
+    /// reasoning across several islands. Far fewer than `forced_bridges`, which can solve a whole puzzle.
+    pub fn opening_bridges(&self) -> BTreeMap<BridgeLine, u8> {
+        solver::Solver::new(self).opening()
+    }
+
+    /// Islands where every bridge is forced but the bridges on the grid don't match yet
+    pub fn forced_islands(&self) -> Vec<Position> {
+        let forced = self.forced_bridges();
+        let lines = self.candidate_bridges();
+
+        self.islands
+            .keys()
+            .filter(|&&position| {
+                let touching: Vec<&BridgeLine> = lines
+                    .iter()
+                    .filter(|line| line.start == position || line.end == position)
+                    .collect();
+                let placed = |line: &BridgeLine| match self.bridges.get(line) {
+                    Some(BridgeType::Single) => 1,
+                    Some(BridgeType::Double) => 2,
+                    None => 0,
+                };
+
+                !touching.is_empty()
+                    && touching.iter().all(|line| forced.contains_key(*line))
+                    && touching.iter().any(|line| forced[*line] != placed(line))
             })
-        });
+            .copied()
+            .collect()
+    }
+
+    /// The top left and bottom right corners of the smallest rectangle holding every island,
+    /// None for a grid with no islands
+    pub fn bounding_box_of_islands(&self) -> Option<(Position, Position)> {
+        let xs = self.islands.keys().map(|position| position.x);
+        let ys = self.islands.keys().map(|position| position.y);
+        Some((
+            Position {
+                x: xs.clone().min()?,
+                y: ys.clone().min()?,
+            },
+            Position {
+                x: xs.max()?,
+                y: ys.max()?,
+            },
+        ))
+    }
 
-        top_covered && bottom_covered && left_covered && right_covered
+    /// Check that the generated islands fully cover the grid, meaning all edges have at least one island in their row/column
+    fn is_full(&self) -> bool {
+        self.bounding_box_of_islands()
+            == Some((
+                Position { x: 0, y: 0 },
+                Position {
+                    x: self.width - 1,
+                    y: self.height - 1,
+                },
+            ))
     }
 }
 
@@ -998,6 +2412,81 @@ mod tests {
         assert_eq!(bridge3, bridge4);
     }
 
+    #[test]
+    fn test_wrapped_bridge_crosses_outside_its_ends() {
+        // Test: A wrapped line covers its ends and the cells out to the edges, not the cells between
+        let bridge =
+            BridgeLine::wrapping(Position { x: 4, y: 3 }, Position { x: 1, y: 3 }).unwrap();
+        assert_eq!(bridge.start, Position { x: 1, y: 3 });
+        assert!(bridge.wraps);
+        for x in [0, 1, 4, 5] {
+            assert!(bridge.crosses(Position { x, y: 3 }), "x {}", x);
+        }
+        for x in [2, 3] {
+            assert!(!bridge.crosses(Position { x, y: 3 }), "x {}", x);
+        }
+        assert!(!bridge.crosses(Position { x: 0, y: 2 }));
+        assert_ne!(
+            bridge,
+            BridgeLine::new(Position { x: 1, y: 3 }, Position { x: 4, y: 3 }).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wrapped_bridge_intersects_across_the_edge() {
+        // Test: Lines meet where one wraps over the other near the edge, but not where a straight line would
+        let vertical = BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 0, y: 3 }).unwrap();
+        let wrapped =
+            BridgeLine::wrapping(Position { x: 2, y: 1 }, Position { x: 5, y: 1 }).unwrap();
+        let straight = BridgeLine::new(Position { x: 2, y: 1 }, Position { x: 5, y: 1 }).unwrap();
+
+        assert_eq!(wrapped.intersects(&vertical), Some(Position { x: 0, y: 1 }));
+        assert_eq!(straight.intersects(&vertical), None);
+
+        // two wrapped lines can cross out by the corner of the board
+        let down = BridgeLine::wrapping(Position { x: 5, y: 1 }, Position { x: 5, y: 4 }).unwrap();
+        let across =
+            BridgeLine::wrapping(Position { x: 1, y: 0 }, Position { x: 3, y: 0 }).unwrap();
+        assert_eq!(down.intersects(&across), Some(Position { x: 5, y: 0 }));
+    }
+
+    #[test]
+    fn test_toroidal_grid_sees_round_the_edge() {
+        // Test: Islands see each other both ways round a toroidal board, a classic board refuses wrapped bridges
+        let at = |x, y| Position { x, y };
+        let mut grid = HashiGrid::from_layout(6, 3, &[(at(1, 1), 2), (at(4, 1), 2)]).unwrap();
+        let straight = BridgeLine::new(at(1, 1), at(4, 1)).unwrap();
+        let wrapped = BridgeLine::wrapping(at(1, 1), at(4, 1)).unwrap();
+
+        assert_eq!(grid.candidate_bridges(), vec![straight]);
+        assert_eq!(grid.add_bridge(wrapped), Err(HashiError::WrappedBridge));
+
+        grid.variant = Variant::Toroidal;
+        assert_eq!(grid.candidate_bridges(), vec![straight, wrapped]);
+        assert_eq!(grid.neighbors(at(1, 1)).left, Some(at(4, 1)));
+        grid.add_bridge(wrapped).unwrap();
+        grid.add_bridge(straight).unwrap();
+        assert!(grid.is_complete());
+    }
+
+    #[test]
+    fn test_line_between_picks_the_way_round() {
+        // Test: The shorter way is used unless an island blocks it or a bridge already runs the other way
+        let at = |x, y| Position { x, y };
+        let mut grid =
+            HashiGrid::from_layout(8, 1, &[(at(0, 0), 2), (at(2, 0), 2), (at(7, 0), 2)]).unwrap();
+        assert!(!grid.line_between(at(0, 0), at(7, 0)).unwrap().wraps);
+
+        grid.variant = Variant::Toroidal;
+        // round the edge is one step, and straight across is blocked by the island at 2
+        assert!(grid.line_between(at(0, 0), at(7, 0)).unwrap().wraps);
+        assert!(!grid.line_between(at(2, 0), at(7, 0)).unwrap().wraps);
+
+        let long_way = BridgeLine::wrapping(at(2, 0), at(7, 0)).unwrap();
+        grid.bridges.insert(long_way, BridgeType::Single);
+        assert_eq!(grid.line_between(at(7, 0), at(2, 0)), Ok(long_way));
+    }
+
     // ============================================================================
     // ISLAND PLACEMENT TESTS
     // ============================================================================
@@ -1135,7 +2624,7 @@ mod tests {
         let result = grid.add_bridge(bridge);
         assert_eq!(
             result.unwrap_err(),
-            HashiError::Overwrite {
+            HashiError::CrossesIsland {
                 position: Position { x: 2, y: 2 }
             }
         );
@@ -1159,25 +2648,65 @@ mod tests {
         let result = grid.add_bridge(horizontal);
         assert_eq!(
             result.unwrap_err(),
-            HashiError::Overwrite {
+            HashiError::CrossesBridge {
                 position: Position { x: 2, y: 3 }
             }
         );
     }
 
     #[test]
-    fn test_add_bridge_single_then_double() {
-        // Test: Adding the same bridge twice converts single -> double
+    fn test_add_bridge_island_full() {
+        // Test: A bridge cannot be added to an island that already has all its required bridges
         let mut grid = HashiGrid::new(5, 5).unwrap();
         grid.add_island(Position { x: 1, y: 2 }).unwrap();
         grid.add_island(Position { x: 4, y: 2 }).unwrap();
+        grid.islands
+            .get_mut(&Position { x: 1, y: 2 })
+            .unwrap()
+            .required_bridges = 1;
 
         let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        grid.add_bridge(bridge).unwrap();
+        assert_eq!(
+            grid.add_bridge(bridge).unwrap_err(),
+            HashiError::IslandFull {
+                position: Position { x: 1, y: 2 }
+            }
+        );
+    }
+
+    #[test]
+    fn test_conflict_position_only_for_crossings() {
+        // Test: Only crossing errors point at a spot on the grid
+        let position = Position { x: 2, y: 3 };
+        assert_eq!(
+            HashiError::CrossesBridge { position }.conflict_position(),
+            Some(position)
+        );
+        assert_eq!(
+            HashiError::CrossesIsland { position }.conflict_position(),
+            Some(position)
+        );
+        assert_eq!(
+            HashiError::IslandFull { position }.conflict_position(),
+            None
+        );
+        assert_eq!(HashiError::DiagonalBridge.conflict_position(), None);
+    }
+
+    #[test]
+    fn test_add_bridge_single_then_double() {
+        // Test: Adding the same bridge twice converts single -> double
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 1, y: 2 }).unwrap();
+        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        // First add should create a single bridge
+        let result1 = grid.add_bridge(bridge);
+        assert_eq!(result1.unwrap(), BridgeType::Single);
 
-        // First add should create a single bridge
-        let result1 = grid.add_bridge(bridge);
-        assert_eq!(result1.unwrap(), BridgeType::Single);
-
         // Second add should upgrade to double bridge
         let result2 = grid.add_bridge(bridge);
         assert_eq!(result2.unwrap(), BridgeType::Double);
@@ -1210,19 +2739,193 @@ mod tests {
         );
     }
 
+    // ============================================================================
+    // BRIDGE REMOVAL TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_remove_bridge_removes_double_entirely() {
+        // Test: remove_bridge clears both bridges of a double and reports what was there
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 1, y: 2 }).unwrap();
+        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        grid.add_bridge(bridge).unwrap();
+        grid.add_bridge(bridge).unwrap();
+
+        assert_eq!(grid.remove_bridge(bridge), Some(BridgeType::Double));
+        assert!(grid.bridges.is_empty());
+        assert_eq!(grid.remove_bridge(bridge), None);
+    }
+
+    #[test]
+    fn test_downgrade_bridge_steps_down() {
+        // Test: downgrade_bridge goes double -> single -> none
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 1, y: 2 }).unwrap();
+        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        grid.add_bridge(bridge).unwrap();
+        grid.add_bridge(bridge).unwrap();
+
+        assert_eq!(grid.downgrade_bridge(bridge), Some(BridgeType::Double));
+        assert_eq!(grid.bridges.get(&bridge), Some(&BridgeType::Single));
+
+        assert_eq!(grid.downgrade_bridge(bridge), Some(BridgeType::Single));
+        assert!(grid.bridges.is_empty());
+
+        assert_eq!(grid.downgrade_bridge(bridge), None);
+    }
+
+    #[test]
+    fn test_from_layout_places_clues() {
+        // Test: Islands are placed exactly where given with their clues, and no bridges
+        let grid = HashiGrid::from_layout(
+            6,
+            4,
+            &[(Position { x: 0, y: 0 }, 3), (Position { x: 5, y: 3 }, 1)],
+        )
+        .unwrap();
+
+        assert_eq!(grid.islands.len(), 2);
+        assert_eq!(grid.islands[&Position { x: 0, y: 0 }].required_bridges, 3);
+        assert_eq!(grid.islands[&Position { x: 5, y: 3 }].required_bridges, 1);
+        assert!(grid.bridges.is_empty());
+    }
+
+    #[test]
+    fn test_from_layout_rejects_bad_islands() {
+        // Test: Off-grid and doubled up islands are errors rather than being dropped
+        let outside = Position { x: 6, y: 0 };
+        assert_eq!(
+            HashiGrid::from_layout(6, 4, &[(outside, 1)]),
+            Err(HashiError::OutOfBounds { position: outside })
+        );
+
+        let doubled = Position { x: 1, y: 1 };
+        assert_eq!(
+            HashiGrid::from_layout(6, 4, &[(doubled, 1), (doubled, 2)]),
+            Err(HashiError::Overwrite { position: doubled })
+        );
+        assert_eq!(HashiGrid::from_layout(0, 4, &[]), Err(HashiError::Size));
+    }
+
+    #[test]
+    fn test_candidate_bridges_full_set() {
+        // Test: Every pair of islands in sight of each other is a candidate, once, whatever is already built
+        //   0 . . 1
+        //   . . . .
+        //   2 . 3 .
+        //   . . 4 .
+        let p = |x, y| Position { x, y };
+        let mut grid = HashiGrid::from_layout(
+            4,
+            4,
+            &[
+                (p(0, 0), 1),
+                (p(3, 0), 1),
+                (p(0, 2), 1),
+                (p(2, 2), 1),
+                (p(2, 3), 1),
+            ],
+        )
+        .unwrap();
+        let line = |a, b| BridgeLine::new(a, b).unwrap();
+        let expected = BTreeSet::from([
+            line(p(0, 0), p(3, 0)),
+            line(p(0, 0), p(0, 2)),
+            line(p(0, 2), p(2, 2)),
+            line(p(2, 2), p(2, 3)),
+        ]);
+
+        let candidates = grid.candidate_bridges();
+        assert_eq!(candidates.len(), expected.len());
+        assert_eq!(BTreeSet::from_iter(candidates), expected);
+
+        grid.add_bridge(line(p(0, 0), p(3, 0))).unwrap();
+        assert_eq!(BTreeSet::from_iter(grid.candidate_bridges()), expected);
+    }
+
     // ============================================================================
     // COMPLETE PUZZLE VALIDATION TESTS
     // ============================================================================
 
     #[test]
-    fn test_is_complete_with_no_bridges() {
-        // Test: A puzzle with islands but no bridges is incomplete
+    fn test_is_complete_rejects_separate_networks() {
+        // Test: Two closed loops that satisfy every clue are not a solution, as they are not connected
+        let mut grid = HashiGrid::new(7, 7).unwrap();
+        for corners in [
+            [(0, 0), (2, 0), (2, 2), (0, 2)],
+            [(4, 4), (6, 4), (6, 6), (4, 6)],
+        ] {
+            for (x, y) in corners {
+                grid.add_island(Position { x, y }).unwrap();
+            }
+            for i in 0..4 {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                grid.add_bridge(
+                    BridgeLine::new(Position { x: a.0, y: a.1 }, Position { x: b.0, y: b.1 })
+                        .unwrap(),
+                )
+                .unwrap();
+            }
+        }
+        for island in grid.islands.values_mut() {
+            island.required_bridges = 2;
+        }
+
+        assert!(!grid.is_connected());
+        assert!(!grid.is_complete());
+    }
+
+    #[test]
+    fn test_is_connected_single_network() {
+        // Test: A grid is connected once bridges join all of its islands
         let mut grid = HashiGrid::new(5, 5).unwrap();
-        grid.add_island(Position { x: 2, y: 2 }).unwrap();
+        grid.add_island(Position { x: 0, y: 0 }).unwrap();
+        grid.add_island(Position { x: 3, y: 0 }).unwrap();
+        assert!(!grid.is_connected());
+
+        grid.add_bridge(BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 3, y: 0 }).unwrap())
+            .unwrap();
+        assert!(grid.is_connected());
+    }
+
+    #[test]
+    fn test_validate_clues_flags_impossible_islands() {
+        // Test: A clue above twice the island's neighbor count is flagged, achievable ones are not
+        let mut grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[
+                (Position { x: 0, y: 0 }, 5),
+                (Position { x: 3, y: 0 }, 2),
+                (Position { x: 0, y: 3 }, 2),
+            ],
+        )
+        .unwrap();
+        assert_eq!(grid.validate_clues(), Err(vec![Position { x: 0, y: 0 }]));
+
         grid.islands
-            .get_mut(&Position { x: 2, y: 2 })
+            .get_mut(&Position { x: 0, y: 0 })
             .unwrap()
-            .required_bridges = 1;
+            .required_bridges = 4;
+        assert_eq!(grid.validate_clues(), Ok(()));
+    }
+
+    #[test]
+    fn test_generated_clues_are_achievable() {
+        // Test: Generated puzzles never ask an island for more bridges than its neighbors allow
+        for seed in 0..20 {
+            let grid = HashiGrid::generate_with_seed(8, 16, seed).unwrap();
+            assert_eq!(grid.validate_clues(), Ok(()), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_is_complete_with_no_bridges() {
+        // Test: A puzzle with islands but no bridges is incomplete
+        let grid = HashiGrid::from_layout(5, 5, &[(Position { x: 2, y: 2 }, 1)]).unwrap();
 
         assert!(!grid.is_complete());
     }
@@ -1244,197 +2947,1396 @@ mod tests {
             .unwrap()
             .required_bridges = 2;
 
-        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
-        grid.add_bridge(bridge).unwrap(); // Only single bridge (1 connection each)
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        grid.add_bridge(bridge).unwrap(); // Only single bridge (1 connection each)
+
+        assert!(!grid.is_complete());
+    }
+
+    #[test]
+    fn test_is_complete_satisfied_single_bridges() {
+        // Test: Puzzle is complete when all islands have exact required bridge count (single bridges)
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 1, y: 2 }).unwrap();
+        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+
+        grid.islands
+            .get_mut(&Position { x: 1, y: 2 })
+            .unwrap()
+            .required_bridges = 1;
+        grid.islands
+            .get_mut(&Position { x: 4, y: 2 })
+            .unwrap()
+            .required_bridges = 1;
+
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        grid.add_bridge(bridge).unwrap();
+
+        assert!(grid.is_complete());
+    }
+
+    #[test]
+    fn test_is_complete_satisfied_double_bridges() {
+        // Test: Puzzle is complete with double bridges (counts as 2 connections per endpoint)
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 1, y: 2 }).unwrap();
+        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+
+        grid.islands
+            .get_mut(&Position { x: 1, y: 2 })
+            .unwrap()
+            .required_bridges = 2;
+        grid.islands
+            .get_mut(&Position { x: 4, y: 2 })
+            .unwrap()
+            .required_bridges = 2;
+
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        grid.add_bridge(bridge).unwrap(); // Single
+        grid.add_bridge(bridge).unwrap(); // Upgrade to double
+
+        assert!(grid.is_complete());
+    }
+
+    #[test]
+    fn test_is_complete_multiple_bridges_per_island() {
+        // Test: Island with bridges in multiple directions properly counted
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 2, y: 2 }).unwrap();
+        grid.add_island(Position { x: 2, y: 0 }).unwrap();
+        grid.add_island(Position { x: 2, y: 4 }).unwrap();
+        grid.add_island(Position { x: 0, y: 2 }).unwrap();
+        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+
+        // Central island should have 4 connections (one per direction)
+        grid.islands
+            .get_mut(&Position { x: 2, y: 2 })
+            .unwrap()
+            .required_bridges = 4;
+        // All surrounding islands need 1 connection
+        grid.islands
+            .get_mut(&Position { x: 2, y: 0 })
+            .unwrap()
+            .required_bridges = 1;
+        grid.islands
+            .get_mut(&Position { x: 2, y: 4 })
+            .unwrap()
+            .required_bridges = 1;
+        grid.islands
+            .get_mut(&Position { x: 0, y: 2 })
+            .unwrap()
+            .required_bridges = 1;
+        grid.islands
+            .get_mut(&Position { x: 4, y: 2 })
+            .unwrap()
+            .required_bridges = 1;
+
+        // Add all four bridges
+        let up = BridgeLine::new(Position { x: 2, y: 2 }, Position { x: 2, y: 0 }).unwrap();
+        let down = BridgeLine::new(Position { x: 2, y: 2 }, Position { x: 2, y: 4 }).unwrap();
+        let left = BridgeLine::new(Position { x: 2, y: 2 }, Position { x: 0, y: 2 }).unwrap();
+        let right = BridgeLine::new(Position { x: 2, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+
+        grid.add_bridge(up).unwrap();
+        grid.add_bridge(down).unwrap();
+        grid.add_bridge(left).unwrap();
+        grid.add_bridge(right).unwrap();
+
+        assert!(grid.is_complete());
+    }
+
+    #[test]
+    fn test_is_complete_has_zero_required_bridges_island() {
+        // Test: If any island has 0 required bridges, puzzle is incomplete
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 2, y: 2 }).unwrap();
+        // Don't set required_bridges (defaults to 0)
+
+        assert!(!grid.is_complete());
+    }
+
+    // ============================================================================
+    // BRIDGE WIPING TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_wipe_bridges_clears_all_bridges() {
+        // Test: wipe_bridges removes all bridges but keeps islands
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 1, y: 2 }).unwrap();
+        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+
+        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+        grid.add_bridge(bridge).unwrap();
+
+        assert!(!grid.bridges.is_empty());
+        let wiped_grid = grid.wipe_bridges();
+        assert!(wiped_grid.bridges.is_empty());
+        assert!(!wiped_grid.islands.is_empty());
+    }
+
+    // ============================================================================
+    // GRID GENERATION TESTS (Deterministic Seeding)
+    // ============================================================================
+
+    #[test]
+    fn test_generate_with_seed_produces_same_grid() {
+        // Test: Same seed always produces identical grid
+        let seed = 12345;
+        let grid1 = HashiGrid::generate_with_seed(10, 10, seed).unwrap();
+        let grid2 = HashiGrid::generate_with_seed(10, 10, seed).unwrap();
+
+        assert_eq!(grid1.islands, grid2.islands);
+        assert_eq!(grid1.bridges, grid2.bridges);
+    }
+
+    #[test]
+    fn test_generate_with_seed_different_seeds_differ() {
+        // Test: Different seeds produce different grids (with very high probability)
+        let grid1 = HashiGrid::generate_with_seed(10, 10, 12345).unwrap();
+        let grid2 = HashiGrid::generate_with_seed(10, 10, 54321).unwrap();
+
+        // Grids should differ (checking islands as main content)
+        assert_ne!(grid1.islands, grid2.islands);
+    }
+
+    #[test]
+    fn test_generate_with_seed_creates_islands() {
+        // Test: Generated grid should contain islands
+        let grid = HashiGrid::generate_with_seed(10, 10, 42).unwrap();
+        assert!(!grid.islands.is_empty());
+        assert!(grid.islands.len() >= 8); // Minimum from code: (width*height)/5 or 8
+    }
+
+    #[test]
+    fn test_generate_with_seed_creates_bridges() {
+        // Test: Generated grid should contain bridges
+        let grid = HashiGrid::generate_with_seed(10, 10, 42).unwrap();
+        assert!(!grid.bridges.is_empty());
+    }
+
+    #[test]
+    fn test_generate_with_seed_respects_grid_bounds() {
+        // Test: All islands and bridges should be within grid bounds
+        let grid = HashiGrid::generate_with_seed(10, 10, 42).unwrap();
+
+        for pos in grid.islands.keys() {
+            assert!(pos.x < grid.width);
+            assert!(pos.y < grid.height);
+        }
+
+        for bridge in grid.bridges.keys() {
+            assert!(bridge.start.x < grid.width);
+            assert!(bridge.start.y < grid.height);
+            assert!(bridge.end.x < grid.width);
+            assert!(bridge.end.y < grid.height);
+        }
+    }
+
+    #[test]
+    fn test_generate_with_seed_small_grid() {
+        // Test: Generation works on minimum viable grid size
+        let grid = HashiGrid::generate_with_seed(3, 3, 42).unwrap();
+        assert!(!grid.islands.is_empty());
+    }
+
+    #[test]
+    fn test_generate_with_seed_one_wide() {
+        // Test: A single column grid is rejected rather than producing a clueless island
+        assert_eq!(
+            HashiGrid::generate_with_seed(1, 10, 42).unwrap_err(),
+            HashiError::Size
+        );
+    }
+
+    #[test]
+    fn test_generate_with_seed_one_tall() {
+        // Test: A single row grid is rejected rather than producing a clueless island
+        assert_eq!(
+            HashiGrid::generate_with_seed(10, 1, 42).unwrap_err(),
+            HashiError::Size
+        );
+    }
+
+    #[test]
+    fn test_generate_with_seed_two_by_two() {
+        // Test: Grids below the minimum generated size are rejected in both dimensions
+        assert_eq!(
+            HashiGrid::generate_with_seed(2, 2, 42).unwrap_err(),
+            HashiError::Size
+        );
+        assert_eq!(
+            HashiGrid::generate_with_seed(2, 10, 42).unwrap_err(),
+            HashiError::Size
+        );
+    }
+
+    #[test]
+    fn test_generate_with_seed_minimum_size_has_clues() {
+        // Test: The smallest allowed size still yields islands that all need bridges
+        for seed in 0..10 {
+            let grid = HashiGrid::generate_with_seed(MIN_GENERATED_SIZE, 10, seed).unwrap();
+            assert!(grid.islands.values().all(|i| i.required_bridges > 0));
+        }
+    }
+
+    #[test]
+    fn test_generate_with_seed_no_adjacent_islands() {
+        // Test: Generated islands are never directly next to each other in a row or column
+        for seed in 0..20 {
+            let grid = HashiGrid::generate_with_seed(8, 16, seed).unwrap();
+            for pos in grid.islands.keys() {
+                let right = Position {
+                    x: pos.x + 1,
+                    y: pos.y,
+                };
+                let below = Position {
+                    x: pos.x,
+                    y: pos.y + 1,
+                };
+                assert!(!grid.islands.contains_key(&right), "seed {}", seed);
+                assert!(!grid.islands.contains_key(&below), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_with_options_respects_min_gap() {
+        // Test: A wider minimum gap is honoured between islands sharing a row or column
+        let options = Difficulty::Easy.options();
+        let grid = HashiGrid::generate_with_options(8, 16, 7, &options).unwrap();
+
+        for a in grid.islands.keys() {
+            for b in grid.islands.keys().filter(|b| *b != a) {
+                if a.x == b.x {
+                    assert!(a.y.abs_diff(b.y) >= options.min_gap);
+                } else if a.y == b.y {
+                    assert!(a.x.abs_diff(b.x) >= options.min_gap);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_beginner_puzzles_use_single_bridges_only() {
+        // Test: With no chance of doubling, every generated bridge is a single and no clue exceeds the neighbor count
+        let options = Difficulty::Beginner.options();
+        for seed in 0..20 {
+            let grid = HashiGrid::generate_with_options(8, 16, seed, &options).unwrap();
+            assert!(
+                grid.bridges.values().all(|b| *b == BridgeType::Single),
+                "seed {}",
+                seed
+            );
+            for (pos, island) in &grid.islands {
+                assert!(island.required_bridges <= grid.count_visible_neighbors(*pos));
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_with_options_respects_max_degree() {
+        // Test: No generated island requires more bridges than the configured maximum
+        for max_degree in [3, 4, 6] {
+            let options = GenerationOptions {
+                max_degree,
+                ..GenerationOptions::default()
+            };
+            for seed in 0..20 {
+                let grid = HashiGrid::generate_with_options(10, 10, seed, &options).unwrap();
+                assert_eq!(grid.components().len(), 1, "seed {}", seed);
+                for (pos, island) in &grid.islands {
+                    assert!(
+                        island.required_bridges <= max_degree,
+                        "seed {} island {:?} needs {}",
+                        seed,
+                        pos,
+                        island.required_bridges
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_has_room() {
+        // Test: A bridge only has room if neither end is already at the maximum
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 0, y: 0 }).unwrap();
+        grid.add_island(Position { x: 3, y: 0 }).unwrap();
+        let line = BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 3, y: 0 }).unwrap();
+        grid.add_bridge(line).unwrap();
+
+        assert!(grid.has_room(line, 2));
+        assert!(!grid.has_room(line, 1));
+    }
+
+    #[test]
+    fn test_is_degenerate() {
+        // Test: Too few islands, separate networks and clueless islands each make a grid degenerate
+        let mut grid = HashiGrid::new(3, 3).unwrap();
+        for (x, y) in [(0, 0), (2, 0), (0, 2), (2, 2)] {
+            grid.add_island(Position { x, y }).unwrap();
+        }
+        for (a, b) in [((0, 0), (2, 0)), ((2, 0), (2, 2)), ((2, 2), (0, 2))] {
+            grid.add_bridge(
+                BridgeLine::new(Position { x: a.0, y: a.1 }, Position { x: b.0, y: b.1 }).unwrap(),
+            )
+            .unwrap();
+        }
+        for (position, island) in grid.islands.iter_mut() {
+            island.required_bridges = if position.x == 0 { 1 } else { 2 };
+        }
+        let target = HashiGrid::target_island_count(3, 3);
+        assert!(!grid.is_degenerate(target));
+
+        let mut clueless = grid.clone();
+        clueless
+            .islands
+            .get_mut(&Position { x: 0, y: 0 })
+            .unwrap()
+            .required_bridges = 0;
+        assert!(clueless.is_degenerate(target));
+
+        let mut split = grid.clone();
+        split
+            .bridges
+            .remove(&BridgeLine::new(Position { x: 2, y: 0 }, Position { x: 2, y: 2 }).unwrap());
+        assert!(split.is_degenerate(target));
+
+        let mut sparse = grid.clone();
+        sparse.islands.remove(&Position { x: 0, y: 2 });
+        sparse.islands.remove(&Position { x: 2, y: 2 });
+        sparse.bridges.clear();
+        assert!(sparse.is_degenerate(target));
+    }
+
+    #[test]
+    fn test_generate_with_stats() {
+        // Test: The stats describe the grid that comes back, which is the same one the other generate functions give
+        let options = GenerationOptions::default();
+        for seed in 0..10 {
+            let (grid, stats) = HashiGrid::generate_with_stats(8, 16, seed, &options).unwrap();
+            let context = format!("seed {}", seed);
+
+            assert_eq!(
+                grid,
+                HashiGrid::generate_with_options(8, 16, seed, &options).unwrap()
+            );
+            assert_eq!(stats.islands_placed, grid.islands.len(), "{}", context);
+            assert_eq!(stats.components, 1, "{}", context);
+            assert_eq!(
+                stats.loops_added,
+                grid.bridges.len() + 1 - grid.islands.len(),
+                "{}",
+                context
+            );
+            assert!(stats.doubles_added <= grid.bridges.len(), "{}", context);
+            assert!(
+                stats.iterations_used > 0 && stats.iterations_used <= GENERATION_BUDGET,
+                "{}",
+                context
+            );
+        }
+    }
+
+    #[test]
+    fn test_big_puzzles_never_need_guessing_unless_allowed() {
+        // Test: Large beginner and medium boards, which often run out of retries, come with bridges
+        // given in place of any guess, and keep those givens when their bridges are wiped
+        let mut givens = 0;
+        for difficulty in [Difficulty::Beginner, Difficulty::Medium] {
+            for seed in 0..3 {
+                let (grid, stats) =
+                    HashiGrid::generate_with_stats(20, 20, seed, &difficulty.options()).unwrap();
+                let puzzle = grid.wipe_bridges();
+                assert!(
+                    !puzzle.requires_guessing(),
+                    "{:?} seed {}",
+                    difficulty,
+                    seed
+                );
+                assert_eq!(puzzle.locked_bridges.len(), stats.givens);
+                assert_eq!(puzzle.bridges.len(), stats.givens);
+                givens += stats.givens;
+            }
+        }
+        assert!(givens > 0);
+    }
+
+    #[test]
+    fn test_generate_with_retries_is_not_degenerate() {
+        // Test: Generated puzzles pass the quality gate, and retrying is deterministic per seed
+        for seed in 0..20 {
+            let (grid, retries) =
+                HashiGrid::generate_with_retries(6, 12, seed, &GenerationOptions::default())
+                    .unwrap();
+            assert!(
+                !grid.is_degenerate(HashiGrid::target_island_count(6, 12)),
+                "seed {} after {} retries",
+                seed,
+                retries
+            );
+
+            let (again, retries_again) =
+                HashiGrid::generate_with_retries(6, 12, seed, &GenerationOptions::default())
+                    .unwrap();
+            assert_eq!(grid, again);
+            assert_eq!(retries, retries_again);
+        }
+    }
+
+    #[test]
+    fn test_consecutive_seeds_differ() {
+        // Test: A retried seed doesn't land on the next seed's puzzle, so galleries and sheets built
+        // from seed + n never repeat one
+        let options = Difficulty::Medium.options();
+        let grids: Vec<HashiGrid> = (100..141)
+            .map(|seed| HashiGrid::generate_with_options(5, 10, seed, &options).unwrap())
+            .collect();
+        for (seed, pair) in (100..).zip(grids.windows(2)) {
+            assert_ne!(pair[0], pair[1], "seeds {} and {}", seed, seed + 1);
+        }
+    }
+
+    #[test]
+    fn test_remove_orphans() {
+        // Test: Islands without a bridge are dropped before clues are set, bridged ones are kept
+        let mut grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[
+                (Position { x: 0, y: 0 }, 0),
+                (Position { x: 3, y: 0 }, 0),
+                (Position { x: 0, y: 3 }, 0),
+            ],
+        )
+        .unwrap();
+        grid.add_bridge(BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 3, y: 0 }).unwrap())
+            .unwrap();
+
+        grid.remove_orphans();
+        grid.set_clues_from_bridges();
+
+        assert_eq!(
+            grid.islands.keys().copied().collect::<Vec<_>>(),
+            vec![Position { x: 0, y: 0 }, Position { x: 3, y: 0 }]
+        );
+        assert!(
+            grid.islands
+                .values()
+                .all(|island| island.required_bridges == 1)
+        );
+    }
+
+    #[test]
+    fn test_spanning_tree_generation() {
+        // Test: The spanning tree generator is deterministic per seed and gives a different, complete puzzle to growth
+        let options = GenerationOptions {
+            algorithm: GenAlgo::SpanningTree,
+            ..GenerationOptions::default()
+        };
+        for seed in 0..10 {
+            let grid = HashiGrid::generate_with_options(8, 16, seed, &options).unwrap();
+            assert!(grid.is_complete(), "seed {}", seed);
+            assert_eq!(
+                grid,
+                HashiGrid::generate_with_options(8, 16, seed, &options).unwrap()
+            );
+            assert_ne!(grid, HashiGrid::generate_with_seed(8, 16, seed).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_toroidal_generation() {
+        // Test: Toroidal puzzles from either algorithm are complete and solvable, and some of their bridges wrap
+        for algorithm in GenAlgo::ALL {
+            let options = GenerationOptions {
+                algorithm,
+                variant: Variant::Toroidal,
+                ..GenerationOptions::default()
+            };
+            let mut wrapped = 0;
+            for seed in 0..10 {
+                let grid = HashiGrid::generate_with_options(8, 8, seed, &options).unwrap();
+                let context = format!("{} seed {}", algorithm, seed);
+                assert_eq!(grid.variant, Variant::Toroidal, "{}", context);
+                assert!(grid.is_complete(), "{}", context);
+                assert!(grid.clone().wipe_bridges().solve().is_some(), "{}", context);
+                wrapped += grid.bridges.keys().filter(|line| line.wraps).count();
+            }
+            assert!(wrapped > 0, "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_symmetric_generation() {
+        // Test: Symmetric puzzles look the same turned half a turn, islands, bridges and clues, on even and odd boards
+        let options = GenerationOptions {
+            symmetric: true,
+            ..GenerationOptions::default()
+        };
+        for (width, height) in [(8, 16), (7, 9), (5, 10)] {
+            for seed in 0..10 {
+                let grid = HashiGrid::generate_with_options(width, height, seed, &options).unwrap();
+                let context = format!("{}x{} seed {}", width, height, seed);
+                assert!(grid.is_complete(), "{}", context);
+                for (&position, island) in &grid.islands {
+                    assert_eq!(
+                        grid.islands.get(&grid.rotated(position)),
+                        Some(island),
+                        "{}",
+                        context
+                    );
+                }
+                for (&line, bridge_type) in &grid.bridges {
+                    assert_eq!(
+                        grid.bridges.get(&grid.rotated_line(line)),
+                        Some(bridge_type),
+                        "{}",
+                        context
+                    );
+                }
+                assert_eq!(
+                    grid,
+                    HashiGrid::generate_with_options(width, height, seed, &options).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_generation_budget() {
+        // Test: Thin boards that burn through the budget still give a connected grid, and no budget at all is an error
+        for seed in 0..5 {
+            let grid = HashiGrid::generate_with_seed(MIN_GENERATED_SIZE, 30, seed).unwrap();
+            assert!(grid.is_connected(), "seed {}", seed);
+        }
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        assert_eq!(
+            HashiGrid::_generate(8, 8, &GenerationOptions::default(), &mut rng, &mut 0),
+            Err(HashiError::GenerationBudget)
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        // Test: Only the closest island in each direction is returned
+        let mut grid = HashiGrid::new(7, 7).unwrap();
+        for (x, y) in [(3, 3), (3, 0), (3, 1), (6, 3), (0, 3), (5, 5)] {
+            grid.add_island(Position { x, y }).unwrap();
+        }
+
+        let neighbors = grid.neighbors(Position { x: 3, y: 3 });
+        assert_eq!(
+            neighbors,
+            Neighbors {
+                up: Some(Position { x: 3, y: 1 }),
+                down: None,
+                left: Some(Position { x: 0, y: 3 }),
+                right: Some(Position { x: 6, y: 3 }),
+            }
+        );
+        assert_eq!(
+            neighbors.iter().collect::<Vec<_>>(),
+            vec![
+                Position { x: 3, y: 1 },
+                Position { x: 0, y: 3 },
+                Position { x: 6, y: 3 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_neighbors_of_empty_cell() {
+        // Test: Neighbors can be looked up from a cell without an island
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 0, y: 2 }).unwrap();
+        grid.add_island(Position { x: 2, y: 4 }).unwrap();
+
+        let neighbors = grid.neighbors(Position { x: 2, y: 2 });
+        assert_eq!(neighbors.left, Some(Position { x: 0, y: 2 }));
+        assert_eq!(neighbors.down, Some(Position { x: 2, y: 4 }));
+        assert_eq!(neighbors.up, None);
+        assert_eq!(neighbors.right, None);
+    }
+
+    #[test]
+    fn test_is_spaced() {
+        // Test: Spacing only considers islands that share a row or column
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 2, y: 2 }).unwrap();
+
+        assert!(!grid.is_spaced(Position { x: 3, y: 2 }, 2));
+        assert!(!grid.is_spaced(Position { x: 2, y: 1 }, 2));
+        assert!(grid.is_spaced(Position { x: 4, y: 2 }, 2));
+        assert!(grid.is_spaced(Position { x: 3, y: 3 }, 2));
+    }
+
+    #[test]
+    fn test_generate_with_seed_reaches_edges() {
+        // Test: Generated grids have at least one island on every edge
+        for seed in 0..20 {
+            let grid = HashiGrid::generate_with_seed(5, 10, seed).unwrap();
+            assert!(grid.is_full(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_extend_to_edges_adds_connected_edge_islands() {
+        // Test: A lone central island gains connected islands on every edge
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 2, y: 2 }).unwrap();
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        grid.extend_to_edges(&GenerationOptions::default(), &mut rng);
+
+        assert!(grid.is_full());
+        assert_eq!(grid.islands.len(), 5);
+        assert_eq!(grid.bridges.len(), 4);
+    }
+
+    #[test]
+    fn test_nearest_island() {
+        // Test: The nearest island is found in each direction, skipping empty cells
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 2, y: 0 }).unwrap();
+        grid.add_island(Position { x: 2, y: 4 }).unwrap();
+        grid.add_island(Position { x: 0, y: 2 }).unwrap();
+
+        let from = Position { x: 2, y: 2 };
+        assert_eq!(
+            grid.nearest_island(from, &Direction::Up),
+            Some(Position { x: 2, y: 0 })
+        );
+        assert_eq!(
+            grid.nearest_island(from, &Direction::Down),
+            Some(Position { x: 2, y: 4 })
+        );
+        assert_eq!(
+            grid.nearest_island(from, &Direction::Left),
+            Some(Position { x: 0, y: 2 })
+        );
+        assert_eq!(grid.nearest_island(from, &Direction::Right), None);
+    }
+
+    #[test]
+    fn test_generate_with_seed_is_single_component() {
+        // Test: Every generated grid forms one connected network of islands
+        for seed in 0..20 {
+            let grid = HashiGrid::generate_with_seed(8, 16, seed).unwrap();
+            assert_eq!(grid.components().len(), 1, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_connect_components_joins_separate_pairs() {
+        // Test: Two bridged pairs sharing a row are joined into a single component
+        let mut grid = HashiGrid::new(7, 3).unwrap();
+        for x in [0, 2, 4, 6] {
+            grid.add_island(Position { x, y: 1 }).unwrap();
+        }
+        grid.add_bridge(BridgeLine::new(Position { x: 0, y: 1 }, Position { x: 2, y: 1 }).unwrap())
+            .unwrap();
+        grid.add_bridge(BridgeLine::new(Position { x: 4, y: 1 }, Position { x: 6, y: 1 }).unwrap())
+            .unwrap();
+        assert_eq!(grid.components().len(), 2);
+
+        grid.connect_components(&GenerationOptions::default())
+            .unwrap();
+
+        assert_eq!(grid.components().len(), 1);
+        assert!(grid.bridges.contains_key(
+            &BridgeLine::new(Position { x: 2, y: 1 }, Position { x: 4, y: 1 }).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_connect_components_unreachable() {
+        // Test: Islands with no aligned partner cannot be joined
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        grid.add_island(Position { x: 0, y: 0 }).unwrap();
+        grid.add_island(Position { x: 3, y: 3 }).unwrap();
+
+        assert_eq!(
+            grid.connect_components(&GenerationOptions::default()),
+            Err(HashiError::Disconnected)
+        );
+    }
+
+    // ============================================================================
+    // SOLVER TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_count_solutions_single_pair() {
+        // Test: Two islands needing one bridge each have exactly one solution
+        let grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[(Position { x: 1, y: 2 }, 1), (Position { x: 4, y: 2 }, 1)],
+        )
+        .unwrap();
+        assert_eq!(grid.count_solutions(10), 1);
+    }
 
-        assert!(!grid.is_complete());
+    #[test]
+    fn test_is_bridge_in_some_solution() {
+        // Test: A bridge is allowed if some solution has at least that many bridges along its line
+        let grid = HashiGrid::from_layout(
+            5,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 1),
+                (Position { x: 2, y: 0 }, 2),
+                (Position { x: 4, y: 0 }, 2),
+                (Position { x: 4, y: 2 }, 1),
+            ],
+        )
+        .unwrap();
+        let at = |x, y| Position { x, y };
+        let middle = BridgeLine::new(at(2, 0), at(4, 0)).unwrap();
+
+        assert!(grid.is_bridge_in_some_solution(middle, BridgeType::Single));
+        // the 2s each need one bridge for their other neighbor
+        assert!(!grid.is_bridge_in_some_solution(middle, BridgeType::Double));
+        // a line over another island is never a candidate
+        let over = BridgeLine::new(at(0, 0), at(4, 0)).unwrap();
+        assert!(!grid.is_bridge_in_some_solution(over, BridgeType::Single));
     }
 
     #[test]
-    fn test_is_complete_satisfied_single_bridges() {
-        // Test: Puzzle is complete when all islands have exact required bridge count (single bridges)
-        let mut grid = HashiGrid::new(5, 5).unwrap();
-        grid.add_island(Position { x: 1, y: 2 }).unwrap();
-        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+    fn test_count_solutions_square_of_twos() {
+        // Test: A square of 2s only works as a ring of singles, doubled sides would split the network
+        let grid = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 2),
+                (Position { x: 2, y: 0 }, 2),
+                (Position { x: 0, y: 2 }, 2),
+                (Position { x: 2, y: 2 }, 2),
+            ],
+        )
+        .unwrap();
+        assert_eq!(grid.count_solutions(10), 1);
+    }
 
-        grid.islands
-            .get_mut(&Position { x: 1, y: 2 })
-            .unwrap()
-            .required_bridges = 1;
-        grid.islands
-            .get_mut(&Position { x: 4, y: 2 })
-            .unwrap()
-            .required_bridges = 1;
+    #[test]
+    fn test_count_solutions_square_of_threes() {
+        // Test: A square of 3s can double either the horizontal or the vertical sides
+        let grid = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 3),
+                (Position { x: 2, y: 0 }, 3),
+                (Position { x: 0, y: 2 }, 3),
+                (Position { x: 2, y: 2 }, 3),
+            ],
+        )
+        .unwrap();
+        assert_eq!(grid.count_solutions(10), 2);
+    }
 
-        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
-        grid.add_bridge(bridge).unwrap();
+    #[test]
+    fn test_count_solutions_respects_cap() {
+        // Test: Counting stops once the cap is reached
+        let grid = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 3),
+                (Position { x: 2, y: 0 }, 3),
+                (Position { x: 0, y: 2 }, 3),
+                (Position { x: 2, y: 2 }, 3),
+            ],
+        )
+        .unwrap();
+        assert_eq!(grid.count_solutions(1), 1);
+        assert_eq!(grid.count_solutions(0), 0);
+    }
 
-        assert!(grid.is_complete());
+    #[test]
+    fn test_code_round_trip() {
+        // Test: A puzzle's code reads back as the same islands and clues, with long empty runs split
+        let at = |x, y| Position { x, y };
+        let grid = HashiGrid::from_layout(3, 2, &[(at(0, 0), 2), (at(2, 0), 2)]).unwrap();
+        assert_eq!(grid.to_code(), "3x2-2a2");
+        assert_eq!(HashiGrid::from_code("3x2-2a2").unwrap(), grid);
+
+        let grid = HashiGrid::from_layout(30, 2, &[(at(0, 0), 1), (at(29, 1), 8)]).unwrap();
+        assert_eq!(grid.to_code(), "30x2-1zzf8");
+        assert_eq!(HashiGrid::from_code(&grid.to_code()).unwrap(), grid);
     }
 
     #[test]
-    fn test_is_complete_satisfied_double_bridges() {
-        // Test: Puzzle is complete with double bridges (counts as 2 connections per endpoint)
-        let mut grid = HashiGrid::new(5, 5).unwrap();
-        grid.add_island(Position { x: 1, y: 2 }).unwrap();
-        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+    fn test_code_carries_locked_bridges() {
+        // Test: Locked bridges go into the code with their type and come back locked, bad ones are refused
+        let at = |x, y| Position { x, y };
+        let mut grid =
+            HashiGrid::from_layout(3, 3, &[(at(0, 0), 3), (at(2, 0), 2), (at(0, 2), 1)]).unwrap();
+        let across = BridgeLine::new(at(0, 0), at(2, 0)).unwrap();
+        grid.add_bridge(across).unwrap();
+        grid.add_bridge(across).unwrap();
+        grid.lock_bridge(across).unwrap();
+        let down = BridgeLine::new(at(0, 0), at(0, 2)).unwrap();
+        grid.add_bridge(down).unwrap();
 
-        grid.islands
-            .get_mut(&Position { x: 1, y: 2 })
-            .unwrap()
-            .required_bridges = 2;
-        grid.islands
-            .get_mut(&Position { x: 4, y: 2 })
-            .unwrap()
-            .required_bridges = 2;
+        // only the locked bridge is part of the puzzle
+        assert_eq!(grid.to_code(), "3x3-3a2c1.0R");
+        let read = HashiGrid::from_code("3x3-3a2c1.0R").unwrap();
+        assert_eq!(read, grid.wipe_bridges());
+        assert_eq!(read.bridges[&across], BridgeType::Double);
+
+        // no island to start from, nothing in that direction, and the same line twice
+        for code in [
+            "3x3-3a2c1.1r",
+            "3x3-3a2c1.2d",
+            "3x3-3a2c1.0r.0r",
+            "3x3-3a2c1.r",
+            "3x3-3a2c1.0x",
+        ] {
+            assert!(HashiGrid::from_code(code).is_err(), "{}", code);
+        }
+    }
 
-        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
-        grid.add_bridge(bridge).unwrap(); // Single
-        grid.add_bridge(bridge).unwrap(); // Upgrade to double
+    #[test]
+    fn test_code_locked_bridges_must_be_in_a_solution() {
+        // Test: Codes whose locked bridges no solution has, or which are solved already, are refused
+        // the double across the top of a square of 2s cuts that pair off from the other two
+        assert_eq!(
+            HashiGrid::from_code("3x3-2a2c2a2.0R"),
+            Err(HashiError::InvalidCode)
+        );
+        // one single across the top leaves the rest of the loop to build
+        let read = HashiGrid::from_code("3x3-2a2c2a2.0r").unwrap();
+        let solution = read.solve().unwrap();
+        let top = BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 2, y: 0 }).unwrap();
+        assert_eq!(solution.get(&top), Some(&BridgeType::Single));
+        assert_eq!(read.count_solutions(10), 1);
+        assert!(read.is_bridge_in_some_solution(top, BridgeType::Single));
+
+        // a lone locked single that meets both clues
+        assert_eq!(
+            HashiGrid::from_code("3x1-1a1.0r"),
+            Err(HashiError::InvalidCode)
+        );
+    }
 
+    #[test]
+    fn test_locked_bridges_stay_put() {
+        // Test: A locked bridge can't be added to, removed or wiped, and still counts towards the clues
+        let at = |x, y| Position { x, y };
+        let mut grid = HashiGrid::from_layout(5, 1, &[(at(0, 0), 1), (at(4, 0), 1)]).unwrap();
+        let line = BridgeLine::new(at(0, 0), at(4, 0)).unwrap();
+        assert!(grid.lock_bridge(line).is_err());
+
+        grid.add_bridge(line).unwrap();
+        grid.lock_bridge(line).unwrap();
+        assert_eq!(
+            grid.add_bridge(line),
+            Err(HashiError::LockedBridge { line })
+        );
+        assert_eq!(grid.remove_bridge(line), None);
+        assert_eq!(grid.downgrade_bridge(line), None);
+        let grid = grid.wipe_bridges();
+        assert_eq!(grid.bridges.get(&line), Some(&BridgeType::Single));
         assert!(grid.is_complete());
+
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<HashiGrid>(&json).unwrap(), grid);
     }
 
     #[test]
-    fn test_is_complete_multiple_bridges_per_island() {
-        // Test: Island with bridges in multiple directions properly counted
-        let mut grid = HashiGrid::new(5, 5).unwrap();
-        grid.add_island(Position { x: 2, y: 2 }).unwrap();
-        grid.add_island(Position { x: 2, y: 0 }).unwrap();
-        grid.add_island(Position { x: 2, y: 4 }).unwrap();
-        grid.add_island(Position { x: 0, y: 2 }).unwrap();
-        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+    fn test_from_code_rejects_bad_codes() {
+        // Test: Malformed codes, clues a code can't hold and islands past the end of the grid are refused
+        for code in [
+            "", "3x2", "3-2a2", "ax2-2", "3x2-2a9", "3x2-2a0", "3x2-2 2", "2x2-d1", "0x2-",
+        ] {
+            assert!(HashiGrid::from_code(code).is_err(), "{}", code);
+        }
+    }
 
-        // Central island should have 4 connections (one per direction)
-        grid.islands
-            .get_mut(&Position { x: 2, y: 2 })
-            .unwrap()
-            .required_bridges = 4;
-        // All surrounding islands need 1 connection
-        grid.islands
-            .get_mut(&Position { x: 2, y: 0 })
-            .unwrap()
-            .required_bridges = 1;
-        grid.islands
-            .get_mut(&Position { x: 2, y: 4 })
-            .unwrap()
-            .required_bridges = 1;
-        grid.islands
-            .get_mut(&Position { x: 0, y: 2 })
-            .unwrap()
-            .required_bridges = 1;
-        grid.islands
-            .get_mut(&Position { x: 4, y: 2 })
-            .unwrap()
-            .required_bridges = 1;
+    #[test]
+    fn test_set_clue_and_remove_island() {
+        // Test: Setting a clue adds or updates an island, removing one takes its bridges with it
+        let mut grid = HashiGrid::new(5, 5).unwrap();
+        let (a, b) = (Position { x: 0, y: 0 }, Position { x: 3, y: 0 });
+        grid.set_clue(a, 1).unwrap();
+        grid.set_clue(b, 1).unwrap();
+        grid.set_clue(a, 3).unwrap();
+        assert_eq!(grid.islands[&a].required_bridges, 3);
+        assert!(grid.set_clue(Position { x: 5, y: 0 }, 1).is_err());
+
+        grid.add_bridge(BridgeLine::new(a, b).unwrap()).unwrap();
+        assert_eq!(
+            grid.remove_island(b),
+            Some(Island {
+                required_bridges: 1
+            })
+        );
+        assert!(grid.bridges.is_empty());
+        assert_eq!(grid.remove_island(b), None);
+    }
 
-        // Add all four bridges
-        let up = BridgeLine::new(Position { x: 2, y: 2 }, Position { x: 2, y: 0 }).unwrap();
-        let down = BridgeLine::new(Position { x: 2, y: 2 }, Position { x: 2, y: 4 }).unwrap();
-        let left = BridgeLine::new(Position { x: 2, y: 2 }, Position { x: 0, y: 2 }).unwrap();
-        let right = BridgeLine::new(Position { x: 2, y: 2 }, Position { x: 4, y: 2 }).unwrap();
+    #[test]
+    fn test_opening_bridges() {
+        // Test: Only what each clue forces on its own is built, a 4 with two neighbours doubles to both
+        let at = |x, y| Position { x, y };
+        let grid = HashiGrid::from_layout(
+            3,
+            3,
+            &[(at(0, 0), 4), (at(2, 0), 3), (at(0, 2), 3), (at(2, 2), 2)],
+        )
+        .unwrap();
+        let line = |a, b| BridgeLine::new(a, b).unwrap();
 
-        grid.add_bridge(up).unwrap();
-        grid.add_bridge(down).unwrap();
-        grid.add_bridge(left).unwrap();
-        grid.add_bridge(right).unwrap();
+        assert_eq!(
+            grid.opening_bridges(),
+            BTreeMap::from([
+                (line(at(0, 0), at(2, 0)), 2),
+                (line(at(0, 0), at(0, 2)), 2),
+                (line(at(2, 0), at(2, 2)), 1),
+                (line(at(0, 2), at(2, 2)), 1),
+            ])
+        );
+    }
 
-        assert!(grid.is_complete());
+    #[test]
+    fn test_requires_guessing() {
+        // Test: A square of 2s can be deduced line by line, a square of 3s has two answers and so needs a guess
+        let twos = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 2),
+                (Position { x: 2, y: 0 }, 2),
+                (Position { x: 0, y: 2 }, 2),
+                (Position { x: 2, y: 2 }, 2),
+            ],
+        )
+        .unwrap();
+        let threes = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 3),
+                (Position { x: 2, y: 0 }, 3),
+                (Position { x: 0, y: 2 }, 3),
+                (Position { x: 2, y: 2 }, 3),
+            ],
+        )
+        .unwrap();
+
+        assert!(!twos.requires_guessing());
+        assert!(threes.requires_guessing());
     }
 
     #[test]
-    fn test_is_complete_has_zero_required_bridges_island() {
-        // Test: If any island has 0 required bridges, puzzle is incomplete
-        let mut grid = HashiGrid::new(5, 5).unwrap();
-        grid.add_island(Position { x: 2, y: 2 }).unwrap();
-        // Don't set required_bridges (defaults to 0)
+    fn test_difficulty_rating() {
+        // Test: A pair of islands rates low, guessing raises the rating and a big hard puzzle rates high
+        let pair = HashiGrid::from_layout(
+            3,
+            1,
+            &[(Position { x: 0, y: 0 }, 1), (Position { x: 2, y: 0 }, 1)],
+        )
+        .unwrap();
+        let twos = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 2),
+                (Position { x: 2, y: 0 }, 2),
+                (Position { x: 0, y: 2 }, 2),
+                (Position { x: 2, y: 2 }, 2),
+            ],
+        )
+        .unwrap();
+        let threes = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 3),
+                (Position { x: 2, y: 0 }, 3),
+                (Position { x: 0, y: 2 }, 3),
+                (Position { x: 2, y: 2 }, 3),
+            ],
+        )
+        .unwrap();
+
+        assert!(pair.difficulty_rating() < 15);
+        assert!(threes.difficulty_rating() >= twos.difficulty_rating() + 25);
+        assert_eq!(HashiGrid::new(5, 5).unwrap().difficulty_rating(), 0);
+
+        let big = HashiGrid::generate_with_options(30, 30, 1, &Difficulty::Hard.options())
+            .unwrap()
+            .wipe_bridges();
+        assert!(big.difficulty_rating() >= 75);
+    }
 
-        assert!(!grid.is_complete());
+    #[test]
+    fn test_rating_rises_with_difficulty() {
+        // Test: At the home page's board sizes, each difficulty's puzzles rate higher on average than the one below
+        for (width, height) in [(5, 10), (8, 16)] {
+            let means: Vec<f64> = Difficulty::ALL
+                .into_iter()
+                .map(|difficulty| {
+                    let total: u32 = (0..15)
+                        .map(|seed| {
+                            HashiGrid::generate_with_options(
+                                width,
+                                height,
+                                seed,
+                                &difficulty.options(),
+                            )
+                            .unwrap()
+                            .wipe_bridges()
+                            .difficulty_rating()
+                        })
+                        .sum();
+                    total as f64 / 15.0
+                })
+                .collect();
+            assert!(
+                means.windows(2).all(|pair| pair[0] < pair[1]),
+                "{}x{} mean ratings {:?}",
+                width,
+                height,
+                means
+            );
+        }
     }
 
-    // ============================================================================
-    // BRIDGE WIPING TESTS
-    // ============================================================================
+    #[test]
+    fn test_generated_clues_are_not_all_maxed() {
+        // Test: Every difficulty keeps at least its share of islands below their most possible bridges
+        let doubled = HashiGrid::from_layout(
+            3,
+            1,
+            &[(Position { x: 0, y: 0 }, 2), (Position { x: 2, y: 0 }, 2)],
+        )
+        .unwrap();
+        assert_eq!(doubled.unsaturated_share(), 0.0);
+        assert_eq!(
+            HashiGrid::from_layout(
+                3,
+                1,
+                &[(Position { x: 0, y: 0 }, 1), (Position { x: 2, y: 0 }, 1)]
+            )
+            .unwrap()
+            .unsaturated_share(),
+            1.0
+        );
+
+        for difficulty in Difficulty::ALL {
+            let options = difficulty.options();
+            for seed in 0..20 {
+                let grid = HashiGrid::generate_with_options(5, 10, seed, &options).unwrap();
+                assert!(
+                    grid.unsaturated_share() >= options.min_unsaturated,
+                    "{} seed {}",
+                    difficulty,
+                    seed
+                );
+            }
+        }
+    }
 
     #[test]
-    fn test_wipe_bridges_clears_all_bridges() {
-        // Test: wipe_bridges removes all bridges but keeps islands
-        let mut grid = HashiGrid::new(5, 5).unwrap();
-        grid.add_island(Position { x: 1, y: 2 }).unwrap();
-        grid.add_island(Position { x: 4, y: 2 }).unwrap();
+    fn test_generation_bounds_bridge_length() {
+        // Test: No generated bridge runs further than the options allow, with either algorithm
+        for algorithm in GenAlgo::ALL {
+            let options = GenerationOptions {
+                algorithm,
+                max_bridge_length: Some(4),
+                ..GenerationOptions::default()
+            };
+            for seed in 0..20 {
+                let grid = HashiGrid::generate_with_options(10, 12, seed, &options).unwrap();
+                assert!(grid.is_connected(), "{} seed {}", algorithm, seed);
+                assert!(
+                    grid.bridges.keys().all(|&line| grid.line_length(line) <= 4),
+                    "{} seed {}",
+                    algorithm,
+                    seed
+                );
+            }
+        }
+    }
 
-        let bridge = BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap();
-        grid.add_bridge(bridge).unwrap();
+    #[test]
+    fn test_generation_hits_island_target() {
+        // Test: A target island count is met exactly for every seed, or refused when the board can't hold it
+        let options = GenerationOptions {
+            target_islands: Some(15),
+            ..GenerationOptions::default()
+        };
+        for seed in 0..10 {
+            let grid = HashiGrid::generate_with_options(10, 10, seed, &options).unwrap();
+            assert_eq!(grid.islands.len(), 15, "seed {}", seed);
+        }
 
-        assert!(!grid.bridges.is_empty());
-        let wiped_grid = grid.wipe_bridges();
-        assert!(wiped_grid.bridges.is_empty());
-        assert!(!wiped_grid.islands.is_empty());
+        // spaced out islands can't fill every cell
+        let crowded = GenerationOptions {
+            target_islands: Some(25),
+            ..GenerationOptions::default()
+        };
+        assert_eq!(
+            HashiGrid::generate_with_options(5, 5, 0, &crowded),
+            Err(HashiError::IslandTarget { target: 25 })
+        );
     }
 
-    // ============================================================================
-    // GRID GENERATION TESTS (Deterministic Seeding)
-    // ============================================================================
+    #[test]
+    fn test_generation_rejects_guessing() {
+        // Test: Difficulties below hard retry until the puzzle can be solved without guessing
+        for seed in 0..10 {
+            let grid = HashiGrid::generate_with_options(8, 16, seed, &Difficulty::Medium.options())
+                .unwrap();
+            assert!(!grid.requires_guessing(), "seed {}", seed);
+        }
+    }
 
     #[test]
-    fn test_generate_with_seed_produces_same_grid() {
-        // Test: Same seed always produces identical grid
-        let seed = 12345;
-        let grid1 = HashiGrid::generate_with_seed(10, 10, seed).unwrap();
-        let grid2 = HashiGrid::generate_with_seed(10, 10, seed).unwrap();
+    fn test_generation_varies_equal_clues() {
+        // Test: Varying clues leaves fewer neighbors with the same clue, with every puzzle still solved by its bridges
+        let plain = Difficulty::Medium.options();
+        let varied = GenerationOptions {
+            vary_clues: true,
+            ..plain
+        };
+        let (mut before, mut after) = (0, 0);
+        for seed in 0..10 {
+            let grid = HashiGrid::generate_with_options(10, 10, seed, &varied).unwrap();
+            assert!(grid.is_complete(), "seed {}", seed);
+            assert!(!grid.requires_guessing(), "seed {}", seed);
+            assert!(
+                grid.islands
+                    .values()
+                    .all(|island| island.required_bridges <= varied.max_degree)
+            );
+            after += grid.equal_neighbor_count();
+            before += HashiGrid::generate_with_options(10, 10, seed, &plain)
+                .unwrap()
+                .equal_neighbor_count();
+        }
+        assert!(
+            after < before,
+            "{} equal pairs, {} without varying",
+            after,
+            before
+        );
+    }
 
-        assert_eq!(grid1.islands, grid2.islands);
-        assert_eq!(grid1.bridges, grid2.bridges);
+    #[test]
+    fn test_bounding_box_of_islands() {
+        // Test: The box reaches from the leftmost and topmost island to the rightmost and lowest
+        let at = |x, y| Position { x, y };
+        let grid =
+            HashiGrid::from_layout(9, 9, &[(at(2, 5), 1), (at(6, 5), 2), (at(6, 1), 1)]).unwrap();
+        assert_eq!(grid.bounding_box_of_islands(), Some((at(2, 1), at(6, 5))));
+        assert!(!grid.is_full());
+        assert_eq!(
+            HashiGrid::new(4, 4).unwrap().bounding_box_of_islands(),
+            None
+        );
     }
 
     #[test]
-    fn test_generate_with_seed_different_seeds_differ() {
-        // Test: Different seeds produce different grids (with very high probability)
-        let grid1 = HashiGrid::generate_with_seed(10, 10, 12345).unwrap();
-        let grid2 = HashiGrid::generate_with_seed(10, 10, 54321).unwrap();
+    fn test_generate_logic_solvable() {
+        // Test: Strict generation only gives puzzles that need no guessing or given bridges, the same
+        // one for the same seed, at the difficulty asked for
+        let options = Difficulty::Beginner.options();
+        for seed in 0..10 {
+            let grid = HashiGrid::generate_logic_solvable(10, 10, seed, &options, 20).unwrap();
+            assert!(!grid.requires_guessing(), "seed {}", seed);
+            assert!(grid.locked_bridges.is_empty(), "seed {}", seed);
+            // beginner puzzles are singles only
+            assert!(!grid.bridges.is_empty());
+            assert!(grid.bridges.values().all(|&b| b == BridgeType::Single));
+            assert_eq!(
+                grid,
+                HashiGrid::generate_logic_solvable(10, 10, seed, &options, 20).unwrap()
+            );
+        }
+        assert_eq!(
+            HashiGrid::generate_logic_solvable(10, 10, 0, &options, 0),
+            Err(HashiError::NoLogicalPuzzle { attempts: 0 })
+        );
+        assert_ne!(attempt_seed(7, 0), attempt_seed(7, 1));
+    }
 
-        // Grids should differ (checking islands as main content)
-        assert_ne!(grid1.islands, grid2.islands);
+    #[test]
+    fn test_forced_islands_from_clues() {
+        // Test: A square of 2s forces every single, a square of 3s leaves each side open until one is placed
+        let mut twos = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 2),
+                (Position { x: 2, y: 0 }, 2),
+                (Position { x: 0, y: 2 }, 2),
+                (Position { x: 2, y: 2 }, 2),
+            ],
+        )
+        .unwrap();
+        assert_eq!(twos.forced_islands().len(), 4);
+        for line in twos.candidate_bridges() {
+            twos.add_bridge(line).unwrap();
+        }
+        assert!(twos.forced_islands().is_empty());
+
+        let mut threes = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 3),
+                (Position { x: 2, y: 0 }, 3),
+                (Position { x: 0, y: 2 }, 3),
+                (Position { x: 2, y: 2 }, 3),
+            ],
+        )
+        .unwrap();
+        assert!(threes.forced_islands().is_empty());
+
+        let top = BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 2, y: 0 }).unwrap();
+        let left = BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 0, y: 2 }).unwrap();
+        threes.add_bridge(top).unwrap();
+        threes.add_bridge(top).unwrap();
+        assert_eq!(threes.forced_bridges().get(&left), Some(&1));
+        assert_eq!(threes.forced_islands().len(), 4);
     }
 
     #[test]
-    fn test_generate_with_seed_creates_islands() {
-        // Test: Generated grid should contain islands
-        let grid = HashiGrid::generate_with_seed(10, 10, 42).unwrap();
-        assert!(!grid.islands.is_empty());
-        assert!(grid.islands.len() >= 8); // Minimum from code: (width*height)/5 or 8
+    fn test_forced_islands_ignores_wrong_bridges() {
+        // Test: A bridge no solution contains is set aside, and its island is pointed out as needing a change
+        let mut grid = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 2),
+                (Position { x: 2, y: 0 }, 2),
+                (Position { x: 0, y: 2 }, 2),
+                (Position { x: 2, y: 2 }, 2),
+            ],
+        )
+        .unwrap();
+        let top = BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 2, y: 0 }).unwrap();
+        grid.add_bridge(top).unwrap();
+        grid.add_bridge(top).unwrap();
+
+        assert_eq!(grid.forced_bridges().get(&top), Some(&1));
+        assert!(grid.forced_islands().contains(&Position { x: 0, y: 0 }));
     }
 
     #[test]
-    fn test_generate_with_seed_creates_bridges() {
-        // Test: Generated grid should contain bridges
-        let grid = HashiGrid::generate_with_seed(10, 10, 42).unwrap();
-        assert!(!grid.bridges.is_empty());
+    fn test_degree_and_island_at() {
+        // Test: Degree counts a double as two and a single as one, and island_at only finds islands
+        let mut grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[
+                (Position { x: 0, y: 0 }, 3),
+                (Position { x: 3, y: 0 }, 2),
+                (Position { x: 0, y: 3 }, 1),
+            ],
+        )
+        .unwrap();
+        let right = BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 3, y: 0 }).unwrap();
+        let down = BridgeLine::new(Position { x: 0, y: 0 }, Position { x: 0, y: 3 }).unwrap();
+        grid.add_bridge(right).unwrap();
+        grid.add_bridge(right).unwrap();
+        grid.add_bridge(down).unwrap();
+
+        assert_eq!(grid.degree(Position { x: 0, y: 0 }), 3);
+        assert_eq!(grid.degree(Position { x: 3, y: 0 }), 2);
+        assert_eq!(grid.degree(Position { x: 4, y: 4 }), 0);
+        assert_eq!(
+            grid.island_at(Position { x: 0, y: 3 }),
+            Some(&Island {
+                required_bridges: 1
+            })
+        );
+        assert_eq!(grid.island_at(Position { x: 1, y: 1 }), None);
     }
 
     #[test]
-    fn test_generate_with_seed_respects_grid_bounds() {
-        // Test: All islands and bridges should be within grid bounds
-        let grid = HashiGrid::generate_with_seed(10, 10, 42).unwrap();
+    fn test_count_solutions_unsolvable() {
+        // Test: Mismatched clues have no solution
+        let grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[(Position { x: 1, y: 2 }, 1), (Position { x: 4, y: 2 }, 2)],
+        )
+        .unwrap();
+        assert_eq!(grid.count_solutions(10), 0);
+        assert_eq!(grid.solve(), None);
+    }
 
-        for pos in grid.islands.keys() {
-            assert!(pos.x < grid.width);
-            assert!(pos.y < grid.height);
-        }
+    #[test]
+    fn test_count_solutions_crossing_excluded() {
+        // Test: Islands whose only possible bridges cross each other have no solution
+        // (1,0)-(1,2) and (0,1)-(2,1) would cross at (1,1)
+        let grid = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 1, y: 0 }, 1),
+                (Position { x: 1, y: 2 }, 1),
+                (Position { x: 0, y: 1 }, 1),
+                (Position { x: 2, y: 1 }, 1),
+            ],
+        )
+        .unwrap();
+        assert_eq!(grid.count_solutions(10), 0);
+    }
 
-        for bridge in grid.bridges.keys() {
-            assert!(bridge.start.x < grid.width);
-            assert!(bridge.start.y < grid.height);
-            assert!(bridge.end.x < grid.width);
-            assert!(bridge.end.y < grid.height);
+    #[test]
+    fn test_solve_generated_puzzles() {
+        // Test: The solver finds a complete layout for generated puzzles
+        for seed in 0..10 {
+            let mut grid = HashiGrid::generate_with_seed(8, 16, seed)
+                .unwrap()
+                .wipe_bridges();
+            let solution = grid.solve().unwrap();
+            for a in solution.keys() {
+                for b in solution.keys() {
+                    assert_eq!(a.intersects(b), None, "seed {}", seed);
+                }
+            }
+            grid.bridges = solution;
+            assert!(grid.is_complete(), "seed {}", seed);
+            assert_eq!(grid.components().len(), 1, "seed {}", seed);
         }
     }
 
     #[test]
-    fn test_generate_with_seed_small_grid() {
-        // Test: Generation works on minimum viable grid size
-        let grid = HashiGrid::generate_with_seed(3, 3, 42).unwrap();
-        assert!(!grid.islands.is_empty());
+    fn test_solve_within_gives_up() {
+        // Test: A search that runs out of steps says so, rather than reporting there's no solution
+        let threes = HashiGrid::from_layout(
+            3,
+            3,
+            &[
+                (Position { x: 0, y: 0 }, 3),
+                (Position { x: 2, y: 0 }, 3),
+                (Position { x: 0, y: 2 }, 3),
+                (Position { x: 2, y: 2 }, 3),
+            ],
+        )
+        .unwrap();
+        let odd = HashiGrid::from_layout(
+            3,
+            1,
+            &[(Position { x: 0, y: 0 }, 1), (Position { x: 2, y: 0 }, 2)],
+        )
+        .unwrap();
+
+        assert_eq!(threes.solve_within(1), Err(HashiError::SolveBudget));
+        assert_eq!(threes.solve_within(1_000), Ok(threes.solve()));
+        assert!(threes.solve().is_some());
+        assert_eq!(odd.solve_within(1_000), Ok(None));
     }
 
     // ============================================================================
@@ -1684,4 +4586,78 @@ mod tests {
 
         assert!(grid.is_full());
     }
+
+    // ============================================================================
+    // SERIALIZATION TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_grid_serde_round_trip() {
+        // Test: A generated grid, bridges included, survives being written to JSON and read back
+        for seed in 0..5 {
+            let grid = HashiGrid::generate_with_seed(8, 16, seed).unwrap();
+            let json = serde_json::to_string(&grid).unwrap();
+            assert_eq!(serde_json::from_str::<HashiGrid>(&json).unwrap(), grid);
+        }
+    }
+
+    #[test]
+    fn test_toroidal_grid_serde_round_trip() {
+        // Test: A toroidal grid keeps its variant and which of its bridges wrap
+        let options = GenerationOptions {
+            variant: Variant::Toroidal,
+            ..GenerationOptions::default()
+        };
+        let grid = HashiGrid::generate_with_options(8, 8, 3, &options).unwrap();
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<HashiGrid>(&json).unwrap(), grid);
+
+        let line = BridgeLine::wrapping(Position { x: 0, y: 2 }, Position { x: 0, y: 6 }).unwrap();
+        assert_eq!(
+            serde_json::to_value(line).unwrap(),
+            serde_json::json!([{ "x": 0, "y": 2 }, { "x": 0, "y": 6 }, true])
+        );
+    }
+
+    #[test]
+    fn test_grid_serde_format() {
+        // Test: Islands and bridges are stored as lists of pairs, with each bridge as just its two ends
+        let mut grid = HashiGrid::from_layout(
+            5,
+            5,
+            &[(Position { x: 1, y: 2 }, 1), (Position { x: 4, y: 2 }, 1)],
+        )
+        .unwrap();
+        grid.add_bridge(BridgeLine::new(Position { x: 4, y: 2 }, Position { x: 1, y: 2 }).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&grid).unwrap(),
+            serde_json::json!({
+                "width": 5,
+                "height": 5,
+                "islands": [
+                    [{ "x": 1, "y": 2 }, { "required_bridges": 1 }],
+                    [{ "x": 4, "y": 2 }, { "required_bridges": 1 }]
+                ],
+                "bridges": [[[{ "x": 1, "y": 2 }, { "x": 4, "y": 2 }], "Single"]]
+            })
+        );
+    }
+
+    #[test]
+    fn test_bridge_line_deserialize_validates() {
+        // Test: Stored lines are rebuilt through BridgeLine::new, so ends are reordered and diagonals rejected
+        let line: BridgeLine = serde_json::from_str(r#"[{"x":4,"y":2},{"x":1,"y":2}]"#).unwrap();
+        assert_eq!(
+            line,
+            BridgeLine::new(Position { x: 1, y: 2 }, Position { x: 4, y: 2 }).unwrap()
+        );
+
+        assert!(serde_json::from_str::<BridgeLine>(r#"[{"x":1,"y":1},{"x":4,"y":2}]"#).is_err());
+        assert!(
+            serde_json::from_str::<BridgeLine>(r#"[{"x":1,"y":1},{"x":4,"y":2},true]"#).is_err()
+        );
+        assert!(serde_json::from_str::<BridgeLine>(r#"[{"x":1,"y":1},{"x":1,"y":1}]"#).is_err());
+    }
 }
@@ -0,0 +1,312 @@
+//! Bit-packed puzzle codes, far shorter than `to_code` on big boards
+
+use super::{BridgeDirection, BridgeType, Direction, HashiError, HashiGrid, Position};
+
+/// Starts every compact code, text codes always start with a digit
+pub(super) const COMPACT_PREFIX: char = '~';
+
+/// First byte of the packed data, so the layout can change without breaking old links
+const COMPACT_VERSION: u8 = 1;
+
+/// Longest number `read_number` accepts, in bits, more than any board can need
+const MAX_NUMBER_BITS: u32 = 32;
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Bits written most significant first into bytes, the last byte padded with zeros
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    used: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        for bit in (0..bits).rev() {
+            if self.used.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> bit & 1 == 1 {
+                // safe unwrap, a byte was pushed above whenever the last one filled up
+                *self.bytes.last_mut().unwrap() |= 0x80 >> (self.used % 8);
+            }
+            self.used += 1;
+        }
+    }
+
+    /// Exp-Golomb code for any number, so small gaps and counts take only a few bits:
+    /// n + 1 written in binary, after as many zeros as it has bits past the first
+    fn write_number(&mut self, n: u32) {
+        let n = n as u64 + 1;
+        let bits = 64 - n.leading_zeros();
+        self.write(0, bits - 1);
+        for bit in (0..bits).rev() {
+            self.write((n >> bit & 1) as u32, 1);
+        }
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    read: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, bits: u32) -> Result<u32, HashiError> {
+        let mut value = 0;
+        for _ in 0..bits {
+            let byte = self
+                .bytes
+                .get(self.read / 8)
+                .ok_or(HashiError::InvalidCode)?;
+            value = value << 1 | (byte >> (7 - self.read % 8) & 1) as u32;
+            self.read += 1;
+        }
+        Ok(value)
+    }
+
+    fn read_number(&mut self) -> Result<u32, HashiError> {
+        let mut zeros = 0;
+        while self.read(1)? == 0 {
+            zeros += 1;
+            if zeros >= MAX_NUMBER_BITS {
+                return Err(HashiError::InvalidCode);
+            }
+        }
+        let n = (1u64 << zeros) | self.read(zeros)? as u64;
+        u32::try_from(n - 1).map_err(|_| HashiError::InvalidCode)
+    }
+}
+
+fn to_base64url(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (n, &byte)| {
+            group | (byte as u32) << (16 - 8 * n)
+        });
+        // a chunk of n bytes needs n + 1 characters, no padding
+        for n in 0..=chunk.len() {
+            text.push(BASE64URL[(group >> (18 - 6 * n) & 63) as usize] as char);
+        }
+    }
+    text
+}
+
+fn from_base64url(text: &str) -> Result<Vec<u8>, HashiError> {
+    let values = text
+        .bytes()
+        .map(|c| BASE64URL.iter().position(|&b| b == c).map(|v| v as u32))
+        .collect::<Option<Vec<u32>>>()
+        .ok_or(HashiError::InvalidCode)?;
+    let mut bytes = Vec::new();
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(HashiError::InvalidCode);
+        }
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (n, &value)| group | value << (18 - 6 * n));
+        for n in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * n)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+impl HashiGrid {
+    /// A shorter code than `to_code` for the same puzzle, `~` then base64url of packed bits:
+    /// a version byte, the width and height, then the number of islands and for each the count
+    /// of empty cells since the last (row by row) and its clue less one in 3 bits. Last come the
+    /// locked bridges, their count and for each the island it starts from, counting islands
+    /// row by row, with a bit each for running down and for being a double
+    pub fn to_compact_code(&self) -> String {
+        let mut bits = BitWriter::default();
+        bits.write(COMPACT_VERSION as u32, 8);
+        bits.write(self.width as u32, 8);
+        bits.write(self.height as u32, 8);
+
+        let mut islands = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if let Some(island) = self.islands.get(&Position { x, y }) {
+                    islands.push((y as u32 * self.width as u32 + x as u32, island));
+                }
+            }
+        }
+        bits.write_number(islands.len() as u32);
+        let mut next_cell = 0;
+        for (cell, island) in &islands {
+            bits.write_number(cell - next_cell);
+            bits.write(island.required_bridges.clamp(1, 8) as u32 - 1, 3);
+            next_cell = cell + 1;
+        }
+
+        bits.write_number(self.locked_bridges.len() as u32);
+        for line in &self.locked_bridges {
+            let cell = line.start.y as u32 * self.width as u32 + line.start.x as u32;
+            let island = islands.iter().position(|(c, _)| *c == cell).unwrap_or(0);
+            bits.write_number(island as u32);
+            bits.write((line.direction == BridgeDirection::Down) as u32, 1);
+            bits.write(
+                (self.bridges.get(line) == Some(&BridgeType::Double)) as u32,
+                1,
+            );
+        }
+
+        format!("{}{}", COMPACT_PREFIX, to_base64url(&bits.bytes))
+    }
+
+    /// Whichever of `to_code` and `to_compact_code` is shorter, for links
+    pub fn share_code(&self) -> String {
+        let code = self.to_code();
+        let compact = self.to_compact_code();
+        if compact.len() < code.len() {
+            compact
+        } else {
+            code
+        }
+    }
+
+    /// Read a puzzle back from `to_compact_code`, without the `~`
+    pub(super) fn from_compact_code(text: &str) -> Result<Self, HashiError> {
+        let bytes = from_base64url(text)?;
+        let mut bits = BitReader {
+            bytes: &bytes,
+            read: 0,
+        };
+        if bits.read(8)? != COMPACT_VERSION as u32 {
+            return Err(HashiError::InvalidCode);
+        }
+        let width = bits.read(8)? as u8;
+        let height = bits.read(8)? as u8;
+        let mut grid = Self::new(width, height)?;
+        let cells = width as u64 * height as u64;
+
+        let island_count = bits.read_number()? as u64;
+        if island_count > cells {
+            return Err(HashiError::InvalidCode);
+        }
+        let mut starts = Vec::new();
+        let mut next_cell = 0u64;
+        for _ in 0..island_count {
+            let cell = next_cell + bits.read_number()? as u64;
+            if cell >= cells {
+                return Err(HashiError::InvalidCode);
+            }
+            let position = Position {
+                x: (cell % width as u64) as u8,
+                y: (cell / width as u64) as u8,
+            };
+            grid.set_clue(position, bits.read(3)? as u8 + 1)?;
+            starts.push(position);
+            next_cell = cell + 1;
+        }
+
+        let locked_count = bits.read_number()?;
+        if locked_count as u64 > 2 * island_count {
+            return Err(HashiError::InvalidCode);
+        }
+        for _ in 0..locked_count {
+            let start = *starts
+                .get(bits.read_number()? as usize)
+                .ok_or(HashiError::InvalidCode)?;
+            let direction = match bits.read(1)? {
+                0 => Direction::Right,
+                _ => Direction::Down,
+            };
+            let strands = bits.read(1)? as u8 + 1;
+            grid.add_locked_bridge(start, &direction, strands)?;
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashi::GenerationOptions;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_base64url_round_trip() {
+        // Test: Bytes of every length come back the same, and characters outside the alphabet are refused
+        for length in 0..10 {
+            let bytes: Vec<u8> = (0..length).map(|n| (n * 37 + 200) as u8).collect();
+            assert_eq!(from_base64url(&to_base64url(&bytes)), Ok(bytes));
+        }
+        assert_eq!(to_base64url(b"hashi"), "aGFzaGk");
+        assert!(from_base64url("aG=z").is_err());
+        assert!(from_base64url("aGFza").is_err());
+    }
+
+    #[test]
+    fn test_compact_code_round_trip() {
+        // Test: Generated puzzles, with some of their bridges locked, read back exactly from their compact codes
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        for seed in 0..20 {
+            let width = rng.random_range(5..=25);
+            let height = rng.random_range(5..=25);
+            let solved = HashiGrid::generate_with_options(
+                width,
+                height,
+                seed,
+                &GenerationOptions::default(),
+            )
+            .unwrap();
+            let mut grid = solved.clone().wipe_bridges();
+            // bridges given with the puzzle are locked already
+            let unlocked = solved
+                .bridges
+                .iter()
+                .filter(|(line, _)| !solved.locked_bridges.contains(line));
+            for (line, bridge_type) in unlocked.step_by(3) {
+                let strands = if *bridge_type == BridgeType::Double {
+                    2
+                } else {
+                    1
+                };
+                for _ in 0..strands {
+                    grid.add_bridge(*line).unwrap();
+                }
+                grid.lock_bridge(*line).unwrap();
+            }
+
+            let code = grid.to_compact_code();
+            assert!(code.starts_with(COMPACT_PREFIX));
+            assert_eq!(HashiGrid::from_code(&code).unwrap(), grid, "seed {}", seed);
+            assert!(grid.share_code().len() <= grid.to_code().len());
+        }
+    }
+
+    #[test]
+    fn test_compact_code_is_shorter_on_big_boards() {
+        // Test: A large puzzle's compact code is well under its text code
+        let grid = HashiGrid::generate_with_seed(30, 30, 1)
+            .unwrap()
+            .wipe_bridges();
+        assert!(grid.to_compact_code().len() * 3 < grid.to_code().len() * 2);
+        assert_eq!(grid.share_code(), grid.to_compact_code());
+    }
+
+    #[test]
+    fn test_compact_code_never_panics() {
+        // Test: Random bytes and random text after the prefix are refused or read, never a panic
+        let mut rng = rand::rngs::StdRng::seed_from_u64(9);
+        for _ in 0..2000 {
+            let length = rng.random_range(0..40);
+            let mut bytes: Vec<u8> = (0..length).map(|_| rng.random()).collect();
+            if rng.random_bool(0.5)
+                && let Some(version) = bytes.first_mut()
+            {
+                *version = COMPACT_VERSION;
+            }
+            let _ = HashiGrid::from_code(&format!("{}{}", COMPACT_PREFIX, to_base64url(&bytes)));
+
+            let text: String = (0..length)
+                .map(|_| rng.random_range(b'!'..=b'~') as char)
+                .collect();
+            let _ = HashiGrid::from_code(&format!("{}{}", COMPACT_PREFIX, text));
+        }
+    }
+}
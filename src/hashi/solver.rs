@@ -0,0 +1,426 @@
+use super::{BridgeLine, BridgeType, HashiGrid, Position};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+/// A potential bridge between two islands that can see each other
+struct Candidate {
+    line: BridgeLine,
+    a: usize,
+    b: usize,
+}
+
+/// Lower and upper bound on the number of bridges (0..=2) each candidate can carry
+#[derive(Clone)]
+struct Bounds {
+    lo: Vec<u8>,
+    hi: Vec<u8>,
+}
+
+/// Constraint propagation + backtracking solver over the candidate bridges of a grid.
+/// Only the islands, their clues and the locked bridges every solution keeps are considered,
+/// any other bridges already on the grid are ignored.
+pub(super) struct Solver {
+    clues: Vec<u8>,
+    /// Bridges that came with the puzzle, the least each of their lines can carry
+    locked: BTreeMap<BridgeLine, BridgeType>,
+    candidates: Vec<Candidate>,
+    /// island index -> indices of candidates touching it
+    incident: Vec<Vec<usize>>,
+    /// candidate index -> indices of candidates it would cross
+    crossings: Vec<Vec<usize>>,
+    /// Steps the search may still take, unlimited unless set by `within`
+    steps_left: Cell<usize>,
+}
+
+/// How many bridges a bridge type stands for
+fn strands(bridge_type: &BridgeType) -> u8 {
+    match bridge_type {
+        BridgeType::Single => 1,
+        BridgeType::Double => 2,
+    }
+}
+
+impl Solver {
+    pub(super) fn new(grid: &HashiGrid) -> Self {
+        let positions: Vec<Position> = grid.islands.keys().copied().collect();
+        let index_of = |pos: Position| positions.binary_search(&pos).unwrap(); // safe unwrap, positions are the island keys
+        let clues = grid.islands.values().map(|i| i.required_bridges).collect();
+
+        let candidates: Vec<Candidate> = grid
+            .candidate_bridges()
+            .into_iter()
+            .map(|line| Candidate {
+                line,
+                a: index_of(line.start),
+                b: index_of(line.end),
+            })
+            .collect();
+
+        let mut incident = vec![Vec::new(); positions.len()];
+        for (c, candidate) in candidates.iter().enumerate() {
+            incident[candidate.a].push(c);
+            incident[candidate.b].push(c);
+        }
+
+        let crossings = candidates
+            .iter()
+            .map(|candidate| {
+                candidates
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| candidate.line.intersects(&other.line).is_some())
+                    .map(|(c, _)| c)
+                    .collect()
+            })
+            .collect();
+
+        let locked = grid
+            .bridges
+            .iter()
+            .filter(|(line, _)| grid.locked_bridges.contains(line))
+            .map(|(line, bridge_type)| (*line, *bridge_type))
+            .collect();
+
+        Self {
+            clues,
+            locked,
+            candidates,
+            incident,
+            crossings,
+            steps_left: Cell::new(usize::MAX),
+        }
+    }
+
+    /// Limit the search to `steps` steps, after which it stops as if nothing more were found
+    pub(super) fn within(self, steps: usize) -> Self {
+        self.steps_left.set(steps);
+        self
+    }
+
+    /// Whether the search stopped early because its steps ran out
+    pub(super) fn ran_out(&self) -> bool {
+        self.steps_left.get() == 0
+    }
+
+    /// Starting bounds: the clue bounds with each locked bridge as its line's minimum. A locked
+    /// bridge over what its line can take is left for `propagate` to find there's no solution
+    fn initial_bounds(&self) -> Bounds {
+        let mut bounds = self.clue_bounds();
+        for (c, candidate) in self.candidates.iter().enumerate() {
+            if let Some(bridge_type) = self.locked.get(&candidate.line) {
+                bounds.lo[c] = strands(bridge_type);
+            }
+        }
+        bounds
+    }
+
+    /// Bounds from the clues alone, with the isolation rules applied: two 1s (or a double between
+    /// two 2s) would close off a pair of islands from the rest of the network
+    fn clue_bounds(&self) -> Bounds {
+        let mut hi = vec![2; self.candidates.len()];
+        if self.clues.len() > 2 {
+            for (c, candidate) in self.candidates.iter().enumerate() {
+                match (self.clues[candidate.a], self.clues[candidate.b]) {
+                    (1, 1) => hi[c] = 0,
+                    (2, 2) => hi[c] = 1,
+                    _ => {}
+                }
+            }
+        }
+
+        Bounds {
+            lo: vec![0; self.candidates.len()],
+            hi,
+        }
+    }
+
+    /// Tighten the bounds until nothing changes. Returns false if the bounds can no longer lead to a solution
+    fn propagate(&self, bounds: &mut Bounds) -> bool {
+        self.propagate_counting(bounds, &mut 0)
+    }
+
+    /// `propagate`, counting the passes over the puzzle it took
+    fn propagate_counting(&self, bounds: &mut Bounds, passes: &mut usize) -> bool {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            *passes += 1;
+
+            // every island needs exactly its clue's worth of bridges
+            for (island, incident) in self.incident.iter().enumerate() {
+                let need = self.clues[island] as i16;
+                let sum_lo: i16 = incident.iter().map(|&c| bounds.lo[c] as i16).sum();
+                let sum_hi: i16 = incident.iter().map(|&c| bounds.hi[c] as i16).sum();
+                if sum_lo > need || sum_hi < need {
+                    return false;
+                }
+
+                for &c in incident {
+                    let others_lo = sum_lo - bounds.lo[c] as i16;
+                    let others_hi = sum_hi - bounds.hi[c] as i16;
+                    let lo = (bounds.lo[c] as i16).max(need - others_hi);
+                    let hi = (bounds.hi[c] as i16).min(need - others_lo);
+                    if lo > hi {
+                        return false;
+                    }
+                    if lo as u8 != bounds.lo[c] || hi as u8 != bounds.hi[c] {
+                        bounds.lo[c] = lo as u8;
+                        bounds.hi[c] = hi as u8;
+                        changed = true;
+                    }
+                }
+            }
+
+            // a placed bridge rules out everything crossing it
+            for (c, crossings) in self.crossings.iter().enumerate() {
+                if bounds.lo[c] == 0 {
+                    continue;
+                }
+                for &x in crossings {
+                    if bounds.lo[x] > 0 {
+                        return false;
+                    }
+                    if bounds.hi[x] > 0 {
+                        bounds.hi[x] = 0;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        self.can_connect(bounds)
+    }
+
+    /// Connectivity pruning: every island must still be reachable over candidates that may carry a bridge,
+    /// and no group of islands may already be closed off from the rest
+    fn can_connect(&self, bounds: &Bounds) -> bool {
+        let island_count = self.clues.len();
+        if island_count == 0 {
+            return true;
+        }
+
+        let reachable = self.component_labels(|c| bounds.hi[c] > 0);
+        if reachable.iter().any(|&label| label != 0) {
+            return false;
+        }
+
+        // a closed component is one where every bridge is already certain
+        let mut settled = vec![true; island_count];
+        for (c, candidate) in self.candidates.iter().enumerate() {
+            if bounds.lo[c] != bounds.hi[c] {
+                settled[candidate.a] = false;
+                settled[candidate.b] = false;
+            }
+        }
+        let labels = self.component_labels(|c| bounds.lo[c] > 0);
+        if labels.iter().all(|&label| label == 0) {
+            return true;
+        }
+        let mut open = vec![false; island_count];
+        for (island, &label) in labels.iter().enumerate() {
+            if !settled[island] {
+                open[label] = true;
+            }
+        }
+        labels.iter().all(|&label| open[label])
+    }
+
+    /// Label each island with the smallest island index in its component, using only the given edges
+    fn component_labels(&self, edge: impl Fn(usize) -> bool) -> Vec<usize> {
+        let mut labels: Vec<usize> = (0..self.clues.len()).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (c, candidate) in self.candidates.iter().enumerate() {
+                if !edge(c) {
+                    continue;
+                }
+                let label = labels[candidate.a].min(labels[candidate.b]);
+                if labels[candidate.a] != label || labels[candidate.b] != label {
+                    labels[candidate.a] = label;
+                    labels[candidate.b] = label;
+                    changed = true;
+                }
+            }
+        }
+        labels
+    }
+
+    /// Depth first search over the candidates. `found` is called for each solution and returns whether to keep going.
+    fn search(&self, mut bounds: Bounds, found: &mut dyn FnMut(&Bounds) -> bool) -> bool {
+        let Some(steps_left) = self.steps_left.get().checked_sub(1) else {
+            return false;
+        };
+        self.steps_left.set(steps_left);
+        if !self.propagate(&mut bounds) {
+            return true;
+        }
+
+        // branch on the least open candidate
+        let next = (0..self.candidates.len())
+            .filter(|&c| bounds.lo[c] < bounds.hi[c])
+            .min_by_key(|&c| bounds.hi[c] - bounds.lo[c]);
+
+        let Some(c) = next else {
+            return found(&bounds);
+        };
+
+        for value in (bounds.lo[c]..=bounds.hi[c]).rev() {
+            let mut branch = bounds.clone();
+            branch.lo[c] = value;
+            branch.hi[c] = value;
+            if !self.search(branch, found) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn to_bridges(&self, bounds: &Bounds) -> BTreeMap<BridgeLine, BridgeType> {
+        self.candidates
+            .iter()
+            .zip(&bounds.lo)
+            .filter_map(|(candidate, &count)| match count {
+                1 => Some((candidate.line, BridgeType::Single)),
+                2 => Some((candidate.line, BridgeType::Double)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub(super) fn solve(&self) -> Option<BTreeMap<BridgeLine, BridgeType>> {
+        let mut solution = None;
+        self.search(self.initial_bounds(), &mut |bounds| {
+            solution = Some(self.to_bridges(bounds));
+            false
+        });
+        solution
+    }
+
+    /// Starting bounds with the placed bridges as minimums, or `None` if some line already has more than it can take
+    fn bounds_with(&self, placed: &BTreeMap<BridgeLine, BridgeType>) -> Option<Bounds> {
+        let mut bounds = self.initial_bounds();
+        for (c, candidate) in self.candidates.iter().enumerate() {
+            let Some(count) = placed.get(&candidate.line).map(strands) else {
+                continue;
+            };
+            if count > bounds.hi[c] {
+                return None;
+            }
+            bounds.lo[c] = bounds.lo[c].max(count);
+        }
+        Some(bounds)
+    }
+
+    /// Bridge counts that propagation alone pins down, without any guessing. The placed bridges are
+    /// built on only while some solution still contains them all, otherwise it starts from the clues.
+    pub(super) fn forced(
+        &self,
+        placed: &BTreeMap<BridgeLine, BridgeType>,
+    ) -> BTreeMap<BridgeLine, u8> {
+        let mut bounds = self
+            .bounds_with(placed)
+            .filter(|bounds| {
+                let mut solvable = false;
+                self.search(bounds.clone(), &mut |_| {
+                    solvable = true;
+                    false
+                });
+                solvable
+            })
+            .unwrap_or_else(|| self.initial_bounds());
+        if !self.propagate(&mut bounds) {
+            return BTreeMap::new();
+        }
+
+        self.candidates
+            .iter()
+            .enumerate()
+            .filter(|&(c, _)| bounds.lo[c] == bounds.hi[c])
+            .map(|(c, candidate)| (candidate.line, bounds.lo[c]))
+            .collect()
+    }
+
+    /// Whether some solution has at least `count` bridges along the line
+    pub(super) fn allows(&self, line: BridgeLine, count: u8) -> bool {
+        let Some(c) = self
+            .candidates
+            .iter()
+            .position(|candidate| candidate.line == line)
+        else {
+            return false;
+        };
+        let mut bounds = self.initial_bounds();
+        if count > bounds.hi[c] {
+            return false;
+        }
+        bounds.lo[c] = bounds.lo[c].max(count);
+
+        let mut found = false;
+        self.search(bounds, &mut |_| {
+            found = true;
+            false
+        });
+        found
+    }
+
+    /// Bridge counts each island's clue forces on its own, without following any chain of reasoning
+    /// between islands: a 4 with two neighbours needs a double to each, say
+    pub(super) fn opening(&self) -> BTreeMap<BridgeLine, u8> {
+        let bounds = self.initial_bounds();
+        let mut lo = vec![0; self.candidates.len()];
+        for (island, incident) in self.incident.iter().enumerate() {
+            let sum_hi: u8 = incident.iter().map(|&c| bounds.hi[c]).sum();
+            for &c in incident {
+                let others_hi = sum_hi - bounds.hi[c];
+                lo[c] = lo[c].max(self.clues[island].saturating_sub(others_hi));
+            }
+        }
+
+        self.candidates
+            .iter()
+            .zip(lo)
+            .filter(|&(_, count)| count > 0)
+            .map(|(candidate, count)| (candidate.line, count.min(2)))
+            .collect()
+    }
+
+    /// How many passes propagation makes from the clues before it stops, a measure of how long
+    /// the chains of reasoning in the puzzle are
+    pub(super) fn deduction_passes(&self) -> usize {
+        let mut passes = 0;
+        self.propagate_counting(&mut self.initial_bounds(), &mut passes);
+        passes
+    }
+
+    /// Lines propagation from the clues leaves undecided, where solving would need a guess
+    pub(super) fn undecided(&self) -> Vec<BridgeLine> {
+        let mut bounds = self.initial_bounds();
+        self.propagate(&mut bounds);
+        self.candidates
+            .iter()
+            .enumerate()
+            .filter(|&(c, _)| bounds.lo[c] != bounds.hi[c])
+            .map(|(_, candidate)| candidate.line)
+            .collect()
+    }
+
+    /// Whether propagation alone leaves some line undecided, so solving needs a guess somewhere
+    pub(super) fn requires_guessing(&self) -> bool {
+        let mut bounds = self.initial_bounds();
+        !self.propagate(&mut bounds) || bounds.lo != bounds.hi
+    }
+
+    pub(super) fn count_solutions(&self, cap: usize) -> usize {
+        let mut count = 0;
+        if cap == 0 {
+            return count;
+        }
+        self.search(self.initial_bounds(), &mut |_| {
+            count += 1;
+            count < cap
+        });
+        count
+    }
+}
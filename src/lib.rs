@@ -0,0 +1,3 @@
+//! The puzzle logic, kept free of any browser code so it can be tested natively
+
+pub mod hashi;
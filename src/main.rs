@@ -1,9 +1,13 @@
+use ::hashi::hashi;
 use std::str;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
 mod components;
-mod hashi;
+mod score;
+mod settings;
+mod sound;
+mod storage;
 
 fn main() {
     yew::Renderer::<App>::new().render();
@@ -15,8 +19,31 @@ enum Route {
     Home,
     #[at("/game/:width/:height/:id")]
     Game { width: u8, height: u8, id: u64 },
+    #[at("/torus/:width/:height/:id")]
+    Torus { width: u8, height: u8, id: u64 },
+    #[at("/replay/:width/:height/:id")]
+    Replay { width: u8, height: u8, id: u64 },
+    #[at("/print/:width/:height/:count/:seed")]
+    Print {
+        width: u8,
+        height: u8,
+        count: u8,
+        seed: u64,
+    },
+    #[at("/gallery/:width/:height/:seed")]
+    Gallery { width: u8, height: u8, seed: u64 },
+    #[at("/create")]
+    Create,
+    #[at("/custom/:code")]
+    Custom { code: String },
+    #[at("/daily")]
+    Daily,
     #[at("/rules")]
     Rules,
+    #[at("/settings")]
+    Settings,
+    #[at("/tutorial")]
+    Tutorial,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -32,7 +59,40 @@ fn switch(routes: Route) -> Html {
         Route::Game { width, height, id } => {
             html! { <components::game::Game width={width} height={height} puzzle_id={id} /> }
         }
+        Route::Torus { width, height, id } => html! {
+            <components::game::Game
+                width={width}
+                height={height}
+                puzzle_id={id}
+                variant={hashi::Variant::Toroidal}
+            />
+        },
+        Route::Replay { width, height, id } => {
+            html! { <components::replay::Replay width={width} height={height} puzzle_id={id} /> }
+        }
+        Route::Print {
+            width,
+            height,
+            count,
+            seed,
+        } => html! {
+            <components::print::PrintSheet width={width} height={height} count={count} seed={seed} />
+        },
+        Route::Gallery {
+            width,
+            height,
+            seed,
+        } => html! {
+            <components::gallery::Gallery width={width} height={height} seed={seed} />
+        },
+        Route::Create => html! { <components::create::CreatePuzzle /> },
+        Route::Custom { code } => html! {
+            <components::create::CustomPuzzle key={code.as_str()} code={code.clone()} />
+        },
+        Route::Daily => html! { <components::daily::Daily /> },
         Route::Rules => html! { <components::rules::Rules /> },
+        Route::Settings => html! { <components::settings::SettingsPage /> },
+        Route::Tutorial => html! { <components::tutorial::Tutorial /> },
         Route::NotFound => html! { <components::not_found::NotFound /> },
     }
 }
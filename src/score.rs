@@ -0,0 +1,176 @@
+use crate::storage::save_quietly;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const BEST_SCORES_KEY: &str = "hashi.best_scores";
+const PUZZLE_TIMES_KEY: &str = "hashi.puzzle_times";
+
+/// Most puzzles `PuzzleTimes` keeps, the longest since solved are dropped past this
+const MAX_PUZZLE_TIMES: usize = 200;
+
+/// Score for a solve that used exactly the solution's bridges at par pace
+const BASE_SCORE: f64 = 1000.0;
+
+/// Time allowed per solution bridge before the score starts dropping
+const PAR_SECONDS_PER_BRIDGE: f64 = 5.0;
+
+/// Lowest the time factor goes, so a slow but tidy solve still beats a fast messy one
+const MIN_TIME_FACTOR: f64 = 0.5;
+
+/// Single number summarising a solve: the fewer moves beyond the solution's bridges and the closer
+/// to par time, the higher. `elapsed_seconds` is `None` in zen mode, where time doesn't count.
+pub fn efficiency_score(
+    solution_moves: u32,
+    actual_moves: u32,
+    elapsed_seconds: Option<u32>,
+) -> u32 {
+    if solution_moves == 0 {
+        return 0;
+    }
+
+    let solution = solution_moves as f64;
+    // undoing and redoing bridges can only add moves, but guard the ratio anyway
+    let move_factor = solution / (actual_moves as f64).max(solution);
+    let time_factor = match elapsed_seconds {
+        Some(seconds) => {
+            let par = solution * PAR_SECONDS_PER_BRIDGE;
+            (par / (seconds as f64).max(1.0)).clamp(MIN_TIME_FACTOR, 1.0)
+        }
+        None => 1.0,
+    };
+
+    (BASE_SCORE * move_factor * time_factor).round() as u32
+}
+
+/// Highest score reached on each board size, persisted in localStorage
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BestScores {
+    /// Keyed by size as "WxH", JSON object keys have to be strings
+    scores: BTreeMap<String, u32>,
+}
+
+impl BestScores {
+    pub fn load() -> Self {
+        LocalStorage::get(BEST_SCORES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_quietly(BEST_SCORES_KEY, self);
+    }
+
+    pub fn best(&self, width: u8, height: u8) -> Option<u32> {
+        self.scores.get(&format!("{}x{}", width, height)).copied()
+    }
+
+    /// Keep the score if it beats the best for its size, returning whether it did
+    pub fn record(&mut self, width: u8, height: u8, score: u32) -> bool {
+        let best = self
+            .scores
+            .entry(format!("{}x{}", width, height))
+            .or_insert(0);
+        if score > *best {
+            *best = score;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Fastest time on each particular puzzle, so a shared link can be raced against an earlier solve
+/// of it. Persisted in localStorage, the most recently solved last
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PuzzleTimes {
+    /// Puzzle key and best time in seconds
+    times: Vec<(String, u32)>,
+}
+
+impl PuzzleTimes {
+    pub fn load() -> Self {
+        LocalStorage::get(PUZZLE_TIMES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_quietly(PUZZLE_TIMES_KEY, self);
+    }
+
+    pub fn best(&self, puzzle: &str) -> Option<u32> {
+        self.times
+            .iter()
+            .find(|(key, _)| key == puzzle)
+            .map(|&(_, seconds)| seconds)
+    }
+
+    /// Keep the time if it beats the best for the puzzle, returning whether it did. The puzzle
+    /// becomes the most recently solved either way, and the longest since solved are dropped
+    /// once there are more than `MAX_PUZZLE_TIMES`
+    pub fn record(&mut self, puzzle: &str, seconds: u32) -> bool {
+        let previous = self
+            .times
+            .iter()
+            .position(|(key, _)| key == puzzle)
+            .map(|n| self.times.remove(n).1);
+        let beaten = previous.is_none_or(|best| seconds < best);
+        let best = previous.filter(|_| !beaten).unwrap_or(seconds);
+        self.times.push((puzzle.to_string(), best));
+
+        let excess = self.times.len().saturating_sub(MAX_PUZZLE_TIMES);
+        self.times.drain(..excess);
+        beaten
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perfect_solve_scores_base() {
+        // Test: Placing exactly the solution's bridges within par time scores the full base
+        assert_eq!(efficiency_score(10, 10, Some(30)), 1000);
+        assert_eq!(efficiency_score(10, 10, None), 1000);
+    }
+
+    #[test]
+    fn test_extra_moves_and_time_lower_score() {
+        // Test: Twice the moves halves the score, slow solves lose up to half, and zen mode ignores time
+        assert_eq!(efficiency_score(10, 20, Some(50)), 500);
+        assert_eq!(efficiency_score(10, 10, Some(100)), 500);
+        assert_eq!(efficiency_score(10, 10, Some(10_000)), 500);
+        assert_eq!(efficiency_score(10, 20, None), 500);
+        assert_eq!(efficiency_score(0, 0, Some(1)), 0);
+    }
+
+    #[test]
+    fn test_best_scores_per_size() {
+        // Test: Only a higher score replaces the best, and sizes are kept apart
+        let mut scores = BestScores::default();
+        assert!(scores.record(5, 10, 600));
+        assert!(!scores.record(5, 10, 400));
+        assert!(scores.record(5, 10, 800));
+        assert_eq!(scores.best(5, 10), Some(800));
+        assert_eq!(scores.best(10, 5), None);
+    }
+
+    #[test]
+    fn test_puzzle_times_keep_best_and_recent() {
+        // Test: Only a faster time replaces a puzzle's best, and the puzzle solved longest ago goes first
+        let mut times = PuzzleTimes::default();
+        assert!(times.record("a", 90));
+        assert!(!times.record("a", 120));
+        assert!(times.record("a", 60));
+        assert_eq!(times.best("a"), Some(60));
+        assert_eq!(times.best("b"), None);
+
+        for n in 0..MAX_PUZZLE_TIMES {
+            times.record(&n.to_string(), 30);
+        }
+        assert_eq!(times.best("a"), None);
+        // solving a puzzle again, even slower, keeps it
+        times.record("0", 45);
+        times.record("new", 30);
+        assert_eq!(times.best("0"), Some(30));
+        assert_eq!(times.best("1"), None);
+    }
+}
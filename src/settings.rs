@@ -0,0 +1,234 @@
+use crate::hashi::{Difficulty, GenAlgo, GenerationOptions};
+use crate::storage::save_quietly;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_KEY: &str = "hashi.settings";
+
+/// How single and double bridges are told apart on the board
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeStyle {
+    /// Every strand the same width, doubles drawn close together
+    #[default]
+    Standard,
+    /// Bold singles and thin, well separated doubles, easier to tell apart on dense boards
+    Distinct,
+    /// Doubles spread apart with a bar across each end and "×2" between their strands, for
+    /// players still learning to tell them from singles
+    Split,
+}
+
+impl BridgeStyle {
+    pub const ALL: [BridgeStyle; 3] = [
+        BridgeStyle::Standard,
+        BridgeStyle::Distinct,
+        BridgeStyle::Split,
+    ];
+}
+
+impl std::fmt::Display for BridgeStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BridgeStyle::Standard => "Standard",
+            BridgeStyle::Distinct => "Distinct",
+            BridgeStyle::Split => "Split doubles",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Settings besides difficulty and variant that change which puzzle a seed generates. Links to a
+/// generated puzzle carry them, so the link is the same puzzle whoever opens it
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SeedSettings {
+    pub generator: GenAlgo,
+    pub symmetric: bool,
+    pub vary_clues: bool,
+}
+
+impl SeedSettings {
+    /// Options for generating a puzzle at the given difficulty with these settings
+    pub fn options(self, difficulty: Difficulty) -> GenerationOptions {
+        GenerationOptions {
+            algorithm: self.generator,
+            symmetric: self.symmetric,
+            vary_clues: self.vary_clues,
+            ..difficulty.options()
+        }
+    }
+}
+
+/// Player preferences, persisted in localStorage
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Use colorblind-safe hues and extra glyphs instead of relying on red/green
+    pub colorblind: bool,
+    /// Dark board and page colors for low-light play
+    pub dark_mode: bool,
+    /// Difficulty used for new random puzzles
+    pub difficulty: Difficulty,
+    /// Show the running timer while playing
+    pub show_timer: bool,
+    /// Board size pre-filled in the home screen's custom size inputs
+    pub board_width: u8,
+    pub board_height: u8,
+    /// Whether the player has been through the tutorial, which is otherwise shown on their first visit
+    pub seen_tutorial: bool,
+    /// Play short sounds for bridge placement, invalid moves and victory
+    pub sound_effects: bool,
+    pub bridge_style: BridgeStyle,
+    /// Show how many more bridges each island needs beside its clue
+    pub show_remaining: bool,
+    /// Tap the gap between two islands to step its bridge through single, double and none,
+    /// instead of tapping both islands
+    pub tap_to_cycle: bool,
+    /// Draw faint grid lines with row and column numbers, for describing positions
+    pub show_grid: bool,
+    /// Algorithm used for new random puzzles, for comparing how each one plays
+    pub generator: GenAlgo,
+    /// Lay new random puzzles out with half-turn symmetry, like many published ones
+    pub symmetric: bool,
+    /// Vary the clues of new random puzzles so fewer islands in a line share the same number
+    pub vary_clues: bool,
+    /// Lock islands once their clue is met, so stray taps can't build more bridges onto them
+    pub lock_satisfied: bool,
+    /// Start puzzles with the bridges their clues force at a glance already built. Solves started
+    /// this way don't count towards best scores.
+    pub fill_opening: bool,
+    /// Only remove a bridge on a double click or tap, so a stray click in fast play can't take one away
+    pub double_click_remove: bool,
+    /// Warn straight away when a bridge is built that no solution has, off for those who'd
+    /// rather find their own mistakes
+    pub warn_wrong_moves: bool,
+    /// Start the timer with the first bridge built rather than as the puzzle appears, so time
+    /// spent studying the board first isn't counted
+    pub timer_on_first_move: bool,
+    /// Draw islands without their clues, for a screenshot that leaves the numbers to whoever it's
+    /// sent to. Only the drawing changes, the clues still decide when an island is satisfied
+    pub hide_clues: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            colorblind: false,
+            dark_mode: false,
+            difficulty: Difficulty::default(),
+            show_timer: true,
+            board_width: 6,
+            board_height: 12,
+            seen_tutorial: false,
+            sound_effects: false,
+            bridge_style: BridgeStyle::default(),
+            show_remaining: false,
+            tap_to_cycle: false,
+            show_grid: false,
+            generator: GenAlgo::default(),
+            symmetric: false,
+            vary_clues: false,
+            lock_satisfied: false,
+            fill_opening: false,
+            double_click_remove: false,
+            warn_wrong_moves: false,
+            timer_on_first_move: false,
+            hide_clues: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Read the stored settings, falling back to defaults if nothing valid is stored
+    pub fn load() -> Self {
+        LocalStorage::get(SETTINGS_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_quietly(SETTINGS_KEY, self);
+    }
+
+    /// The chosen generator settings, for new random puzzles and the links that start them
+    pub fn seed_settings(&self) -> SeedSettings {
+        SeedSettings {
+            generator: self.generator,
+            symmetric: self.symmetric,
+            vary_clues: self.vary_clues,
+        }
+    }
+
+    /// Options for generating a puzzle at the given difficulty with the chosen generator
+    pub fn generation_options(&self, difficulty: Difficulty) -> GenerationOptions {
+        self.seed_settings().options(difficulty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_older_settings_keep_new_defaults() {
+        // Test: Settings saved before a field existed still load, with that field at its default
+        let settings: Settings = serde_json::from_str(r#"{"colorblind":true}"#).unwrap();
+
+        assert!(settings.colorblind);
+        assert!(settings.show_timer);
+        assert_eq!(settings.difficulty, Difficulty::Medium);
+        assert_eq!((settings.board_width, settings.board_height), (6, 12));
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        // Test: Every setting survives being stored and read back
+        let settings = Settings {
+            dark_mode: true,
+            difficulty: Difficulty::Hard,
+            show_timer: false,
+            board_width: 10,
+            board_height: 20,
+            bridge_style: BridgeStyle::Distinct,
+            show_remaining: true,
+            tap_to_cycle: true,
+            generator: GenAlgo::SpanningTree,
+            symmetric: true,
+            vary_clues: true,
+            lock_satisfied: true,
+            fill_opening: true,
+            double_click_remove: true,
+            warn_wrong_moves: true,
+            timer_on_first_move: true,
+            hide_clues: true,
+            ..Settings::default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+
+        assert_eq!(serde_json::from_str::<Settings>(&json).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_seed_settings_options() {
+        // Test: The player's generator settings start out standard, and changed ones reach the options
+        assert_eq!(Settings::default().seed_settings(), SeedSettings::default());
+
+        let spanning = SeedSettings {
+            generator: GenAlgo::SpanningTree,
+            ..SeedSettings::default()
+        };
+        assert_eq!(
+            spanning.options(Difficulty::Hard).algorithm,
+            GenAlgo::SpanningTree
+        );
+
+        let symmetric = SeedSettings {
+            symmetric: true,
+            ..spanning
+        };
+        assert!(symmetric.options(Difficulty::Hard).symmetric);
+
+        let varied = SeedSettings {
+            vary_clues: true,
+            ..SeedSettings::default()
+        };
+        assert!(varied.options(Difficulty::Hard).vary_clues);
+    }
+}
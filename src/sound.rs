@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use web_sys::wasm_bindgen::JsValue;
+use web_sys::{AudioContext, OscillatorType};
+
+/// Feedback sounds, synthesised on the fly so there are no audio files to load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    /// A bridge was placed
+    Place,
+    /// A bridge could not be placed, plays alongside the island shudder
+    Invalid,
+    /// The puzzle was completed
+    Victory,
+}
+
+/// A single note making up a sound
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Tone {
+    frequency: f32,
+    /// Seconds after the sound starts that this note begins
+    delay: f64,
+    seconds: f64,
+    volume: f32,
+    wave: OscillatorType,
+}
+
+impl Sound {
+    fn tones(self) -> Vec<Tone> {
+        match self {
+            Sound::Place => vec![Tone {
+                frequency: 660.0,
+                delay: 0.0,
+                seconds: 0.05,
+                volume: 0.2,
+                wave: OscillatorType::Sine,
+            }],
+            // as long as the 300ms shudder, so the two finish together
+            Sound::Invalid => vec![Tone {
+                frequency: 110.0,
+                delay: 0.0,
+                seconds: 0.3,
+                volume: 0.15,
+                wave: OscillatorType::Square,
+            }],
+            // rising C major arpeggio
+            Sound::Victory => [523.25, 659.25, 783.99, 1046.5]
+                .into_iter()
+                .enumerate()
+                .map(|(i, frequency)| Tone {
+                    frequency,
+                    delay: i as f64 * 0.12,
+                    seconds: 0.4,
+                    volume: 0.2,
+                    wave: OscillatorType::Triangle,
+                })
+                .collect(),
+        }
+    }
+}
+
+thread_local! {
+    // created on the first sound, which comes from a click, so browsers' autoplay rules allow it
+    static CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// Play a sound, silently doing nothing if the browser has no Web Audio support
+pub fn play(sound: Sound) {
+    CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        if context.is_none() {
+            *context = AudioContext::new().ok();
+        }
+        let Some(context) = context.as_ref() else {
+            return;
+        };
+
+        // a context created outside a user gesture starts suspended
+        let _ = context.resume();
+        let start = context.current_time();
+        for tone in sound.tones() {
+            let _ = schedule(context, &tone, start);
+        }
+    });
+}
+
+fn schedule(context: &AudioContext, tone: &Tone, start: f64) -> Result<(), JsValue> {
+    let at = start + tone.delay;
+
+    let oscillator = context.create_oscillator()?;
+    oscillator.set_type(tone.wave);
+    oscillator.frequency().set_value(tone.frequency);
+
+    // fade out rather than stopping dead, which clicks
+    let gain = context.create_gain()?;
+    gain.gain().set_value_at_time(tone.volume, at)?;
+    gain.gain()
+        .exponential_ramp_to_value_at_time(0.001, at + tone.seconds)?;
+
+    oscillator.connect_with_audio_node(&gain)?;
+    gain.connect_with_audio_node(&context.destination())?;
+    oscillator.start_with_when(at)?;
+    oscillator.stop_with_when(at + tone.seconds)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_sound_matches_shudder() {
+        // Test: The invalid move buzz lasts as long as the 300ms island shudder
+        let tones = Sound::Invalid.tones();
+        let end = tones
+            .iter()
+            .map(|tone| tone.delay + tone.seconds)
+            .fold(0.0, f64::max);
+        assert_eq!(end, 0.3);
+    }
+
+    #[test]
+    fn test_sounds_are_short() {
+        // Test: Feedback sounds stay brief so they never pile up during fast play
+        for sound in [Sound::Place, Sound::Invalid, Sound::Victory] {
+            for tone in sound.tones() {
+                assert!(tone.delay + tone.seconds <= 1.0, "{:?}", sound);
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::Serialize;
+
+/// Save a value to localStorage, ignoring failure. Storage can be unavailable (private browsing,
+/// quota), and then the value just won't persist past this visit, which is no reason to stop play
+pub fn save_quietly<T: Serialize>(key: &str, value: &T) {
+    let _ = LocalStorage::set(key, value);
+}
@@ -0,0 +1,81 @@
+use hashi::hashi::{Difficulty, GenAlgo, GenerationOptions, HashiGrid};
+
+/// The board sizes offered on the home screen
+const SIZES: [(u8, u8); 2] = [(5, 10), (8, 16)];
+
+/// Fixed so a failure names a seed that reproduces it
+const SEEDS: std::ops::Range<u64> = 0..25;
+
+#[test]
+fn test_generated_puzzles_are_solvable() {
+    // Test: The solver finds a solution meeting every generated clue, for each algorithm, difficulty and shipped size
+    for (algorithm, difficulty) in GenAlgo::ALL
+        .into_iter()
+        .flat_map(|algorithm| Difficulty::ALL.map(|difficulty| (algorithm, difficulty)))
+    {
+        let options = GenerationOptions {
+            algorithm,
+            ..difficulty.options()
+        };
+        for (width, height) in SIZES {
+            for seed in SEEDS {
+                let generated =
+                    HashiGrid::generate_with_options(width, height, seed, &options).unwrap();
+                let context = format!(
+                    "{} {} {}x{} seed {}",
+                    algorithm, difficulty, width, height, seed
+                );
+                assert!(
+                    generated.is_complete(),
+                    "{}: generator's own bridges",
+                    context
+                );
+                assert!(
+                    generated
+                        .islands
+                        .values()
+                        .all(|island| island.required_bridges > 0),
+                    "{}: island without a bridge",
+                    context
+                );
+
+                let mut grid = generated.wipe_bridges();
+                let Some(solution) = grid.solve() else {
+                    panic!("{}: no solution found", context);
+                };
+                grid.bridges = solution;
+                assert!(grid.is_complete(), "{}: solver's bridges", context);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_islands_spread_evenly_over_the_board() {
+    // Test: No quarter of the board gets noticeably more islands than the others, for each algorithm
+    for algorithm in GenAlgo::ALL {
+        let options = GenerationOptions {
+            algorithm,
+            ..GenerationOptions::default()
+        };
+        let mut quarters = [0u32; 4];
+        // islands within one puzzle go together, so it takes a few hundred to even out
+        for seed in 0..400 {
+            let generated = HashiGrid::generate_with_options(10, 10, seed, &options).unwrap();
+            for position in generated.islands.keys() {
+                let quarter = (position.x >= 5) as usize + 2 * (position.y >= 5) as usize;
+                quarters[quarter] += 1;
+            }
+        }
+
+        let mean = quarters.iter().sum::<u32>() as f64 / 4.0;
+        for count in quarters {
+            assert!(
+                (count as f64 - mean).abs() < mean * 0.1,
+                "{}: islands per quarter {:?}",
+                algorithm,
+                quarters
+            );
+        }
+    }
+}